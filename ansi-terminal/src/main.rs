@@ -50,6 +50,8 @@ fn main() {
                 initial_rng_seed,
                 omniscient,
                 new_game,
+                terrain_config,
+                maps,
             },
         col_encode_choice,
     } = Args::parser().with_help_default().parse_env_or_exit();
@@ -67,6 +69,8 @@ fn main() {
         initial_rng_seed: InitialRngSeed::U64(initial_rng_seed),
         omniscient,
         new_game,
+        terrain_config,
+        maps,
     });
     use ColEncodeChoice as C;
     match col_encode_choice {
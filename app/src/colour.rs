@@ -49,3 +49,47 @@ pub const CROWBAR_SHAFT: Rgba32 = Rgba32::new_rgb(185, 63, 63);
 pub const CROWBAR_TIP: Rgba32 = Rgba32::new_rgb(127, 127, 127);
 pub const DITCH_FOREGROUND: Rgba32 = Rgba32::hex_rgb(0x372405);
 pub const DITCH_BACKGROUND: Rgba32 = Rgba32::hex_rgb(0x291b04);
+pub const LOG_BARK: Rgba32 = Rgba32::hex_rgb(0x4a3a1c);
+pub const LOG_GRAIN: Rgba32 = Rgba32::hex_rgb(0x8b602c);
+pub const LIGHTNING: Rgba32 = Rgba32::new_rgb(230, 230, 255);
+pub const AXE_HEAD: Rgba32 = Rgba32::new_grey(200);
+pub const AXE_HANDLE: Rgba32 = Rgba32::hex_rgb(0x8b602c);
+pub const MAP_FALLEN_LOG: Rgba32 = Rgba32::hex_rgb(0xb07a3a);
+pub const MUD_BACKGROUND: Rgba32 = Rgba32::hex_rgb(0x3b2a14);
+pub const MUD_FOREGROUND: Rgba32 = Rgba32::hex_rgb(0x6b5030);
+pub const MAP_MUD: Rgba32 = Rgba32::hex_rgb(0x6b5030);
+pub const PUDDLE_BACKGROUND: Rgba32 = Rgba32::hex_rgb(0x1a3550);
+pub const PUDDLE_FOREGROUND: Rgba32 = Rgba32::hex_rgb(0x4a7aa0);
+pub const FOOTPRINT: Rgba32 = Rgba32::hex_rgb(0x1c1208);
+pub const BOAT_HULL: Rgba32 = Rgba32::hex_rgb(0x9c5a2a);
+pub const BOAT_OAR: Rgba32 = Rgba32::hex_rgb(0xc8a060);
+pub const CAIRN: Rgba32 = Rgba32::new_grey(150);
+pub const FISHING_ROD: Rgba32 = Rgba32::hex_rgb(0xa0703a);
+pub const FISHING_LINE: Rgba32 = Rgba32::new_grey(220);
+pub const STOVE: Rgba32 = Rgba32::new_grey(90);
+pub const STOVE_FIRE: Rgba32 = Rgba32::hex_rgb(0xe0601a);
+pub const MUSHROOM_CAP: Rgba32 = Rgba32::hex_rgb(0xc8553d);
+pub const MUSHROOM_STEM: Rgba32 = Rgba32::hex_rgb(0xe8dcc0);
+pub const KITCHEN_BENCH: Rgba32 = Rgba32::hex_rgb(0xb08850);
+pub const KITCHEN_BENCH_TOP: Rgba32 = Rgba32::hex_rgb(0xd8c8a0);
+pub const TRAIL_BACKGROUND: Rgba32 = Rgba32::hex_rgb(0x5a4a32);
+pub const TRAIL_FOREGROUND: Rgba32 = Rgba32::hex_rgb(0x8a7452);
+pub const MAP_TRAIL: Rgba32 = Rgba32::hex_rgb(0x7a4a1a);
+pub const SIGNPOST: Rgba32 = Rgba32::hex_rgb(0xa07840);
+pub const STREAM_BACKGROUND: Rgba32 = Rgba32::hex_rgb(0x123a5a);
+pub const STREAM_FOREGROUND: Rgba32 = Rgba32::hex_rgb(0x4f8ab8);
+pub const FORD_STONE: Rgba32 = Rgba32::new_grey(140);
+pub const LOOKOUT_BACKGROUND: Rgba32 = Rgba32::hex_rgb(0x6a4a2a);
+pub const LOOKOUT_FOREGROUND: Rgba32 = Rgba32::hex_rgb(0xa07a4a);
+pub const TOWER_LEG: Rgba32 = Rgba32::hex_rgb(0x7a5a34);
+pub const MAP_LOOKOUT: Rgba32 = Rgba32::hex_rgb(0xffd060);
+pub const TABLE: Rgba32 = Rgba32::hex_rgb(0x8a5a30);
+pub const BOOKSHELF: Rgba32 = Rgba32::hex_rgb(0x6a4020);
+pub const BOOK_SPINES: [Rgba32; 3] = [
+    Rgba32::hex_rgb(0xa03030),
+    Rgba32::hex_rgb(0x30609a),
+    Rgba32::hex_rgb(0x50803a),
+];
+pub const LADDER: Rgba32 = Rgba32::hex_rgb(0xb08a58);
+pub const HATCH: Rgba32 = Rgba32::new_grey(16);
+pub const STAIRS: Rgba32 = Rgba32::hex_rgb(0x9c7446);
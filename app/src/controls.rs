@@ -19,6 +19,8 @@ pub enum AppInput {
     Lantern,
     Pushing,
     Dig,
+    Chop,
+    Fish,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -59,6 +61,8 @@ impl Default for Controls {
             KeyboardInput::Char('f') => AppInput::Lantern,
             KeyboardInput::Char('p') => AppInput::Pushing,
             KeyboardInput::Char('e') => AppInput::Dig,
+            KeyboardInput::Char('c') => AppInput::Chop,
+            KeyboardInput::Char('F') => AppInput::Fish,
         ];
         let gamepad = btreemap![
             GamepadButton::DPadLeft => AppInput::Direction(CardinalDirection::West),
@@ -0,0 +1,350 @@
+use rainforest_game::TerrainConfig;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The game is won on the morning of the sixth day
+const TRIP_DAYS: u32 = 5;
+
+/// How many past results are listed on the daily forest screen
+pub const RECENT_RESULTS: usize = 5;
+
+/// A day in the proleptic Gregorian calendar, counted in UTC so that everyone gets the same daily
+/// forest at the same time
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Date {
+    days_since_epoch: i64,
+}
+
+#[cfg(not(feature = "web"))]
+fn days_since_epoch_now() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| (duration.as_secs() / 86400) as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(feature = "web")]
+fn days_since_epoch_now() -> i64 {
+    (js_sys::Date::now() / 86_400_000.0).floor() as i64
+}
+
+impl Date {
+    pub fn today() -> Self {
+        Self {
+            days_since_epoch: days_since_epoch_now(),
+        }
+    }
+
+    fn previous(self) -> Self {
+        Self {
+            days_since_epoch: self.days_since_epoch - 1,
+        }
+    }
+
+    /// Year, month and day of month, converted with the algorithm from
+    /// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    fn ymd(self) -> (i64, u32, u32) {
+        let z = self.days_since_epoch + 719468;
+        let era = z.div_euclid(146097);
+        let day_of_era = z.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year, month, day)
+    }
+
+    /// The seed of this day's forest. Like random seeds it is kept below 2^32 so its seed code is
+    /// at most four words long.
+    pub fn seed(self) -> u64 {
+        // splitmix64, so that consecutive days get unrelated seeds
+        let mut z = (self.days_since_epoch as u64).wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        (z ^ (z >> 31)) & 0xffff_ffff
+    }
+
+    pub fn difficulty(self) -> Difficulty {
+        Difficulty::ALL[(self.seed() >> 8) as usize % Difficulty::ALL.len()]
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+/// Each day's forest is generated with one of a few fixed sets of world generation parameters,
+/// ignoring any the player has configured, so the challenge is the same for everyone
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Gentle,
+    Soggy,
+    Wild,
+}
+
+impl Difficulty {
+    const ALL: &'static [Self] = &[Self::Gentle, Self::Soggy, Self::Wild];
+
+    pub fn terrain_config(self) -> TerrainConfig {
+        match self {
+            Self::Gentle => TerrainConfig {
+                num_extra_lamps: 5,
+                num_streams: 2,
+                equipment_cabin_distance: 40,
+                equipment_spread: 15,
+                ..TerrainConfig::default()
+            },
+            Self::Soggy => TerrainConfig::default(),
+            Self::Wild => TerrainConfig {
+                tree_chance_scale: 0.4,
+                num_rocks: 300,
+                num_extra_lamps: 1,
+                num_streams: 5,
+                ..TerrainConfig::default()
+            },
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string = match self {
+            Self::Gentle => "Gentle",
+            Self::Soggy => "Soggy",
+            Self::Wild => "Wild",
+        };
+        write!(f, "{}", string)
+    }
+}
+
+/// A game of a daily forest
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct DailyAttempt {
+    pub date: Date,
+    /// Only the first attempt at each day's forest goes in the history
+    pub first: bool,
+}
+
+/// How a daily forest went
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub date: Date,
+    pub survived: bool,
+    pub motivation: i32,
+    pub days: u32,
+    /// The game is still being played, or was abandoned. Pending results count as going home.
+    #[serde(default)]
+    pub pending: bool,
+}
+
+impl DailyResult {
+    /// `day` is the day the game ended on, where the first night is day 0
+    pub fn new(date: Date, survived: bool, motivation: i32, day: u32) -> Self {
+        Self {
+            date,
+            survived,
+            motivation: motivation.max(0),
+            days: day.min(TRIP_DAYS),
+            pending: false,
+        }
+    }
+
+    /// Stands in for the result of a game that has just started, until it ends
+    pub fn pending(date: Date) -> Self {
+        Self {
+            pending: true,
+            ..Self::new(date, false, 0, 0)
+        }
+    }
+
+    pub fn outcome(&self) -> &'static str {
+        if self.pending {
+            "Unfinished"
+        } else if self.survived {
+            "Survived"
+        } else {
+            "Went home"
+        }
+    }
+}
+
+impl fmt::Display for DailyResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.pending {
+            return write!(f, "{}", self.outcome());
+        }
+        write!(
+            f,
+            "{} after {} of {} days, motivation {}",
+            self.outcome(),
+            self.days,
+            TRIP_DAYS,
+            self.motivation
+        )
+    }
+}
+
+/// The first result of each daily forest played, oldest first
+#[derive(Default, Serialize, Deserialize)]
+pub struct DailyHistory {
+    results: Vec<DailyResult>,
+}
+
+impl DailyHistory {
+    pub fn result(&self, date: Date) -> Option<&DailyResult> {
+        self.results.iter().find(|result| result.date == date)
+    }
+
+    /// Only the first attempt at each day's forest counts. It's recorded as pending when it
+    /// starts, and the pending result is replaced when it ends. Returns whether the result was
+    /// kept.
+    pub fn record(&mut self, result: DailyResult) -> bool {
+        match self
+            .results
+            .iter_mut()
+            .find(|other| other.date == result.date)
+        {
+            Some(other) if other.pending && !result.pending => {
+                *other = result;
+                true
+            }
+            Some(_) => false,
+            None => {
+                self.results.push(result);
+                self.results.sort_by_key(|result| result.date);
+                true
+            }
+        }
+    }
+
+    pub fn recent(&self) -> impl Iterator<Item = &DailyResult> {
+        self.results.iter().rev().take(RECENT_RESULTS)
+    }
+
+    /// Consecutive days survived ending today, or yesterday if today's forest is yet to be
+    /// finished
+    pub fn current_streak(&self, today: Date) -> u32 {
+        let mut date = match self.result(today) {
+            Some(result) if !result.pending => today,
+            _ => today.previous(),
+        };
+        let mut streak = 0;
+        while let Some(true) = self.result(date).map(|result| result.survived) {
+            streak += 1;
+            date = date.previous();
+        }
+        streak
+    }
+
+    pub fn best_streak(&self) -> u32 {
+        let mut best = 0;
+        let mut streak = 0;
+        let mut previous: Option<Date> = None;
+        for result in &self.results {
+            if !result.survived {
+                streak = 0;
+            } else if previous == Some(result.date.previous()) && streak > 0 {
+                streak += 1;
+            } else {
+                streak = 1;
+            }
+            previous = Some(result.date);
+            best = best.max(streak);
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn date(days_since_epoch: i64) -> Date {
+        Date { days_since_epoch }
+    }
+
+    fn history(results: &[(i64, bool)]) -> DailyHistory {
+        let mut history = DailyHistory::default();
+        for &(day, survived) in results {
+            history.record(DailyResult::new(date(day), survived, 10, TRIP_DAYS));
+        }
+        history
+    }
+
+    #[test]
+    fn ymd() {
+        assert_eq!(date(0).ymd(), (1970, 1, 1));
+        assert_eq!(date(-1).ymd(), (1969, 12, 31));
+        assert_eq!(date(11016).ymd(), (2000, 2, 29));
+        assert_eq!(date(11017).ymd(), (2000, 3, 1));
+        assert_eq!(date(19722).ymd(), (2023, 12, 31));
+        assert_eq!(date(19723).ymd(), (2024, 1, 1));
+        assert_eq!(date(19753).ymd(), (2024, 1, 31));
+        assert_eq!(date(19754).ymd(), (2024, 2, 1));
+        // 2100 isn't a leap year
+        assert_eq!(date(47540).ymd(), (2100, 2, 28));
+        assert_eq!(date(47541).ymd(), (2100, 3, 1));
+        assert_eq!(date(19723).to_string(), "2024-01-01");
+    }
+
+    #[test]
+    fn current_streak() {
+        // the last days of 2023 and the first of 2024
+        let history = history(&[(19719, true), (19720, false), (19721, true), (19722, true)]);
+        assert_eq!(history.current_streak(date(19722)), 2);
+        // today is yet to be played
+        assert_eq!(history.current_streak(date(19723)), 2);
+        // a day was missed
+        assert_eq!(history.current_streak(date(19724)), 0);
+        let mut history = history;
+        history.record(DailyResult::new(date(19723), true, 10, TRIP_DAYS));
+        assert_eq!(history.current_streak(date(19723)), 3);
+        assert_eq!(history.current_streak(date(19724)), 3);
+        history.record(DailyResult::new(date(19724), false, 0, 2));
+        assert_eq!(history.current_streak(date(19724)), 0);
+    }
+
+    #[test]
+    fn best_streak() {
+        assert_eq!(history(&[]).best_streak(), 0);
+        assert_eq!(history(&[(10, false)]).best_streak(), 0);
+        // across the end of January
+        let mut history = history(&[
+            (19750, true),
+            (19751, true),
+            (19752, true),
+            (19753, false),
+            (19754, true),
+            (19756, true),
+            (19757, true),
+        ]);
+        assert_eq!(history.best_streak(), 3);
+        history.record(DailyResult::new(date(19755), true, 10, TRIP_DAYS));
+        assert_eq!(history.best_streak(), 4);
+    }
+
+    #[test]
+    fn pending_attempts() {
+        let mut history = history(&[(100, true)]);
+        assert!(history.record(DailyResult::pending(date(101))));
+        // a second attempt while the first is in progress isn't recorded
+        assert!(!history.record(DailyResult::pending(date(101))));
+        assert_eq!(history.current_streak(date(101)), 1);
+        assert_eq!(history.result(date(101)).unwrap().to_string(), "Unfinished");
+        assert!(history.record(DailyResult::new(date(101), true, 10, TRIP_DAYS)));
+        assert!(!history.record(DailyResult::new(date(101), false, 0, 1)));
+        assert!(history.result(date(101)).unwrap().survived);
+        assert_eq!(history.current_streak(date(101)), 2);
+        // an abandoned attempt breaks the streak
+        history.record(DailyResult::pending(date(102)));
+        assert_eq!(history.current_streak(date(103)), 0);
+        assert_eq!(history.best_streak(), 2);
+    }
+}
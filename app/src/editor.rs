@@ -0,0 +1,389 @@
+use crate::{
+    colour,
+    controls::{AppInput, Controls},
+    examine,
+    fields::{GroundField, LogField, TeaField},
+    game_loop::{GAME_VIEW_OFFSET, GAME_VIEW_SIZE},
+    mist::Mist,
+};
+use gridbugs::chargrid::{prelude::*, text::StyledString};
+use rainforest_game::{witness, Config as GameConfig, CustomMap, Spawn, Time};
+use rand::SeedableRng;
+use rand_isaac::Isaac64Rng;
+
+/// Size of the map the editor starts with when there's no saved map
+const NEW_MAP_SIZE: Size = Size::new_u16(60, 40);
+const NEW_MAP_NAME: &str = "My Map";
+
+/// The preview is rebuilt from the same seed after every edit so that the parts of the map that
+/// haven't changed look the same
+const PREVIEW_SEED: u64 = 0;
+
+/// How far the cursor moves with the quick movement keys
+const CURSOR_JUMP: i32 = 5;
+
+/// The view scrolls to keep the cursor at least this far from its edge
+const SCROLL_MARGIN: i32 = 3;
+
+const MAX_BRUSH_RADIUS: u32 = 8;
+
+/// What pressing space (or clicking) does to the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Brush {
+    Spawn(Spawn),
+    Raise,
+    Lower,
+    Erase,
+    Player,
+    Prefab(&'static str),
+}
+
+impl Brush {
+    fn all() -> Vec<Self> {
+        let mut brushes = vec![Self::Player, Self::Raise, Self::Lower, Self::Erase];
+        brushes.extend(CustomMap::prefab_names().into_iter().map(Self::Prefab));
+        brushes.extend(Spawn::ALL.iter().copied().map(Self::Spawn));
+        brushes
+    }
+
+    /// Brushes that place a single thing ignore the brush radius
+    fn uses_radius(self) -> bool {
+        !matches!(self, Self::Player | Self::Prefab(_))
+    }
+
+    fn name(self) -> String {
+        match self {
+            Self::Spawn(spawn) => spawn.name().replace('_', " "),
+            Self::Raise => "raise ground".to_string(),
+            Self::Lower => "lower ground".to_string(),
+            Self::Erase => "erase".to_string(),
+            Self::Player => "player start".to_string(),
+            Self::Prefab(name) => format!("{} (prefab)", name),
+        }
+    }
+}
+
+/// A map being edited, along with a game built from it which is rendered with the same code as
+/// the real game
+pub struct Editor {
+    map: CustomMap,
+    brushes: Vec<Brush>,
+    brush_index: usize,
+    brush_radius: u32,
+    cursor: Coord,
+    /// World coordinate of the top-left corner of the view
+    view_offset: Coord,
+    /// Hour of the day at which to preview lighting, or `None` to show everything in full light
+    preview_hour: Option<u32>,
+    message: Option<String>,
+    game: witness::Game,
+    /// Set when the map has been painted since the preview was built. Painting by dragging the
+    /// mouse changes the map many times a frame, so the preview is rebuilt on the next tick.
+    preview_stale: bool,
+    ground_field: GroundField,
+    log_field: LogField,
+    tea_field: TeaField,
+    mist: Mist,
+}
+
+impl Editor {
+    /// Starts editing `map`, or a new map if it's `None`
+    pub fn new(map: Option<CustomMap>) -> Self {
+        let map = map.unwrap_or_else(|| CustomMap::new(NEW_MAP_NAME, NEW_MAP_SIZE));
+        let mut rng = Isaac64Rng::seed_from_u64(PREVIEW_SEED);
+        let size = map.size();
+        let cursor = map.player_coord();
+        let message = format!("Editing \"{}\" (escape/start for menu)", map.name());
+        Self {
+            game: Self::build_preview(&map, None),
+            preview_stale: false,
+            ground_field: GroundField::new(size, &mut rng),
+            log_field: LogField::new(size, &mut rng),
+            tea_field: TeaField::new(size, &mut rng),
+            mist: Mist::new(&mut rng),
+            map,
+            brushes: Brush::all(),
+            brush_index: 0,
+            brush_radius: 0,
+            cursor,
+            view_offset: cursor - (GAME_VIEW_SIZE / 2),
+            preview_hour: None,
+            message: Some(message),
+        }
+    }
+
+    pub fn map(&self) -> &CustomMap {
+        &self.map
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    fn build_preview(map: &CustomMap, preview_hour: Option<u32>) -> witness::Game {
+        let mut map = map.clone();
+        if let Some(hour) = preview_hour {
+            map.set_start_time(Some(Time::new(0, hour, 0, 0)));
+        }
+        let config = GameConfig {
+            omniscient: preview_hour.is_none(),
+            debug: false,
+            terrain: Default::default(),
+            map: Some(map),
+            endless: false,
+        };
+        let mut rng = Isaac64Rng::seed_from_u64(PREVIEW_SEED);
+        // custom maps are placed as drawn, so there's nothing to retry
+        let (mut game, running) =
+            witness::new_game(&config, &mut rng).expect("custom map failed to generate");
+        if preview_hour.is_some() {
+            running.reveal_lit_map(&mut game);
+        }
+        game
+    }
+
+    fn rebuild_preview(&mut self) {
+        self.game = Self::build_preview(&self.map, self.preview_hour);
+        self.preview_stale = false;
+    }
+
+    fn brush(&self) -> Brush {
+        self.brushes[self.brush_index]
+    }
+
+    /// The cells that the brush covers when centred on the cursor
+    fn brush_coords(&self) -> Vec<Coord> {
+        let radius = if self.brush().uses_radius() {
+            self.brush_radius as i32
+        } else {
+            0
+        };
+        let mut coords = Vec::new();
+        for y in -radius..=radius {
+            for x in -radius..=radius {
+                let offset = Coord::new(x, y);
+                if offset.magnitude2() as i32 <= radius * radius {
+                    let coord = self.cursor + offset;
+                    if coord.is_valid(self.map.size()) {
+                        coords.push(coord);
+                    }
+                }
+            }
+        }
+        coords
+    }
+
+    fn apply_brush(&mut self) {
+        match self.brush() {
+            Brush::Spawn(spawn) => {
+                for coord in self.brush_coords() {
+                    self.map.paint(coord, spawn);
+                }
+            }
+            Brush::Erase => {
+                for coord in self.brush_coords() {
+                    self.map.erase(coord);
+                }
+            }
+            Brush::Raise => self.map.adjust_height(self.cursor, self.brush_radius, 1),
+            Brush::Lower => self.map.adjust_height(self.cursor, self.brush_radius, -1),
+            Brush::Player => self.map.set_player(self.cursor),
+            Brush::Prefab(name) => self.map.stamp_prefab(self.cursor, name),
+        }
+        self.preview_stale = true;
+    }
+
+    fn move_cursor(&mut self, delta: Coord) {
+        let max = self.map.size().to_coord().unwrap() - Coord::new(1, 1);
+        self.cursor = Coord::new(
+            (self.cursor.x + delta.x).clamp(0, max.x),
+            (self.cursor.y + delta.y).clamp(0, max.y),
+        );
+        let view_max = GAME_VIEW_SIZE.to_coord().unwrap() - Coord::new(1, 1);
+        let min_offset = self.cursor - view_max + Coord::new(SCROLL_MARGIN, SCROLL_MARGIN);
+        let max_offset = self.cursor - Coord::new(SCROLL_MARGIN, SCROLL_MARGIN);
+        self.view_offset = Coord::new(
+            self.view_offset.x.clamp(min_offset.x, max_offset.x),
+            self.view_offset.y.clamp(min_offset.y, max_offset.y),
+        );
+    }
+
+    /// Moves the cursor to the cell under the mouse, returning false if the mouse is outside the
+    /// view
+    fn mouse_cursor(&mut self, coord: Coord) -> bool {
+        let screen_coord = (coord - GAME_VIEW_OFFSET) / 3;
+        let world_coord = self.view_offset + screen_coord;
+        if screen_coord.is_valid(GAME_VIEW_SIZE) && world_coord.is_valid(self.map.size()) {
+            self.cursor = world_coord;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn cycle_brush(&mut self, step: isize) {
+        let num_brushes = self.brushes.len() as isize;
+        self.brush_index = (self.brush_index as isize + step).rem_euclid(num_brushes) as usize;
+    }
+
+    fn step_preview_hour(&mut self, step: i32) {
+        let hour = self.preview_hour.unwrap_or(12) as i32;
+        self.preview_hour = Some((hour + step).rem_euclid(24) as u32);
+        self.rebuild_preview();
+    }
+
+    fn toggle_lighting(&mut self) {
+        self.preview_hour = match self.preview_hour {
+            Some(_) => None,
+            None => Some(self.map.start_time().map(|time| time.hour()).unwrap_or(23)),
+        };
+        self.rebuild_preview();
+    }
+
+    /// Makes the map start at the hour currently being previewed
+    fn set_start_time(&mut self) {
+        match self.preview_hour {
+            Some(hour) => {
+                self.map.set_start_time(Some(Time::new(0, hour, 0, 0)));
+                self.message = Some(format!("The map now starts at {:02}:00", hour));
+            }
+            None => {
+                self.message = Some("Preview the lighting (v) to choose a start time".to_string())
+            }
+        }
+    }
+
+    pub fn update(&mut self, controls: &Controls, event: Event) {
+        match event {
+            Event::Input(Input::Mouse(mouse_input)) => {
+                let over_map = self.mouse_cursor(mouse_input.coord());
+                match mouse_input {
+                    MouseInput::MouseMove {
+                        button: Some(MouseButton::Left),
+                        ..
+                    }
+                    | MouseInput::MousePress {
+                        button: MouseButton::Left,
+                        ..
+                    } if over_map => self.apply_brush(),
+                    MouseInput::MouseScroll { direction, .. } => match direction {
+                        ScrollDirection::Up => self.cycle_brush(-1),
+                        ScrollDirection::Down => self.cycle_brush(1),
+                        _ => (),
+                    },
+                    _ => (),
+                }
+            }
+            Event::Input(input) => {
+                match controls.get(input) {
+                    Some(AppInput::Direction(direction)) => {
+                        return self.move_cursor(direction.coord());
+                    }
+                    Some(AppInput::DirectionLong(direction)) => {
+                        return self.move_cursor(direction.coord() * CURSOR_JUMP);
+                    }
+                    _ => (),
+                }
+                if let Input::Keyboard(KeyboardInput::Char(ch)) = input {
+                    self.message = None;
+                    match ch {
+                        ' ' => self.apply_brush(),
+                        '[' => self.cycle_brush(-1),
+                        ']' => self.cycle_brush(1),
+                        '-' => self.brush_radius = self.brush_radius.saturating_sub(1),
+                        '=' | '+' => {
+                            self.brush_radius = (self.brush_radius + 1).min(MAX_BRUSH_RADIUS)
+                        }
+                        ',' => self.step_preview_hour(-1),
+                        '.' => self.step_preview_hour(1),
+                        'v' => self.toggle_lighting(),
+                        't' => self.set_start_time(),
+                        _ => (),
+                    }
+                }
+            }
+            Event::Tick(_) => {
+                if self.preview_stale {
+                    self.rebuild_preview();
+                }
+                self.mist.tick();
+            }
+            _ => (),
+        }
+    }
+
+    pub fn render(&self, ctx: Ctx, fb: &mut FrameBuffer) {
+        crate::game::render_game_with_visibility(
+            &self.game,
+            self.view_offset,
+            GAME_VIEW_SIZE,
+            &self.ground_field,
+            &self.log_field,
+            &self.tea_field,
+            &self.mist,
+            ctx.add_offset(GAME_VIEW_OFFSET),
+            fb,
+        );
+        for coord in self.brush_coords() {
+            let screen_coord = coord - self.view_offset;
+            if !screen_coord.is_valid(GAME_VIEW_SIZE) {
+                continue;
+            }
+            let screen_cursor = GAME_VIEW_OFFSET + (screen_coord * 3);
+            for offset in Size::new_u16(3, 3).coord_iter_row_major() {
+                fb.set_cell_relative_to_ctx(
+                    ctx,
+                    screen_cursor + offset,
+                    10,
+                    RenderCell::BLANK.with_background(colour::CURSOR),
+                );
+            }
+        }
+        self.render_text(ctx.add_depth(20), fb);
+    }
+
+    fn render_text(&self, ctx: Ctx, fb: &mut FrameBuffer) {
+        if let Some(mut here) = examine::examine(&self.game, self.cursor) {
+            here.string = format!("Cursor: {}", here.string);
+            here.wrap_word().render(&(), ctx.add_x(1), fb);
+        }
+        if let Some(message) = self.message.as_ref() {
+            StyledString::plain_text(message.clone()).render(&(), ctx.add_xy(1, 1), fb);
+        }
+        let ctx = ctx.add_xy(1, GAME_VIEW_OFFSET.y + GAME_VIEW_SIZE.y() as i32 * 3);
+        let radius = if self.brush().uses_radius() {
+            format!(" (radius {})", self.brush_radius)
+        } else {
+            String::new()
+        };
+        StyledString {
+            string: format!("Brush: {}{}", self.brush().name(), radius),
+            style: Style::plain_text().with_bold(true),
+        }
+        .render(&(), ctx, fb);
+        let lighting = match self.preview_hour {
+            Some(hour) => format!("{:02}:00", hour),
+            None => "full light".to_string(),
+        };
+        let start_time = self
+            .map
+            .start_time()
+            .map(|time| format!("{:02}:{:02}", time.hour(), time.minute()))
+            .unwrap_or_else(|| "default".to_string());
+        StyledString::plain_text(format!(
+            "Position: {},{}  Height: {:.2}  Lighting: {}  Start time: {}",
+            self.cursor.x,
+            self.cursor.y,
+            self.map.height(self.cursor),
+            lighting,
+            start_time,
+        ))
+        .render(&(), ctx.add_y(1), fb);
+        StyledString {
+            string: "Space: paint  [ ]: brush  - +: radius  v: lighting  , .: hour  t: start here"
+                .to_string(),
+            style: Style::plain_text().with_foreground(Rgba32::new_grey(127)),
+        }
+        .render(&(), ctx.add_y(2), fb);
+    }
+}
@@ -32,20 +32,36 @@ pub fn examine(game: &Game, coord: Coord) -> Option<StyledString> {
             }
         }
     }
+    let walk_time = if coord == game.player_coord() {
+        None
+    } else {
+        game.expected_walk_time(coord)
+    };
     entity_under_cursor.and_then(|(tile, verb)| {
-        tile_str(tile).map(|label| match label {
-            TileLabel::Name(name) => {
-                let verb_str = match verb {
-                    MessageVerb::Remember => "remember seeing",
-                    MessageVerb::See => "see",
-                };
-                StyledString::plain_text(format!("You {} {} here.", verb_str, name))
+        tile_str(tile).map(|label| {
+            let mut string = match label {
+                TileLabel::Name(name) => {
+                    let verb_str = match verb {
+                        MessageVerb::Remember => "remember seeing",
+                        MessageVerb::See => "see",
+                    };
+                    format!("You {} {} here.", verb_str, name)
+                }
+                TileLabel::Literal(literal) => literal.to_string(),
+            };
+            if let Some(walk_time) = walk_time {
+                string.push_str(&format!(" {}", walk_time_str(walk_time)));
             }
-            TileLabel::Literal(literal) => StyledString::plain_text(literal.to_string()),
+            StyledString::plain_text(string)
         })
     })
 }
 
+fn walk_time_str(seconds: u32) -> String {
+    let minutes = ((seconds + 30) / 60).max(1);
+    format!("(about {} min to walk here)", minutes)
+}
+
 enum TileLabel {
     Literal(&'static str),
     Name(&'static str),
@@ -79,7 +95,31 @@ fn tile_str(tile: Tile) -> Option<TileLabel> {
         Tile::WeatherReport => TileLabel::Name("this week's weather report"),
         Tile::Lantern => TileLabel::Name("a portable lantern"),
         Tile::Crowbar => TileLabel::Name("a crowbar"),
+        Tile::Axe => TileLabel::Name("an axe"),
+        Tile::Firewood => TileLabel::Name("some firewood"),
+        Tile::Mud => TileLabel::Name("thick mud"),
+        Tile::Puddle => TileLabel::Name("a puddle"),
+        Tile::Boat => TileLabel::Name("a rowing boat"),
+        Tile::Cairn => TileLabel::Name("a cairn of stones"),
+        Tile::FishingRod => TileLabel::Name("a fishing rod"),
+        Tile::Stove => TileLabel::Name("a wood stove"),
+        Tile::Mushroom => TileLabel::Name("a mushroom"),
+        Tile::KitchenBench => TileLabel::Name("a kitchen bench"),
+        Tile::Trail => TileLabel::Name("a trail"),
+        Tile::Signpost => TileLabel::Name("a signpost"),
+        Tile::Stream => TileLabel::Name("a stream"),
+        Tile::Ford => TileLabel::Name("a ford"),
+        Tile::Bridge => TileLabel::Name("a bridge"),
+        Tile::Lookout => TileLabel::Name("the lookout tower"),
+        Tile::TowerLeg => TileLabel::Name("a leg of the lookout tower"),
+        Tile::Table => TileLabel::Name("a table"),
+        Tile::Bookshelf => TileLabel::Name("a bookshelf"),
+        Tile::Ladder => TileLabel::Name("a ladder up to the loft"),
+        Tile::Hatch => TileLabel::Name("the ladder down from the loft"),
+        Tile::StairsDown => TileLabel::Name("stairs down to the cellar"),
+        Tile::StairsUp => TileLabel::Name("stairs up out of the cellar"),
         Tile::Ditch => TileLabel::Name("a ditch"),
+        Tile::Log(_) => TileLabel::Name("a fallen log"),
     };
     Some(label)
 }
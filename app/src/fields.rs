@@ -10,6 +10,19 @@ use rand::{
 };
 use serde::{Deserialize, Serialize};
 
+/// Moves the cells of a field by `offset`, wrapping around at the edges, so that the field keeps
+/// lining up with the world of the endless forest when it moves along
+fn shifted<T: Clone>(grid: &Grid<T>, offset: Coord) -> Grid<T> {
+    let size = grid.size();
+    Grid::new_fn(size, |coord| {
+        let from = Coord::new(
+            (coord.x - offset.x).rem_euclid(size.width() as i32),
+            (coord.y - offset.y).rem_euclid(size.height() as i32),
+        );
+        grid.get_checked(from).clone()
+    })
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct TeaField {
     grid: Grid<u16>,
@@ -27,9 +40,13 @@ impl TeaField {
     pub fn get(&self, coord: Coord) -> Option<u16> {
         self.grid.get(coord).cloned()
     }
+
+    pub fn shift(&mut self, offset: Coord) {
+        self.grid = shifted(&self.grid, offset);
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct GroundCell {
     fg: Rgb24,
     bg: Rgb24,
@@ -65,6 +82,9 @@ impl GroundField {
             grid: Grid::new_fn(size * 3, |coord| Self::choose(coord, rng)),
         }
     }
+    pub fn shift(&mut self, offset: Coord) {
+        self.grid = shifted(&self.grid, offset * 3);
+    }
     pub fn render(&self, coord: Coord, ctx: Ctx, fb: &mut FrameBuffer) {
         let base_coord = coord * 3;
         for offset in Size::new_u16(3, 3).coord_iter_row_major() {
@@ -124,6 +144,10 @@ impl LogField {
             vertical,
         }
     }
+    pub fn shift(&mut self, offset: Coord) {
+        self.horizontal = shifted(&self.horizontal, Coord::new(offset.x * 3, offset.y * 6));
+        self.vertical = shifted(&self.vertical, Coord::new(offset.x * 6, offset.y * 3));
+    }
     pub fn get_horizontal(&self, coord: Coord) -> Option<Rgb24> {
         self.horizontal.get(coord).cloned()
     }
@@ -1,11 +1,13 @@
 use crate::{
     colour,
     controls::{AppInput, Controls},
+    daily::{self, DailyAttempt, DailyHistory, DailyResult, Date},
+    editor::Editor,
     examine,
     fields::{GroundField, LogField, TeaField},
     mist::Mist,
     rain::{Rain, RainDirection},
-    text, AppStorage, InitialRngSeed,
+    seed_code, text, AppStorage, CustomMap, InitialRngSeed,
 };
 use gridbugs::{
     chargrid::{
@@ -16,15 +18,16 @@ use gridbugs::{
 };
 use rainforest_game::{
     witness::{self, RunningGame, Witness},
-    ActionError, Config as GameConfig, Game, Item, RainLevel, RainSchedule, TopographyCell,
-    MAX_MOTIVATION,
+    ActionError, Config as GameConfig, Game, GenerationFailed, Item, Level, MoonPhase, RainLevel,
+    RainSchedule, TerrainConfig, Thunder, TopographyCell, WindLevel, MAX_MOTIVATION, RECIPES,
 };
 use rand::{Rng, SeedableRng};
 use rand_isaac::Isaac64Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-const GAME_VIEW_SIZE: Size = Size::new_u16(26, 18);
-const GAME_VIEW_OFFSET: Coord = Coord::new(1, 2);
+pub const GAME_VIEW_SIZE: Size = Size::new_u16(26, 18);
+pub const GAME_VIEW_OFFSET: Coord = Coord::new(1, 2);
 
 /// An interactive, renderable process yielding a value of type `T`
 pub type AppCF<T> = CF<Option<T>, GameLoopData>;
@@ -35,12 +38,17 @@ struct RngSeedSource {
     seed_rng: Isaac64Rng,
 }
 
+/// Random seeds are kept below 2^32 so their seed codes are only four words long
+fn random_seed<R: Rng>(rng: &mut R) -> u64 {
+    rng.gen::<u32>() as u64
+}
+
 impl RngSeedSource {
     fn new(initial_rng_seed: InitialRngSeed) -> Self {
         let mut seed_rng = Isaac64Rng::from_entropy();
         let next_seed = match initial_rng_seed {
             InitialRngSeed::U64(seed) => seed,
-            InitialRngSeed::Random => seed_rng.gen(),
+            InitialRngSeed::Random => random_seed(&mut seed_rng),
         };
         Self {
             next_seed,
@@ -48,9 +56,15 @@ impl RngSeedSource {
         }
     }
 
+    /// A random id for telling games apart, which isn't drawn from the seeds so it doesn't
+    /// change which forests are generated
+    fn next_id(&mut self) -> u64 {
+        self.seed_rng.gen()
+    }
+
     fn next_seed(&mut self) -> u64 {
         let seed = self.next_seed;
-        self.next_seed = self.seed_rng.gen();
+        self.next_seed = random_seed(&mut self.seed_rng);
         #[cfg(feature = "print_stdout")]
         println!("RNG Seed: {}", seed);
         #[cfg(feature = "print_log")]
@@ -67,8 +81,56 @@ fn action_error_message(action_error: ActionError) -> StyledString {
     StyledString { string, style }
 }
 
+fn thunder_message(thunder: Thunder) -> StyledString {
+    let string = match thunder {
+        Thunder::Near => "Thunder cracks overhead!",
+        Thunder::Distant => "Thunder rumbles in the distance.",
+    };
+    StyledString {
+        string: string.to_string(),
+        style: Style::plain_text(),
+    }
+}
+
+/// Where the chunks of an endless forest are in storage. Each save starts a new generation, and
+/// chunks are written under the current generation, so the copies that a save refers to are left
+/// alone until the game is saved again.
+#[derive(Default, Serialize, Deserialize)]
+struct ChunkFiles {
+    generation: u64,
+    /// The generation of the latest copy of each chunk
+    latest: HashMap<Coord, u64>,
+    /// Copies that have been replaced since the game was last saved, which the save still
+    /// refers to
+    replaced: Vec<(Coord, u64)>,
+}
+
+impl ChunkFiles {
+    /// Notes that a chunk is being written, returning the generation to write it under
+    fn write(&mut self, coord: Coord) -> u64 {
+        match self.latest.insert(coord, self.generation) {
+            Some(previous) if previous != self.generation => self.replaced.push((coord, previous)),
+            _ => (),
+        }
+        self.generation
+    }
+
+    fn all(&self) -> impl '_ + Iterator<Item = (Coord, u64)> {
+        self.latest
+            .iter()
+            .map(|(&coord, &generation)| (coord, generation))
+            .chain(self.replaced.iter().cloned())
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct GameInstanceStorable {
+    seed: Option<u64>,
+    daily: Option<DailyAttempt>,
+    id: u64,
+    chunk_files: ChunkFiles,
+    world_origin: Coord,
+    level: Level,
     running_game: RunningGame,
     ground_field: GroundField,
     log_field: LogField,
@@ -80,6 +142,12 @@ pub struct GameInstanceStorable {
 impl GameInstanceStorable {
     fn into_game_instance(self) -> (GameInstance, witness::Running) {
         let Self {
+            seed,
+            daily,
+            id,
+            chunk_files,
+            world_origin,
+            level,
             running_game,
             ground_field,
             log_field,
@@ -90,6 +158,12 @@ impl GameInstanceStorable {
         let (game, running) = running_game.into_game();
         (
             GameInstance {
+                seed,
+                daily,
+                id,
+                chunk_files,
+                world_origin,
+                level,
                 game,
                 ground_field,
                 log_field,
@@ -102,7 +176,29 @@ impl GameInstanceStorable {
     }
 }
 
+#[derive(Clone, Copy)]
+struct LightningFlash(u8);
+
+impl Tint for LightningFlash {
+    fn tint(&self, rgba32: Rgba32) -> Rgba32 {
+        rgba32.linear_interpolate(colour::LIGHTNING, self.0)
+    }
+}
+
 struct GameInstance {
+    /// The seed to enter to play this forest again. Daily forests, custom maps, the endless
+    /// forest and forests generated with a terrain config other than the default aren't made from
+    /// the seed alone, so they don't have one.
+    seed: Option<u64>,
+    /// The daily forest this is an attempt at, if it is one
+    daily: Option<DailyAttempt>,
+    /// Identifies the stored chunks of an endless forest as belonging to this game
+    id: u64,
+    chunk_files: ChunkFiles,
+    /// Where the world was when the fields were last lined up with it
+    world_origin: Coord,
+    /// The level the player was on when rain was last hidden under the roofs
+    level: Level,
     game: Game,
     ground_field: GroundField,
     log_field: LogField,
@@ -112,15 +208,34 @@ struct GameInstance {
 }
 
 impl GameInstance {
-    pub fn new<R: Rng>(config: &GameConfig, rng: &mut R) -> (Self, witness::Running) {
-        let (game, running) = witness::new_game(config, rng);
-        let ground_field = GroundField::new(game.world_size(), rng);
-        let log_field = LogField::new(game.world_size(), rng);
-        let tea_field = TeaField::new(game.world_size(), rng);
-        let rain = Rain::new(&game, 10000, RainDirection::Diagonal, rng);
-        let mist = Mist::new(rng);
-        (
+    pub fn new(
+        config: &GameConfig,
+        seed: u64,
+    ) -> Result<(Self, witness::Running), GenerationFailed> {
+        let mut rng = Isaac64Rng::seed_from_u64(seed);
+        let (game, running) = witness::new_game(config, &mut rng)?;
+        log::info!("{}", game.generation_stats());
+        let ground_field = GroundField::new(game.world_size(), &mut rng);
+        let log_field = LogField::new(game.world_size(), &mut rng);
+        let tea_field = TeaField::new(game.world_size(), &mut rng);
+        let rain = Rain::new(&game, 10000, RainDirection::Diagonal, &mut rng);
+        let mist = Mist::new(&mut rng);
+        let world_origin = game.world_origin();
+        let level = game.level();
+        // a seed code only reproduces the forest with the default terrain config
+        let seed = if config.terrain == TerrainConfig::default() {
+            Some(seed)
+        } else {
+            None
+        };
+        Ok((
             GameInstance {
+                seed,
+                daily: None,
+                id: 0,
+                chunk_files: ChunkFiles::default(),
+                world_origin,
+                level,
                 game,
                 ground_field,
                 log_field,
@@ -129,11 +244,17 @@ impl GameInstance {
                 mist,
             },
             running,
-        )
+        ))
     }
 
     pub fn into_storable(self, running: witness::Running) -> GameInstanceStorable {
         let Self {
+            seed,
+            daily,
+            id,
+            chunk_files,
+            world_origin,
+            level,
             game,
             ground_field,
             log_field,
@@ -143,6 +264,12 @@ impl GameInstance {
         } = self;
         let running_game = running.running_game(game);
         GameInstanceStorable {
+            seed,
+            daily,
+            id,
+            chunk_files,
+            world_origin,
+            level,
             running_game,
             ground_field,
             log_field,
@@ -152,10 +279,32 @@ impl GameInstance {
         }
     }
 
+    /// Moves the fields along with the world of the endless forest, and keeps rain off the roofs
+    /// of whichever level the player is on
+    fn follow_world(&mut self) {
+        let world_origin = self.game.world_origin();
+        if world_origin != self.world_origin {
+            let offset = self.world_origin - world_origin;
+            self.ground_field.shift(offset);
+            self.log_field.shift(offset);
+            self.tea_field.shift(offset);
+            self.rain.refresh_hide_table(&self.game);
+            self.world_origin = world_origin;
+        }
+        let level = self.game.level();
+        if level != self.level {
+            self.rain.refresh_hide_table(&self.game);
+            self.level = level;
+        }
+    }
+
     pub fn render(&self, ctx: Ctx, fb: &mut FrameBuffer) {
         self.render_top_ui(ctx.add_depth(10), fb);
         let offset = self.game.player_coord() - (GAME_VIEW_SIZE / 2);
         let ctx = ctx.add_offset(GAME_VIEW_OFFSET);
+        let flash = LightningFlash(self.game.lightning_flash_intensity());
+        let flash_tint = ctx.compose_tint(&flash);
+        let game_ctx = ctx.with_tint(&flash_tint);
         crate::game::render_game_with_visibility(
             &self.game,
             offset,
@@ -164,11 +313,11 @@ impl GameInstance {
             &self.log_field,
             &self.tea_field,
             &self.mist,
-            ctx,
+            game_ctx,
             fb,
         );
         self.rain
-            .render(&self.game, offset, GAME_VIEW_SIZE, ctx, fb);
+            .render(&self.game, offset, GAME_VIEW_SIZE, game_ctx, fb);
         self.render_bottom_ui(ctx.add_y(GAME_VIEW_SIZE.y() as i32 * 3), fb);
     }
 
@@ -228,6 +377,8 @@ impl GameInstance {
             Some(Item::Flower) => "a flower (drop with g)",
             Some(Item::Tea) => "some tea leaves (drop with g)",
             Some(Item::Rock) => "a rock (drop with g)",
+            Some(Item::Firewood) => "some firewood (drop with g)",
+            Some(Item::Mushroom) => "a mushroom (drop with g)",
         };
         StyledString {
             string: (format!("Holding: {}", item_str)),
@@ -242,6 +393,7 @@ impl GameInstance {
         }
         .wrap_word()
         .render(&(), ctx.add_xy(25, 2), fb);
+        let fishing_rod_text = format!("Fishing Rod ({} fish)", self.game.fish_held());
         let mut equipped_text = Vec::new();
         {
             let equipped = self.game.equipped();
@@ -270,6 +422,12 @@ impl GameInstance {
             if equipped.weather_report {
                 equipped_text.push("Weather Report");
             }
+            if equipped.axe {
+                equipped_text.push("Axe");
+            }
+            if equipped.fishing_rod {
+                equipped_text.push(fishing_rod_text.as_str());
+            }
         }
         let equipped_string = if equipped_text.is_empty() {
             "(nothing)".to_string()
@@ -292,12 +450,18 @@ pub enum GameLoopState {
     MainMenu,
     Map(witness::Running),
     WeatherReport(witness::Running),
+    Editor,
+    EditorMenu,
+    GenerationFailed(GenerationFailed),
 }
 
 pub struct GameLoopData {
     instance: Option<GameInstance>,
     controls: Controls,
     game_config: GameConfig,
+    maps: Vec<CustomMap>,
+    editor: Option<Editor>,
+    daily_history: DailyHistory,
     storage: AppStorage,
     rng_seed_source: RngSeedSource,
     context_message: Option<StyledString>,
@@ -308,14 +472,22 @@ pub struct GameLoopData {
 fn new_game(
     rng_seed_source: &mut RngSeedSource,
     game_config: &GameConfig,
-) -> (GameInstance, witness::Running) {
-    let mut rng = Isaac64Rng::seed_from_u64(rng_seed_source.next_seed());
-    GameInstance::new(game_config, &mut rng)
+) -> Result<(GameInstance, witness::Running), GenerationFailed> {
+    GameInstance::new(game_config, rng_seed_source.next_seed())
+}
+
+/// The config for a new game played on a hand-authored map
+fn custom_map_config(game_config: &GameConfig, map: &CustomMap) -> GameConfig {
+    GameConfig {
+        map: Some(map.clone()),
+        ..game_config.clone()
+    }
 }
 
 impl GameLoopData {
     pub fn new(
         game_config: GameConfig,
+        mut maps: Vec<CustomMap>,
         mut storage: AppStorage,
         initial_rng_seed: InitialRngSeed,
         force_new_game: bool,
@@ -331,16 +503,32 @@ impl GameLoopData {
             }
             None => {
                 if force_new_game {
-                    let (instance, running) = new_game(&mut rng_seed_source, &game_config);
-                    (
-                        Some(instance),
-                        GameLoopState::Playing(running.into_witness()),
-                    )
+                    // a map given on the command line is played straight away
+                    let new = match maps.first() {
+                        Some(map) => {
+                            new_game(&mut rng_seed_source, &custom_map_config(&game_config, map))
+                                .map(|(mut instance, running)| {
+                                    instance.seed = None;
+                                    (instance, running)
+                                })
+                        }
+                        None => new_game(&mut rng_seed_source, &game_config),
+                    };
+                    match new {
+                        Ok((instance, running)) => (
+                            Some(instance),
+                            GameLoopState::Playing(running.into_witness()),
+                        ),
+                        Err(failure) => (None, GameLoopState::GenerationFailed(failure)),
+                    }
                 } else {
                     (None, GameLoopState::MainMenu)
                 }
             }
         };
+        // the map from the editor can be played from the main menu too
+        maps.extend(storage.load_editor_map());
+        let daily_history = storage.load_daily_history().unwrap_or_default();
         let controls = if let Some(controls) = storage.load_controls() {
             controls
         } else {
@@ -353,6 +541,9 @@ impl GameLoopData {
                 instance,
                 controls,
                 game_config,
+                maps,
+                editor: None,
+                daily_history,
                 storage,
                 rng_seed_source,
                 context_message: None,
@@ -422,7 +613,28 @@ impl GameLoopData {
         });
     }
 
+    /// Stores chunks of the endless forest that the player has left far behind, and hands back
+    /// the stored chunks they're heading towards
+    fn exchange_chunks(&mut self, running: witness::Running) -> witness::Running {
+        let instance = self.instance.as_mut().unwrap();
+        let (mut running, far_chunks) = running.take_far_chunks(&mut instance.game);
+        for chunk in far_chunks {
+            let generation = instance.chunk_files.write(chunk.coord());
+            self.storage.save_chunk(instance.id, generation, &chunk);
+        }
+        for coord in instance.game.wanted_chunks() {
+            let chunk = instance
+                .chunk_files
+                .latest
+                .get(&coord)
+                .and_then(|&generation| self.storage.load_chunk(instance.id, generation, coord));
+            running = running.restore_chunk(&mut instance.game, coord, chunk);
+        }
+        running
+    }
+
     fn update(&mut self, event: Event, running: witness::Running) -> GameLoopState {
+        let running = self.exchange_chunks(running);
         let instance = self.instance.as_mut().unwrap();
         let witness = match event {
             Event::Input(input) => {
@@ -453,6 +665,12 @@ impl GameLoopData {
                             running.player_toggle_pushing(&mut instance.game, &self.game_config)
                         }
                         AppInput::Dig => running.player_dig(&mut instance.game, &self.game_config),
+                        AppInput::Chop => {
+                            running.player_chop(&mut instance.game, &self.game_config)
+                        }
+                        AppInput::Fish => {
+                            running.player_fish(&mut instance.game, &self.game_config)
+                        }
                         AppInput::Map => {
                             if instance.game.equipped().map {
                                 return GameLoopState::Map(running);
@@ -497,31 +715,149 @@ impl GameLoopData {
                 }
                 instance.rain.tick();
                 instance.mist.tick();
-                running.tick(&mut instance.game, since_previous, &self.game_config)
+                let witness = running.tick(&mut instance.game, since_previous, &self.game_config);
+                if let Some(thunder) = instance.game.thunder() {
+                    self.context_message = Some(thunder_message(thunder));
+                }
+                witness
             }
             _ => Witness::Running(running),
         };
+        instance.follow_world();
         self.examine_mouse(event);
         self.update_examine_text();
         GameLoopState::Playing(witness)
     }
 
-    fn new_game(&mut self) -> witness::Running {
-        let (instance, running) = new_game(&mut self.rng_seed_source, &self.game_config);
+    /// Starts playing a new game. The current game is abandoned, so its save and stored chunks
+    /// are removed.
+    fn replace_instance(&mut self, instance: GameInstance) {
+        self.clear_saved_game();
         self.instance = Some(instance);
-        running
+    }
+
+    fn new_game(&mut self) -> Result<witness::Running, GenerationFailed> {
+        let (instance, running) = new_game(&mut self.rng_seed_source, &self.game_config)?;
+        self.replace_instance(instance);
+        Ok(running)
+    }
+
+    fn new_game_with_seed(&mut self, seed: u64) -> Result<witness::Running, GenerationFailed> {
+        let (instance, running) = GameInstance::new(&self.game_config, seed)?;
+        self.replace_instance(instance);
+        Ok(running)
+    }
+
+    fn new_daily_game(&mut self, date: Date) -> Result<witness::Running, GenerationFailed> {
+        let game_config = GameConfig {
+            terrain: date.difficulty().terrain_config(),
+            map: None,
+            ..self.game_config.clone()
+        };
+        let (mut instance, running) = GameInstance::new(&game_config, date.seed())?;
+        instance.seed = None;
+        // recording the attempt as it starts means quitting a bad run doesn't get a retry
+        let first = self.daily_history.record(DailyResult::pending(date));
+        if first {
+            self.storage.save_daily_history(&self.daily_history);
+        }
+        instance.daily = Some(DailyAttempt { date, first });
+        self.replace_instance(instance);
+        Ok(running)
+    }
+
+    fn new_endless_game(&mut self) -> Result<witness::Running, GenerationFailed> {
+        let game_config = GameConfig {
+            map: None,
+            endless: true,
+            ..self.game_config.clone()
+        };
+        let (mut instance, running) = new_game(&mut self.rng_seed_source, &game_config)?;
+        instance.seed = None;
+        instance.id = self.rng_seed_source.next_id();
+        self.replace_instance(instance);
+        Ok(running)
+    }
+
+    /// Adds the result of the current game to the daily history if it is a daily forest, and
+    /// describes the result and the player's streak
+    fn record_daily_result(&mut self, survived: bool) -> Option<String> {
+        let instance = self.instance.as_ref().unwrap();
+        let DailyAttempt { date, first } = instance.daily?;
+        let result = DailyResult::new(
+            date,
+            survived,
+            instance.game.motivation(),
+            instance.game.time().day(),
+        );
+        let summary = format!("Daily Forest {}: {}.", date, result);
+        if first && self.daily_history.record(result) {
+            self.storage.save_daily_history(&self.daily_history);
+            let streak = self.daily_history.current_streak(Date::today());
+            Some(format!("{}\nCurrent streak: {}", summary, days(streak)))
+        } else {
+            Some(format!(
+                "{}\nOnly the first attempt at each daily forest is recorded.",
+                summary
+            ))
+        }
+    }
+
+    /// The seed of the current game written as a seed code, if it can be played again from one
+    fn seed_code(&self) -> Option<String> {
+        self.instance.as_ref().unwrap().seed.map(seed_code::encode)
+    }
+
+    fn new_custom_map_game(&mut self, index: usize) -> Result<witness::Running, GenerationFailed> {
+        let game_config = custom_map_config(&self.game_config, &self.maps[index]);
+        let (mut instance, running) = new_game(&mut self.rng_seed_source, &game_config)?;
+        instance.seed = None;
+        self.replace_instance(instance);
+        Ok(running)
     }
 
     fn save_instance(&mut self, running: witness::Running) -> witness::Running {
-        let instance = self.instance.take().unwrap().into_storable(running);
+        let mut instance = self.instance.take().unwrap();
+        // chunks stored from now on mustn't overwrite the ones this save refers to
+        instance.chunk_files.generation += 1;
+        let replaced = std::mem::take(&mut instance.chunk_files.replaced);
+        let instance = instance.into_storable(running);
         self.storage.save_game(&instance);
+        for (coord, generation) in replaced {
+            self.storage.remove_chunk(instance.id, generation, coord);
+        }
         let (instance, running) = instance.into_game_instance();
         self.instance = Some(instance);
         running
     }
 
+    /// Removes the saved game along with every stored chunk of the current game
     fn clear_saved_game(&mut self) {
         self.storage.clear_game();
+        if let Some(instance) = self.instance.as_mut() {
+            for (coord, generation) in instance.chunk_files.all() {
+                self.storage.remove_chunk(instance.id, generation, coord);
+            }
+            instance.chunk_files = ChunkFiles::default();
+        }
+    }
+
+    /// Opens the editor on the map it last saved, if any
+    fn open_editor(&mut self) {
+        self.editor = Some(Editor::new(self.storage.load_editor_map()));
+    }
+
+    fn new_editor_map(&mut self) {
+        self.editor = Some(Editor::new(None));
+    }
+
+    fn save_editor_map(&mut self) {
+        let editor = self.editor.as_mut().unwrap();
+        let map = editor.map().clone();
+        self.storage.save_editor_map(&map);
+        editor.set_message(format!("Saved \"{}\"", map.name()));
+        self.maps.retain(|other| other.name() != map.name());
+        self.maps.push(map);
     }
 }
 
@@ -614,8 +950,15 @@ fn weather_report_component() -> AppCF<()> {
             style: Style::plain_text(),
         });
         let rain_schedule = state.game().rain_schedule();
+        let moon_phase = state.game().moon_phase();
+        // the endless forest's forecast starts from today
+        let first_day = if state.game().is_endless() {
+            (state.game().time().day() as usize).max(1)
+        } else {
+            1
+        };
         state.examine_message = None;
-        cf(WeatherReportComponent(rain_schedule))
+        cf(WeatherReportComponent(rain_schedule, moon_phase, first_day))
             .catch_escape_or_start()
             .map_val(|| ())
             .side_effect(|state: &mut State| {
@@ -625,7 +968,8 @@ fn weather_report_component() -> AppCF<()> {
     })
 }
 
-struct WeatherReportComponent(RainSchedule);
+/// The rain schedule and moon phase, and the first of the five days to show
+struct WeatherReportComponent(RainSchedule, MoonPhase, usize);
 impl Component for WeatherReportComponent {
     type Output = Option<()>;
     type State = GameLoopData;
@@ -633,9 +977,15 @@ impl Component for WeatherReportComponent {
     fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
         state.render_text(ctx, fb);
         let mut ctx = ctx.add_xy(1, 3);
+        let first_day = self.2;
+        let day_name = |i: usize| format!("Day {}", first_day + i);
         StyledString::plain_text(format!(
             "              |{:^11}|{:^11}|{:^11}|{:^11}|{:^11}|",
-            "Day 1", "Day 2", "Day 3", "Day 4", "Day 5",
+            day_name(0),
+            day_name(1),
+            day_name(2),
+            day_name(3),
+            day_name(4),
         ))
         .render(&(), ctx, fb);
         let mut line = |name: &str, time| {
@@ -655,17 +1005,25 @@ impl Component for WeatherReportComponent {
             StyledString::plain_text(format!(
                 "{} |{:^11}|{:^11}|{:^11}|{:^11}|{:^11}|",
                 name,
-                self.0.get(1, time).to_string(),
-                self.0.get(2, time).to_string(),
-                self.0.get(3, time).to_string(),
-                self.0.get(4, time).to_string(),
-                self.0.get(5, time).to_string(),
+                self.0.get(first_day, time).to_string(),
+                self.0.get(first_day + 1, time).to_string(),
+                self.0.get(first_day + 2, time).to_string(),
+                self.0.get(first_day + 3, time).to_string(),
+                self.0.get(first_day + 4, time).to_string(),
             ))
             .render(&(), ctx, fb);
             ctx = ctx.add_y(1);
+            let wind = |day| match self.0.get_wind(day, time) {
+                WindLevel::Calm => "".to_string(),
+                wind_level => format!("{}", wind_level),
+            };
             StyledString::plain_text(format!(
                 "              |{:^11}|{:^11}|{:^11}|{:^11}|{:^11}|",
-                "", "", "", "", ""
+                wind(first_day),
+                wind(first_day + 1),
+                wind(first_day + 2),
+                wind(first_day + 3),
+                wind(first_day + 4),
             ))
             .render(&(), ctx, fb);
         };
@@ -685,6 +1043,7 @@ impl Component for WeatherReportComponent {
             "", "", "", "", ""
         ))
         .render(&(), ctx.add_y(1), fb);
+        StyledString::plain_text(format!("Moon tonight: {}", self.1)).render(&(), ctx.add_y(3), fb);
     }
 
     fn update(&mut self, state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
@@ -731,6 +1090,33 @@ impl Component for MapComponent {
                     0,
                 ),
                 TopographyCell::Unknown => (RenderCell::default().with_character('?'), 0),
+                TopographyCell::Mud => (
+                    RenderCell::default()
+                        .with_character(' ')
+                        .with_background(colour::MAP_MUD),
+                    0,
+                ),
+                TopographyCell::Trail => (
+                    RenderCell::default()
+                        .with_character('·')
+                        .with_bold(true)
+                        .with_foreground(colour::MAP_TRAIL),
+                    0,
+                ),
+                TopographyCell::Lookout => (
+                    RenderCell::default()
+                        .with_character('Ψ')
+                        .with_bold(true)
+                        .with_foreground(colour::MAP_LOOKOUT),
+                    1,
+                ),
+                TopographyCell::FallenLog => (
+                    RenderCell::default()
+                        .with_character('=')
+                        .with_bold(true)
+                        .with_foreground(colour::MAP_FALLEN_LOG),
+                    1,
+                ),
                 TopographyCell::Player => (
                     RenderCell::default()
                         .with_character('@')
@@ -754,6 +1140,14 @@ impl Component for MapComponent {
                     );
                     continue;
                 }
+                TopographyCell::Island => {
+                    StyledString::plain_text("Island".to_string()).render(
+                        &(),
+                        ctx.add_offset(coord / 3).add_depth(1),
+                        fb,
+                    );
+                    continue;
+                }
                 TopographyCell::Tea => {
                     StyledString::plain_text("Tea".to_string()).render(
                         &(),
@@ -814,6 +1208,37 @@ fn map_component() -> AppCF<()> {
     })
 }
 
+struct EditorComponent;
+
+impl Component for EditorComponent {
+    type Output = Option<()>;
+    type State = GameLoopData;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        state.editor.as_ref().unwrap().render(ctx, fb);
+    }
+
+    fn update(&mut self, state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        if event.is_escape_or_start() {
+            return Some(());
+        }
+        state
+            .editor
+            .as_mut()
+            .unwrap()
+            .update(&state.controls, event);
+        None
+    }
+
+    fn size(&self, _state: &Self::State, ctx: Ctx) -> Size {
+        ctx.bounding_box.size()
+    }
+}
+
+fn editor_component() -> AppCF<()> {
+    cf(EditorComponent)
+}
+
 const MENU_FADE_SPEC: menu::identifier::fade_spec::FadeSpec = {
     use menu::identifier::fade_spec::*;
     FadeSpec {
@@ -876,16 +1301,22 @@ fn popup_style<T: 'static>(menu: AppCF<T>) -> AppCF<T> {
 #[derive(Clone)]
 enum MainMenuEntry {
     NewGame,
+    Daily,
+    Endless,
+    EnterSeed,
+    CustomMaps,
+    Editor,
     Help,
     Quit,
 }
 
 enum MainMenuOutput {
     NewGame { new_running: witness::Running },
+    Editor,
     Quit,
 }
 
-fn main_menu() -> AppCF<MainMenuEntry> {
+fn main_menu(has_custom_maps: bool) -> AppCF<MainMenuEntry> {
     use menu::builder::*;
     use MainMenuEntry::*;
     let mut builder = menu_builder().vi_keys();
@@ -895,11 +1326,229 @@ fn main_menu() -> AppCF<MainMenuEntry> {
         builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
     };
     add_item(NewGame, "New Game", 'n');
+    add_item(Daily, "Daily Forest", 'd');
+    add_item(Endless, "Endless Forest", 'f');
+    add_item(EnterSeed, "Enter Seed", 's');
+    if has_custom_maps {
+        add_item(CustomMaps, "Custom Maps", 'm');
+    }
+    add_item(Editor, "Map Editor", 'e');
     add_item(Help, "Help", 'h');
     add_item(Quit, "Quit", 'q');
     builder.build_cf()
 }
 
+fn custom_map_menu(names: &[String]) -> AppCF<Option<usize>> {
+    use menu::builder::*;
+    let mut builder = menu_builder().vi_keys();
+    for (index, name) in names.iter().enumerate() {
+        let name = name.clone();
+        match std::char::from_digit(index as u32 + 1, 10) {
+            Some(ch) => {
+                let identifier =
+                    MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+                builder.add_item_mut(item(Some(index), identifier).add_hotkey_char(ch));
+            }
+            None => {
+                let identifier =
+                    MENU_FADE_SPEC.identifier(move |b| write!(b, "    {}", name).unwrap());
+                builder.add_item_mut(item(Some(index), identifier));
+            }
+        }
+    }
+    builder.add_item_mut(
+        item(
+            None,
+            MENU_FADE_SPEC.identifier(move |b| write!(b, "(x) Back").unwrap()),
+        )
+        .add_hotkey_char('x'),
+    );
+    builder.build_cf()
+}
+
+const SEED_ENTRY_WIDTH: u32 = 40;
+
+/// Longest seed code that can be typed in; eight words of the longest length
+const SEED_ENTRY_MAX_LEN: usize = 80;
+
+/// A line of text for typing in a seed code
+#[derive(Default)]
+struct SeedEntryComponent {
+    text: String,
+    invalid: bool,
+}
+
+impl Component for SeedEntryComponent {
+    type Output = Option<u64>;
+    type State = GameLoopData;
+
+    fn render(&self, _state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        StyledString::plain_text("Enter a seed code:".to_string()).render(&(), ctx, fb);
+        StyledString {
+            string: self.text.clone(),
+            style: Style::plain_text().with_bold(true),
+        }
+        .wrap_char()
+        .render(&(), ctx.add_y(2), fb);
+        let cursor = self.text.chars().count() as u32;
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord::new(
+                (cursor % SEED_ENTRY_WIDTH) as i32,
+                2 + (cursor / SEED_ENTRY_WIDTH) as i32,
+            ),
+            0,
+            RenderCell::BLANK.with_background(Rgba32::new_grey(127)),
+        );
+        let hint = if self.invalid {
+            "That isn't a seed code. Seed codes are words like moss-heron-cedar-drizzle."
+        } else {
+            "Press enter to play, or escape to go back."
+        };
+        StyledString {
+            string: hint.to_string(),
+            style: Style::plain_text().with_foreground(Rgba32::new_grey(127)),
+        }
+        .wrap_word()
+        .render(&(), ctx.add_y(5), fb);
+    }
+
+    fn update(&mut self, _state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        if let Some(Input::Keyboard(key)) = event.input() {
+            match key {
+                input::keys::RETURN => match seed_code::decode(&self.text) {
+                    Some(seed) => return Some(seed),
+                    None => self.invalid = true,
+                },
+                input::keys::BACKSPACE => {
+                    self.text.pop();
+                }
+                KeyboardInput::Char(ch)
+                    if !ch.is_control() && self.text.len() < SEED_ENTRY_MAX_LEN =>
+                {
+                    self.text.push(ch);
+                    self.invalid = false;
+                }
+                _ => (),
+            }
+        }
+        None
+    }
+
+    fn size(&self, _state: &Self::State, _ctx: Ctx) -> Size {
+        Size::new(SEED_ENTRY_WIDTH, 7)
+    }
+}
+
+/// Yields the seed typed in, or `None` if the player backs out
+fn seed_entry() -> AppCF<Option<u64>> {
+    cf(SeedEntryComponent::default())
+        .catch_escape_or_start()
+        .map(|result| result.ok())
+}
+
+/// Formats a number of days, e.g. "1 day" or "3 days"
+fn days(count: u32) -> String {
+    if count == 1 {
+        "1 day".to_string()
+    } else {
+        format!("{} days", count)
+    }
+}
+
+const DAILY_FOREST_WIDTH: u32 = 56;
+
+/// Describes today's daily forest and the player's history of daily forests
+struct DailyForestComponent {
+    today: Date,
+}
+
+impl Component for DailyForestComponent {
+    type Output = Option<Date>;
+    type State = GameLoopData;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        let history = &state.daily_history;
+        let grey = Style::plain_text().with_foreground(Rgba32::new_grey(127));
+        let today_result = match history.result(self.today) {
+            Some(result) => result.to_string(),
+            None => "Not played yet".to_string(),
+        };
+        let mut lines = vec![
+            (
+                format!("Daily Forest for {}", self.today),
+                Style::plain_text().with_bold(true),
+            ),
+            (
+                format!("Conditions: {}", self.today.difficulty()),
+                Style::plain_text(),
+            ),
+            (String::new(), Style::plain_text()),
+            (format!("Today: {}", today_result), Style::plain_text()),
+            (
+                format!(
+                    "Current streak: {}",
+                    days(history.current_streak(self.today))
+                ),
+                Style::plain_text(),
+            ),
+            (
+                format!("Best streak: {}", days(history.best_streak())),
+                Style::plain_text(),
+            ),
+            (String::new(), Style::plain_text()),
+            ("Recent forests:".to_string(), Style::plain_text()),
+        ];
+        let mut any_recent = false;
+        for result in history.recent() {
+            any_recent = true;
+            lines.push((
+                format!(
+                    "  {}  {:<6}  {:<9}  day {}  motivation {}",
+                    result.date,
+                    result.date.difficulty().to_string(),
+                    result.outcome(),
+                    result.days,
+                    result.motivation
+                ),
+                Style::plain_text(),
+            ));
+        }
+        if !any_recent {
+            lines.push(("  (none yet)".to_string(), grey));
+        }
+        for (i, (string, style)) in lines.into_iter().enumerate() {
+            StyledString { string, style }.render(&(), ctx.add_y(i as i32), fb);
+        }
+        StyledString {
+            string: "Press enter to play, or escape to go back.".to_string(),
+            style: grey,
+        }
+        .render(&(), ctx.add_y(10 + daily::RECENT_RESULTS as i32), fb);
+    }
+
+    fn update(&mut self, _state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        if let Some(input::keys::RETURN) = event.keyboard_input() {
+            Some(self.today)
+        } else {
+            None
+        }
+    }
+
+    fn size(&self, _state: &Self::State, _ctx: Ctx) -> Size {
+        Size::new(DAILY_FOREST_WIDTH, 11 + daily::RECENT_RESULTS as u32)
+    }
+}
+
+/// Yields the date of the daily forest to play, or `None` if the player backs out
+fn daily_forest() -> AppCF<Option<Date>> {
+    cf(DailyForestComponent {
+        today: Date::today(),
+    })
+    .catch_escape_or_start()
+    .map(|result| result.ok())
+}
+
 fn title_decorate<T: 'static>(cf: AppCF<T>) -> AppCF<T> {
     cf.with_title(
         styled_string(
@@ -913,16 +1562,63 @@ fn title_decorate<T: 'static>(cf: AppCF<T>) -> AppCF<T> {
     .centre()
 }
 
+/// Explains to the player that no forest could be grown with the current config
+fn generation_failed(failure: &GenerationFailed) -> AppCF<()> {
+    popup(format!("Couldn't grow a forest: {}", failure))
+}
+
+/// Leaves the main menu to play the new game, or stays in the menu if it couldn't be generated
+fn start_new_game(
+    new_running: Result<witness::Running, GenerationFailed>,
+) -> AppCF<LoopControl<(), MainMenuOutput>> {
+    match new_running {
+        Ok(new_running) => break_(MainMenuOutput::NewGame { new_running }),
+        Err(failure) => generation_failed(&failure).continue_(),
+    }
+}
+
 const MAIN_MENU_TEXT_WIDTH: u32 = 40;
 fn main_menu_loop() -> AppCF<MainMenuOutput> {
     use MainMenuEntry::*;
-    title_decorate(main_menu()).repeat_unit(move |entry| match entry {
-        NewGame => on_state(|state: &mut State| MainMenuOutput::NewGame {
-            new_running: state.new_game(),
+    on_state_then(|state: &mut State| {
+        let map_names = state
+            .maps
+            .iter()
+            .map(|map| map.name().to_string())
+            .collect::<Vec<_>>();
+        title_decorate(main_menu(!map_names.is_empty())).repeat_unit(move |entry| match entry {
+            NewGame => on_state_then(|state: &mut State| start_new_game(state.new_game())),
+            Daily => {
+                title_decorate(daily_forest()).and_then_side_effect(|date, state: &mut State| {
+                    match date {
+                        Some(date) => start_new_game(state.new_daily_game(date)),
+                        None => continue_(()),
+                    }
+                })
+            }
+            Endless => on_state_then(|state: &mut State| start_new_game(state.new_endless_game())),
+            EnterSeed => {
+                title_decorate(seed_entry()).and_then_side_effect(|seed, state: &mut State| {
+                    match seed {
+                        Some(seed) => start_new_game(state.new_game_with_seed(seed)),
+                        None => continue_(()),
+                    }
+                })
+            }
+            CustomMaps => title_decorate(custom_map_menu(&map_names)).and_then_side_effect(
+                |index, state: &mut State| match index {
+                    Some(index) => start_new_game(state.new_custom_map_game(index)),
+                    None => continue_(()),
+                },
+            ),
+            Editor => on_state(|state: &mut State| {
+                state.open_editor();
+                MainMenuOutput::Editor
+            })
+            .break_(),
+            Help => text::help(MAIN_MENU_TEXT_WIDTH).centre().continue_(),
+            Quit => val_once(MainMenuOutput::Quit).break_(),
         })
-        .break_(),
-        Help => text::help(MAIN_MENU_TEXT_WIDTH).centre().continue_(),
-        Quit => val_once(MainMenuOutput::Quit).break_(),
     })
 }
 
@@ -963,38 +1659,129 @@ fn pause_menu() -> AppCF<PauseMenuEntry> {
 fn pause_menu_loop(running: witness::Running) -> AppCF<PauseOutput> {
     use PauseMenuEntry::*;
     let text_width = 64;
-    menu_style(
-        pause_menu()
-            .menu_harness()
-            .repeat(
-                running,
-                move |running, entry_or_escape| match entry_or_escape {
-                    Ok(entry) => match entry {
-                        Resume => break_(PauseOutput::ContinueGame { running }),
-                        SaveQuit => on_state(|state: &mut State| {
-                            state.save_instance(running);
-                            PauseOutput::Quit
-                        })
-                        .break_(),
-                        Save => on_state(|state: &mut State| PauseOutput::ContinueGame {
-                            running: state.save_instance(running),
-                        })
-                        .break_(),
-                        NewGame => on_state(|state: &mut State| PauseOutput::ContinueGame {
-                            running: state.new_game(),
-                        })
-                        .break_(),
-                        Help => text::help(text_width).continue_with(running),
-                        Clear => on_state(|state: &mut State| {
-                            state.clear_saved_game();
-                            PauseOutput::MainMenu
-                        })
-                        .break_(),
+    on_state_then(move |state: &mut State| {
+        let seed = StyledString {
+            string: state
+                .seed_code()
+                .map(|code| format!("Seed: {}", code))
+                .unwrap_or_default(),
+            style: Style::plain_text().with_foreground(Rgba32::new_grey(127)),
+        };
+        menu_style(
+            pause_menu()
+                .with_title(cf(seed).ignore_state(), 1)
+                .menu_harness()
+                .repeat(
+                    running,
+                    move |running, entry_or_escape| match entry_or_escape {
+                        Ok(entry) => match entry {
+                            Resume => break_(PauseOutput::ContinueGame { running }),
+                            SaveQuit => on_state(|state: &mut State| {
+                                state.save_instance(running);
+                                PauseOutput::Quit
+                            })
+                            .break_(),
+                            Save => on_state(|state: &mut State| PauseOutput::ContinueGame {
+                                running: state.save_instance(running),
+                            })
+                            .break_(),
+                            NewGame => on_state_then(|state: &mut State| match state.new_game() {
+                                Ok(running) => break_(PauseOutput::ContinueGame { running }),
+                                // the current game carries on if a new one couldn't be generated
+                                Err(failure) => generation_failed(&failure).continue_with(running),
+                            }),
+                            Help => text::help(text_width).continue_with(running),
+                            Clear => on_state(|state: &mut State| {
+                                state.clear_saved_game();
+                                PauseOutput::MainMenu
+                            })
+                            .break_(),
+                        },
+                        Err(_escape_or_start) => break_(PauseOutput::ContinueGame { running }),
                     },
-                    Err(_escape_or_start) => break_(PauseOutput::ContinueGame { running }),
-                },
-            ),
-    )
+                ),
+        )
+    })
+}
+
+#[derive(Clone)]
+enum EditorMenuEntry {
+    Resume,
+    Save,
+    SaveQuit,
+    Quit,
+    NewMap,
+    Help,
+}
+
+enum EditorMenuOutput {
+    Resume,
+    MainMenu,
+}
+
+fn editor_menu() -> AppCF<EditorMenuEntry> {
+    use menu::builder::*;
+    use EditorMenuEntry::*;
+    let mut builder = menu_builder().vi_keys();
+    let mut add_item = |entry, name, ch: char| {
+        let identifier =
+            MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+        builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
+    };
+    add_item(Resume, "Resume", 'r');
+    add_item(Save, "Save", 's');
+    add_item(SaveQuit, "Save and Quit", 'q');
+    add_item(Quit, "Quit Without Saving", 'x');
+    add_item(NewMap, "New Map", 'n');
+    add_item(Help, "Help", 'h');
+    builder.build_cf()
+}
+
+fn editor_menu_loop() -> AppCF<EditorMenuOutput> {
+    use EditorMenuEntry::*;
+    let text_width = 64;
+    editor_menu()
+        .menu_harness()
+        .repeat_unit(move |entry_or_escape| match entry_or_escape {
+            Ok(entry) => match entry {
+                Resume => break_(EditorMenuOutput::Resume),
+                Save => on_state(|state: &mut State| {
+                    state.save_editor_map();
+                    EditorMenuOutput::Resume
+                })
+                .break_(),
+                SaveQuit => on_state(|state: &mut State| {
+                    state.save_editor_map();
+                    state.editor = None;
+                    EditorMenuOutput::MainMenu
+                })
+                .break_(),
+                Quit => on_state(|state: &mut State| {
+                    state.editor = None;
+                    EditorMenuOutput::MainMenu
+                })
+                .break_(),
+                NewMap => on_state(|state: &mut State| {
+                    state.new_editor_map();
+                    EditorMenuOutput::Resume
+                })
+                .break_(),
+                Help => text::editor_help(text_width).continue_(),
+            },
+            Err(_escape_or_start) => break_(EditorMenuOutput::Resume),
+        })
+        .border(BorderStyle::default())
+        .fill(Rgba32::new_grey(0))
+        .centre()
+        .overlay_tint(
+            render_state(|state: &State, ctx, fb| {
+                if let Some(editor) = state.editor.as_ref() {
+                    editor.render(ctx, fb);
+                }
+            }),
+            chargrid::core::TintDim(63),
+            10,
+        )
 }
 
 fn yes_no_menu() -> AppCF<bool> {
@@ -1046,6 +1833,87 @@ fn sleep_menu(sleep: witness::Sleep) -> AppCF<Witness> {
     })
 }
 
+fn recipe_menu(game: &Game) -> AppCF<Option<usize>> {
+    use menu::builder::*;
+    let mut builder = menu_builder().vi_keys();
+    for (index, recipe) in RECIPES.iter().enumerate() {
+        let status = if game.crafted_today(index) {
+            " (made today)"
+        } else if game.can_craft(index) {
+            ""
+        } else {
+            " (missing ingredients)"
+        };
+        let name = format!(
+            "{} [{}]{}",
+            recipe.name,
+            recipe.ingredients_string(),
+            status
+        );
+        match std::char::from_digit(index as u32 + 1, 10) {
+            Some(ch) => {
+                let identifier =
+                    MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+                builder.add_item_mut(item(Some(index), identifier).add_hotkey_char(ch));
+            }
+            None => {
+                let identifier =
+                    MENU_FADE_SPEC.identifier(move |b| write!(b, "    {}", name).unwrap());
+                builder.add_item_mut(item(Some(index), identifier));
+            }
+        }
+    }
+    builder.add_item_mut(
+        item(
+            None,
+            MENU_FADE_SPEC.identifier(move |b| write!(b, "(x) Leave").unwrap()),
+        )
+        .add_hotkey_char('x'),
+    );
+    builder.build_cf()
+}
+
+fn craft_menu(craft: witness::Craft) -> AppCF<Witness> {
+    on_state_then(|state: &mut State| {
+        let game = &state.instance.as_ref().unwrap().game;
+        let title = format!(
+            "What will you make at the kitchen bench?\n\nOn the bench: {}\nFish caught: {}",
+            game.pantry_contents(),
+            game.fish_held()
+        );
+        menu_style(
+            recipe_menu(game).with_title(
+                cf(StyledString {
+                    string: title,
+                    style: Style::plain_text(),
+                }
+                .wrap_word())
+                .ignore_state()
+                .bound_width(60),
+                1,
+            ),
+        )
+    })
+    .map_side_effect(|index, state: &mut State| match index {
+        Some(index) => {
+            let instance = state.instance.as_mut().unwrap();
+            craft.commit(&mut instance.game, index, &state.game_config)
+        }
+        None => craft.cancel(),
+    })
+}
+
+fn enter_lake_menu(enter_lake: witness::EnterLake) -> AppCF<Witness> {
+    yes_no(enter_lake.message()).map_side_effect(|yes, state: &mut State| {
+        if yes {
+            let instance = state.instance.as_mut().unwrap();
+            enter_lake.commit(&mut instance.game, &state.game_config)
+        } else {
+            enter_lake.cancel()
+        }
+    })
+}
+
 fn popup_delay(string: String) -> AppCF<()> {
     popup_style(
         StyledString {
@@ -1089,12 +1957,39 @@ fn prompt(prompt_witness: witness::Prompt) -> AppCF<Witness> {
     })
 }
 
+/// Records the result of a daily forest, and describes it for the end of game popup
+fn daily_summary(state: &mut State, survived: bool) -> String {
+    state
+        .record_daily_result(survived)
+        .map(|summary| format!("\n\n{}", summary))
+        .unwrap_or_default()
+}
+
+/// Shown at the end of a game so the same forest can be played again
+fn seed_summary(state: &State) -> String {
+    state
+        .seed_code()
+        .map(|code| format!("\n\nSeed: {}", code))
+        .unwrap_or_default()
+}
+
+fn catch_summary(state: &State) -> String {
+    state
+        .instance
+        .as_ref()
+        .map(|instance| instance.game.catch_summary())
+        .unwrap_or_default()
+}
+
 fn game_over() -> AppCF<()> {
     on_state_then(move |state: &mut State| {
         state.examine_message = None;
         state.cursor = None;
         state.clear_saved_game();
-        popup_delay("You tire of trudging through the flooded forest in the rain. You pack up your belongings and return home.".to_string())
+        let catch_summary = catch_summary(state);
+        let seed_summary = seed_summary(state);
+        let daily_summary = daily_summary(state, false);
+        popup_delay(format!("You tire of trudging through the flooded forest in the rain. You pack up your belongings and return home.\n\n{}{}{}", catch_summary, seed_summary, daily_summary))
     })
 }
 
@@ -1103,7 +1998,10 @@ fn win() -> AppCF<()> {
         state.examine_message = None;
         state.cursor = None;
         state.clear_saved_game();
-        popup_delay("After five days enjoying the forest in the rain, it's time to return to your life. This break was just what you needed.".to_string())
+        let catch_summary = catch_summary(state);
+        let seed_summary = seed_summary(state);
+        let daily_summary = daily_summary(state, true);
+        popup_delay(format!("After five days enjoying the forest in the rain, it's time to return to your life. This break was just what you needed.\n\n{}{}{}", catch_summary, seed_summary, daily_summary))
     })
 }
 
@@ -1113,6 +2011,8 @@ pub fn game_loop_component(initial_state: GameLoopState) -> AppCF<()> {
         Playing(witness) => match witness {
             Witness::Running(running) => game_instance_component(running).continue_(),
             Witness::Sleep(sleep) => sleep_menu(sleep).map(Playing).continue_(),
+            Witness::Craft(craft) => craft_menu(craft).map(Playing).continue_(),
+            Witness::EnterLake(enter_lake) => enter_lake_menu(enter_lake).map(Playing).continue_(),
             Witness::Prompt(prompt_witness) => prompt(prompt_witness).map(Playing).continue_(),
             Witness::GameOver => game_over().map_val(|| MainMenu).continue_(),
             Witness::Win => win().map_val(|| MainMenu).continue_(),
@@ -1137,7 +2037,14 @@ pub fn game_loop_component(initial_state: GameLoopState) -> AppCF<()> {
             MainMenuOutput::NewGame { new_running } => {
                 LoopControl::Continue(Playing(new_running.into_witness()))
             }
+            MainMenuOutput::Editor => LoopControl::Continue(Editor),
             MainMenuOutput::Quit => LoopControl::Break(()),
         }),
+        Editor => editor_component().map_val(|| EditorMenu).continue_(),
+        EditorMenu => editor_menu_loop().map(|editor_menu_output| match editor_menu_output {
+            EditorMenuOutput::Resume => LoopControl::Continue(Editor),
+            EditorMenuOutput::MainMenu => LoopControl::Continue(MainMenu),
+        }),
+        GenerationFailed(failure) => generation_failed(&failure).map_val(|| MainMenu).continue_(),
     })
 }
@@ -1,19 +1,25 @@
 use gridbugs::chargrid::control_flow::*;
+use gridbugs::grid_2d::Coord;
 use gridbugs::storage::{format, Storage};
-use rainforest_game::Config as GameConfig;
+use rainforest_game::{Chunk, Config as GameConfig};
+pub use rainforest_game::{CustomMap, TerrainConfig};
 
 mod colour;
 mod controls;
+mod daily;
+mod editor;
 mod examine;
 mod fields;
 mod game;
 mod game_loop;
 mod mist;
 mod rain;
+mod seed_code;
 mod text;
 mod tile_3x3;
 
 use controls::Controls;
+use daily::DailyHistory;
 use game_loop::GameInstanceStorable;
 
 pub enum InitialRngSeed {
@@ -21,15 +27,28 @@ pub enum InitialRngSeed {
     Random,
 }
 
+impl InitialRngSeed {
+    /// Reads a seed code as shown on the pause menu, or a seed given as a plain number
+    pub fn from_code(code: &str) -> Option<Self> {
+        seed_code::decode(code).map(Self::U64)
+    }
+}
+
 pub struct AppStorage {
     pub handle: Storage,
     pub save_game_key: String,
     pub controls_key: String,
+    /// Where the map editor keeps the map being edited, in the custom map format
+    pub editor_map_key: String,
+    /// Results of past daily forests
+    pub daily_history_key: String,
 }
 
 impl AppStorage {
     const SAVE_GAME_STORAGE_FORMAT: format::Bincode = format::Bincode;
     const CONTROLS_STORAGE_FORMAT: format::JsonPretty = format::JsonPretty;
+    const DAILY_HISTORY_STORAGE_FORMAT: format::JsonPretty = format::JsonPretty;
+    const CHUNK_STORAGE_FORMAT: format::Bincode = format::Bincode;
 
     fn save_game(&mut self, instance: &GameInstanceStorable) {
         let result = self.handle.store(
@@ -87,6 +106,69 @@ impl AppStorage {
         }
     }
 
+    /// Chunks of an endless forest are stored alongside the save file, under keys that include
+    /// the id of the game they belong to and the generation they were written in
+    fn chunk_key(&self, game_id: u64, generation: u64, coord: Coord) -> String {
+        format!(
+            "{}_chunk_{:016x}_{}_{}_{}",
+            self.save_game_key, game_id, generation, coord.x, coord.y
+        )
+    }
+
+    fn save_chunk(&mut self, game_id: u64, generation: u64, chunk: &Chunk) {
+        let key = self.chunk_key(game_id, generation, chunk.coord());
+        let result = self.handle.store(&key, chunk, Self::CHUNK_STORAGE_FORMAT);
+        if let Err(e) = result {
+            use gridbugs::storage::{StoreError, StoreRawError};
+            match e {
+                StoreError::FormatError(e) => log::error!("Failed to format chunk: {}", e),
+                StoreError::Raw(e) => match e {
+                    StoreRawError::IoError(e) => {
+                        log::error!("Error while writing chunk: {}", e)
+                    }
+                },
+            }
+        }
+    }
+
+    fn load_chunk(&self, game_id: u64, generation: u64, coord: Coord) -> Option<Chunk> {
+        let result = self.handle.load::<_, Chunk, _>(
+            &self.chunk_key(game_id, generation, coord),
+            Self::CHUNK_STORAGE_FORMAT,
+        );
+        match result {
+            Err(e) => {
+                use gridbugs::storage::{LoadError, LoadRawError};
+                match e {
+                    LoadError::FormatError(e) => log::error!("Failed to parse chunk: {}", e),
+                    LoadError::Raw(e) => match e {
+                        LoadRawError::IoError(e) => {
+                            log::error!("Error while reading chunk: {}", e)
+                        }
+                        LoadRawError::NoSuchKey => (),
+                    },
+                }
+                None
+            }
+            Ok(chunk) => Some(chunk),
+        }
+    }
+
+    fn remove_chunk(&mut self, game_id: u64, generation: u64, coord: Coord) {
+        let key = self.chunk_key(game_id, generation, coord);
+        if self.handle.exists(&key) {
+            if let Err(e) = self.handle.remove(&key) {
+                use gridbugs::storage::RemoveError;
+                match e {
+                    RemoveError::IoError(e) => {
+                        log::error!("Error while removing data: {}", e)
+                    }
+                    RemoveError::NoSuchKey => (),
+                }
+            }
+        }
+    }
+
     fn save_controls(&mut self, controls: &Controls) {
         let result =
             self.handle
@@ -127,6 +209,86 @@ impl AppStorage {
             Ok(instance) => Some(instance),
         }
     }
+
+    fn save_daily_history(&mut self, history: &DailyHistory) {
+        let result = self.handle.store(
+            &self.daily_history_key,
+            &history,
+            Self::DAILY_HISTORY_STORAGE_FORMAT,
+        );
+        if let Err(e) = result {
+            use gridbugs::storage::{StoreError, StoreRawError};
+            match e {
+                StoreError::FormatError(e) => {
+                    log::error!("Failed to format daily history: {}", e)
+                }
+                StoreError::Raw(e) => match e {
+                    StoreRawError::IoError(e) => {
+                        log::error!("Error while writing daily history: {}", e)
+                    }
+                },
+            }
+        }
+    }
+
+    fn load_daily_history(&self) -> Option<DailyHistory> {
+        let result = self.handle.load::<_, DailyHistory, _>(
+            &self.daily_history_key,
+            Self::DAILY_HISTORY_STORAGE_FORMAT,
+        );
+        match result {
+            Err(e) => {
+                use gridbugs::storage::{LoadError, LoadRawError};
+                match e {
+                    LoadError::FormatError(e) => {
+                        log::error!("Failed to parse daily history: {}", e)
+                    }
+                    LoadError::Raw(e) => match e {
+                        LoadRawError::IoError(e) => {
+                            log::error!("Error while reading daily history: {}", e)
+                        }
+                        LoadRawError::NoSuchKey => (),
+                    },
+                }
+                None
+            }
+            Ok(history) => Some(history),
+        }
+    }
+
+    fn save_editor_map(&mut self, map: &CustomMap) {
+        let result = self.handle.store_raw(&self.editor_map_key, map.to_text());
+        if let Err(e) = result {
+            use gridbugs::storage::StoreRawError;
+            match e {
+                StoreRawError::IoError(e) => log::error!("Error while writing map: {}", e),
+            }
+        }
+    }
+
+    fn load_editor_map(&self) -> Option<CustomMap> {
+        let result = self.handle.load_raw(&self.editor_map_key);
+        match result {
+            Err(e) => {
+                use gridbugs::storage::LoadRawError;
+                match e {
+                    LoadRawError::IoError(e) => log::error!("Error while reading map: {}", e),
+                    LoadRawError::NoSuchKey => (),
+                }
+                None
+            }
+            Ok(bytes) => match String::from_utf8(bytes)
+                .map_err(|e| e.to_string())
+                .and_then(|text| CustomMap::parse(&text))
+            {
+                Err(e) => {
+                    log::error!("Failed to parse map: {}", e);
+                    None
+                }
+                Ok(map) => Some(map),
+            },
+        }
+    }
 }
 
 pub struct AppArgs {
@@ -134,6 +296,9 @@ pub struct AppArgs {
     pub initial_rng_seed: InitialRngSeed,
     pub omniscient: bool,
     pub new_game: bool,
+    pub terrain_config: TerrainConfig,
+    /// Hand-authored maps that can be chosen from the main menu
+    pub maps: Vec<CustomMap>,
 }
 
 pub fn app(
@@ -142,14 +307,19 @@ pub fn app(
         initial_rng_seed,
         omniscient,
         new_game,
+        terrain_config,
+        maps,
     }: AppArgs,
 ) -> App {
     let config = GameConfig {
         omniscient,
         debug: false,
+        terrain: terrain_config,
+        map: None,
+        endless: false,
     };
     let (game_loop_data, initial_state) =
-        game_loop::GameLoopData::new(config, storage, initial_rng_seed, new_game);
+        game_loop::GameLoopData::new(config, maps, storage, initial_rng_seed, new_game);
     game_loop::game_loop_component(initial_state)
         .map(|_| app::Exit)
         .with_state(game_loop_data)
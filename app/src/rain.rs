@@ -157,6 +157,12 @@ impl Rain {
         }
     }
 
+    /// The cells where rain is hidden change when the world of the endless forest moves along, or
+    /// when the player goes to another level
+    pub fn refresh_hide_table(&mut self, game: &Game) {
+        self.hide_table = Grid::new_fn(game.world_size(), |coord| game.should_hide_rain(coord));
+    }
+
     pub fn update(&mut self, num_drops: usize, direction: RainDirection) {
         self.num_drops = num_drops;
         self.direction = direction;
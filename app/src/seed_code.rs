@@ -0,0 +1,100 @@
+//! Seeds written as a few words, so they're easy to read out to a friend and type back in
+
+/// Each word stands for one byte of a seed. The list is sorted so words can be found with a
+/// binary search.
+const WORDS: [&str; 256] = [
+    "acorn", "alder", "amber", "anchor", "apple", "arbor", "ash", "aspen", "autumn", "badger",
+    "bamboo", "bark", "basin", "bay", "beach", "bear", "beaver", "bee", "beetle", "berry", "birch",
+    "bison", "bloom", "blossom", "bluff", "bog", "boulder", "bramble", "branch", "breeze", "brook",
+    "brush", "bud", "burrow", "cabin", "canopy", "canyon", "cedar", "chestnut", "cliff", "cloud",
+    "clover", "coast", "cobble", "cocoa", "comet", "coral", "cove", "crane", "creek", "crest",
+    "cricket", "crow", "current", "cypress", "dale", "dawn", "deer", "delta", "dew", "drift",
+    "drizzle", "dune", "dusk", "eagle", "echo", "eel", "elder", "elk", "elm", "ember", "fawn",
+    "feather", "fern", "field", "finch", "fir", "firefly", "flint", "flower", "fog", "ford",
+    "forest", "fox", "frog", "frost", "gale", "garden", "gecko", "glade", "glen", "gorge", "grass",
+    "grove", "gull", "gumboot", "gust", "hail", "harbor", "hare", "hawk", "hazel", "heath",
+    "heather", "heron", "hill", "hollow", "honey", "hummock", "ibis", "inlet", "iris", "island",
+    "ivy", "jade", "jay", "juniper", "kauri", "kelp", "kestrel", "kettle", "kite", "kiwi",
+    "lagoon", "lake", "lantern", "larch", "lark", "laurel", "leaf", "lichen", "lily", "lizard",
+    "lodge", "loon", "lotus", "lynx", "magpie", "maple", "marsh", "meadow", "minnow", "mist",
+    "moon", "moor", "moose", "moss", "moth", "mountain", "mouse", "mud", "mushroom", "nest",
+    "nettle", "newt", "night", "oak", "oasis", "ocean", "orchid", "otter", "owl", "paddle", "palm",
+    "pebble", "pelican", "pepper", "petal", "pier", "pine", "plover", "pond", "poplar", "poppy",
+    "puddle", "pumice", "quail", "quartz", "rabbit", "rain", "raven", "reed", "ridge", "rimu",
+    "river", "robin", "rock", "rose", "rowan", "rush", "sage", "salmon", "sand", "sapling",
+    "sedge", "seed", "shade", "shell", "shore", "shrub", "silt", "sky", "slope", "snail", "snow",
+    "sparrow", "spring", "sprout", "spruce", "squall", "star", "stone", "storm", "stream",
+    "summit", "sun", "swallow", "swamp", "swan", "tarn", "teal", "teapot", "thicket", "thistle",
+    "thorn", "thrush", "thunder", "tide", "timber", "toad", "torrent", "totara", "trail", "trout",
+    "tui", "tulip", "tundra", "twig", "umbrella", "valley", "vine", "violet", "vole", "walnut",
+    "wasp", "water", "wave", "weasel", "willow", "wind", "wolf", "wood", "wren", "yarrow", "yew",
+    "zephyr",
+];
+
+/// Writes a seed as one word per byte, most significant first, leaving out leading zero bytes
+pub fn encode(seed: u64) -> String {
+    let bytes = seed.to_be_bytes();
+    let first = bytes
+        .iter()
+        .position(|&byte| byte != 0)
+        .unwrap_or(bytes.len() - 1);
+    bytes[first..]
+        .iter()
+        .map(|&byte| WORDS[byte as usize])
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Reads a seed code in any case, with its words separated by hyphens, spaces or commas. A seed
+/// given as a plain number is accepted too.
+pub fn decode(code: &str) -> Option<u64> {
+    let code = code.trim();
+    if let Ok(seed) = code.parse::<u64>() {
+        return Some(seed);
+    }
+    let words = code
+        .split(|ch: char| ch == '-' || ch == ',' || ch.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    if words.is_empty() || words.len() > 8 {
+        return None;
+    }
+    words.iter().try_fold(0, |seed: u64, word| {
+        let byte = WORDS.binary_search(&word.to_lowercase().as_str()).ok()?;
+        Some((seed << 8) | byte as u64)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn words_are_sorted() {
+        assert!(WORDS.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        for seed in [0, 1, 255, 256, 0x1234_5678, u64::MAX] {
+            assert_eq!(decode(&encode(seed)), Some(seed));
+        }
+        assert_eq!(encode(0), "acorn");
+        assert_eq!(encode(257), "alder-alder");
+    }
+
+    #[test]
+    fn decode_is_forgiving() {
+        assert_eq!(decode("  Alder, ALDER "), Some(257));
+        assert_eq!(decode("alder alder"), Some(257));
+        assert_eq!(decode("257"), Some(257));
+    }
+
+    #[test]
+    fn reject_bad_codes() {
+        assert_eq!(decode("alder-dragon"), None);
+        assert_eq!(decode(""), None);
+        assert_eq!(decode("- ,"), None);
+        assert_eq!(decode(&["acorn"; 9].join("-")), None);
+    }
+}
@@ -42,6 +42,47 @@ pub fn help(width: u32) -> AppCF<()> {
             t("Lantern: f\n"),
             t("Toggle rock pushing mode: p\n"),
             t("Dig ditch: e\n"),
+            t("Chop fallen log: c\n"),
+            t("Fish: F\n"),
+            f("\n\nPress any key..."),
+        ],
+    )
+}
+
+pub fn editor_help(width: u32) -> AppCF<()> {
+    let normal = Style::plain_text();
+    let faint = Style::plain_text().with_foreground(Rgba32::new_grey(127));
+    let f = |s: &str| StyledString {
+        string: s.to_string(),
+        style: faint,
+    };
+    let b = |s: &str| StyledString {
+        string: s.to_string(),
+        style: normal.with_bold(true),
+    };
+    let t = |s: &str| StyledString {
+        string: s.to_string(),
+        style: normal,
+    };
+    text_component(
+        width,
+        vec![
+            b("Map Editor Controls\n"),
+            t("Move cursor: Arrows/wasd/hjkl\n"),
+            t("Move cursor quickly: shift + wasd/hjkl\n"),
+            t("Use brush: Space or left click (drag to paint)\n"),
+            t("Previous/next brush: [ and ] or scroll wheel\n"),
+            t("Shrink/grow brush: - and +\n"),
+            t("Toggle lighting preview: v\n"),
+            t("Preview an hour earlier/later: comma and period\n"),
+            t("Start the map at the previewed hour: t\n"),
+            t("Menu: Escape\n"),
+            t(
+                "\nPainting replaces whatever is in the same layer (floor, feature or item). \
+              Erase removes the last thing painted in each cell. \
+              The map is saved in the custom map format described in the README, \
+              and can be played from Custom Maps on the main menu.\n",
+            ),
             f("\n\nPress any key..."),
         ],
     )
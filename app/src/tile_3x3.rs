@@ -5,9 +5,10 @@ use crate::{
 use gridbugs::{
     chargrid::core::prelude::*,
     coord_2d::{Axis, Coord, Size},
+    direction::CardinalDirection,
     rgb_int::Rgb24,
 };
-use rainforest_game::{EntityTile, Game, Tile, VisibilityCell};
+use rainforest_game::{EntityTile, Footprint, Game, Tile, VisibilityCell, FOOTPRINT_STRENGTH};
 
 pub fn render_3x3_from_visibility(
     screen_coord: Coord,
@@ -78,7 +79,32 @@ pub fn render_3x3_from_visibility(
         Tile::WeatherReport => weather_report(ctx, fb),
         Tile::Lantern => lantern(ctx, fb),
         Tile::Crowbar => crowbar(ctx, fb),
+        Tile::Axe => axe(ctx, fb),
+        Tile::Mud => mud(world_coord, ctx, fb),
+        Tile::Puddle => puddle(ctx, fb),
+        Tile::Boat => boat(ctx, fb),
+        Tile::Cairn => cairn(ctx, fb),
+        Tile::FishingRod => fishing_rod(ctx, fb),
+        Tile::Stove => stove(ctx, fb),
+        Tile::Mushroom => mushroom(ctx, fb),
+        Tile::KitchenBench => kitchen_bench(ctx, fb),
+        Tile::Trail => trail(world_coord, ctx, fb),
+        Tile::Signpost => signpost(ctx, fb),
+        Tile::Stream => stream(world_coord, false, ctx, fb),
+        Tile::Ford => stream(world_coord, true, ctx, fb),
+        Tile::Bridge => pier_floor(ctx, fb),
+        Tile::Lookout => lookout(ctx, fb),
+        Tile::TowerLeg => tower_leg(ctx, fb),
+        Tile::Table => table(ctx, fb),
+        Tile::Bookshelf => bookshelf(ctx, fb),
+        Tile::Ladder => ladder(ctx, fb),
+        Tile::Hatch => hatch(ctx, fb),
+        Tile::StairsDown => stairs(true, ctx, fb),
+        Tile::StairsUp => stairs(false, ctx, fb),
+        Tile::Firewood => firewood(ctx, fb),
         Tile::Ditch => ditch(ctx, fb),
+        Tile::Log(Axis::X) => log_x(ctx, fb),
+        Tile::Log(Axis::Y) => log_y(ctx, fb),
     };
     let tile_layers = visibility_cell.tile_layers();
     if let Some(EntityTile { entity, tile }) = tile_layers.floor {
@@ -93,6 +119,9 @@ pub fn render_3x3_from_visibility(
     if let Some(EntityTile { entity, tile }) = tile_layers.character {
         render_tile(entity, tile, ctx.add_depth(3));
     }
+    if let Some(footprint) = game.footprint(world_coord) {
+        render_footprint(footprint, ctx, fb);
+    }
 }
 
 pub fn render_3x3_from_visibility_remembered(
@@ -161,7 +190,32 @@ pub fn render_3x3_from_visibility_remembered(
         Tile::WeatherReport => weather_report(ctx, fb),
         Tile::Lantern => lantern(ctx, fb),
         Tile::Crowbar => crowbar(ctx, fb),
+        Tile::Axe => axe(ctx, fb),
+        Tile::Mud => mud(world_coord, ctx, fb),
+        Tile::Puddle => puddle(ctx, fb),
+        Tile::Boat => boat(ctx, fb),
+        Tile::Cairn => cairn(ctx, fb),
+        Tile::FishingRod => fishing_rod(ctx, fb),
+        Tile::Stove => stove(ctx, fb),
+        Tile::Mushroom => mushroom(ctx, fb),
+        Tile::KitchenBench => kitchen_bench(ctx, fb),
+        Tile::Trail => trail(world_coord, ctx, fb),
+        Tile::Signpost => signpost(ctx, fb),
+        Tile::Stream => stream(world_coord, false, ctx, fb),
+        Tile::Ford => stream(world_coord, true, ctx, fb),
+        Tile::Bridge => pier_floor(ctx, fb),
+        Tile::Lookout => lookout(ctx, fb),
+        Tile::TowerLeg => tower_leg(ctx, fb),
+        Tile::Table => table(ctx, fb),
+        Tile::Bookshelf => bookshelf(ctx, fb),
+        Tile::Ladder => ladder(ctx, fb),
+        Tile::Hatch => hatch(ctx, fb),
+        Tile::StairsDown => stairs(true, ctx, fb),
+        Tile::StairsUp => stairs(false, ctx, fb),
+        Tile::Firewood => firewood(ctx, fb),
         Tile::Ditch => ditch(ctx, fb),
+        Tile::Log(Axis::X) => log_x(ctx, fb),
+        Tile::Log(Axis::Y) => log_y(ctx, fb),
     };
     let tile_layers = visibility_cell.tile_layers();
     if let Some(EntityTile { entity: _, tile }) = tile_layers.floor {
@@ -176,6 +230,7 @@ pub fn render_3x3_from_visibility_remembered(
     if let Some(EntityTile { entity: _, tile }) = tile_layers.character {
         render_tile(tile, ctx.add_depth(3));
     }
+    // footprints aren't remembered, as they come and go out of sight
 }
 
 fn floor(ctx: Ctx, fb: &mut FrameBuffer) {
@@ -1313,6 +1368,51 @@ fn flower(ctx: Ctx, fb: &mut FrameBuffer) {
     );
 }
 
+fn kitchen_bench(ctx: Ctx, fb: &mut FrameBuffer) {
+    for x in 0..3 {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord::new(x, 1),
+            0,
+            RenderCell::default()
+                .with_character('▀')
+                .with_foreground(colour::KITCHEN_BENCH_TOP)
+                .with_background(colour::KITCHEN_BENCH),
+        );
+    }
+    for x in [0, 2] {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord::new(x, 2),
+            0,
+            RenderCell::default()
+                .with_character('█')
+                .with_foreground(colour::KITCHEN_BENCH),
+        );
+    }
+}
+
+fn mushroom(ctx: Ctx, fb: &mut FrameBuffer) {
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord::new(1, 1),
+        0,
+        RenderCell::default()
+            .with_character('▲')
+            .with_bold(true)
+            .with_foreground(colour::MUSHROOM_CAP),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord::new(1, 2),
+        0,
+        RenderCell::default()
+            .with_character('│')
+            .with_bold(true)
+            .with_foreground(colour::MUSHROOM_STEM),
+    );
+}
+
 fn bed(ctx: Ctx, fb: &mut FrameBuffer) {
     fb.set_cell_relative_to_ctx(
         ctx,
@@ -1857,6 +1957,65 @@ fn crowbar(ctx: Ctx, fb: &mut FrameBuffer) {
     );
 }
 
+fn axe(ctx: Ctx, fb: &mut FrameBuffer) {
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord::new(0, 0),
+        0,
+        RenderCell::default()
+            .with_character('◢')
+            .with_bold(true)
+            .with_foreground(colour::AXE_HEAD),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord::new(1, 0),
+        0,
+        RenderCell::default()
+            .with_character('┬')
+            .with_bold(true)
+            .with_foreground(colour::AXE_HEAD),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord::new(1, 1),
+        0,
+        RenderCell::default()
+            .with_character('│')
+            .with_bold(true)
+            .with_foreground(colour::AXE_HANDLE),
+    );
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord::new(1, 2),
+        0,
+        RenderCell::default()
+            .with_character('│')
+            .with_bold(true)
+            .with_foreground(colour::AXE_HANDLE),
+    );
+}
+
+fn firewood(ctx: Ctx, fb: &mut FrameBuffer) {
+    for coord in [
+        Coord::new(0, 2),
+        Coord::new(1, 2),
+        Coord::new(2, 2),
+        Coord::new(1, 1),
+    ] {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            coord,
+            0,
+            RenderCell::default()
+                .with_character('o')
+                .with_bold(true)
+                .with_foreground(colour::LOG_GRAIN)
+                .with_background(colour::LOG_BARK),
+        );
+    }
+}
+
 fn ditch(ctx: Ctx, fb: &mut FrameBuffer) {
     for coord in Size::new_u16(3, 3).coord_iter_row_major() {
         fb.set_cell_relative_to_ctx(
@@ -1871,3 +2030,408 @@ fn ditch(ctx: Ctx, fb: &mut FrameBuffer) {
         );
     }
 }
+
+fn mud(world_coord: Coord, ctx: Ctx, fb: &mut FrameBuffer) {
+    for coord in Size::new_u16(3, 3).coord_iter_row_major() {
+        // vary the texture from cell to cell without needing a field
+        let ch = if (world_coord.x * 3 + coord.x + (world_coord.y * 3 + coord.y) * 2) % 5 == 0 {
+            '~'
+        } else {
+            ' '
+        };
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            coord,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_background(colour::MUD_BACKGROUND)
+                .with_foreground(colour::MUD_FOREGROUND),
+        );
+    }
+}
+
+fn trail(world_coord: Coord, ctx: Ctx, fb: &mut FrameBuffer) {
+    for coord in Size::new_u16(3, 3).coord_iter_row_major() {
+        let ch = if (world_coord.x * 3 + coord.x + (world_coord.y * 3 + coord.y) * 3) % 7 == 0 {
+            '.'
+        } else {
+            ' '
+        };
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            coord,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_background(colour::TRAIL_BACKGROUND)
+                .with_foreground(colour::TRAIL_FOREGROUND),
+        );
+    }
+}
+
+fn stream(world_coord: Coord, ford: bool, ctx: Ctx, fb: &mut FrameBuffer) {
+    for coord in Size::new_u16(3, 3).coord_iter_row_major() {
+        let ch = if ford && (coord.x + coord.y) % 2 == 0 {
+            '●'
+        } else if (world_coord.x * 3 + coord.x + world_coord.y * 3 + coord.y) % 3 == 0 {
+            '~'
+        } else {
+            ' '
+        };
+        let foreground = if ch == '●' {
+            colour::FORD_STONE
+        } else {
+            colour::STREAM_FOREGROUND
+        };
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            coord,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_background(colour::STREAM_BACKGROUND)
+                .with_foreground(foreground),
+        );
+    }
+}
+
+fn signpost(ctx: Ctx, fb: &mut FrameBuffer) {
+    for (offset, ch) in [
+        (Coord::new(0, 0), '◄'),
+        (Coord::new(1, 0), '█'),
+        (Coord::new(2, 0), '►'),
+        (Coord::new(1, 1), '│'),
+        (Coord::new(1, 2), '│'),
+    ] {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_bold(true)
+                .with_foreground(colour::SIGNPOST),
+        );
+    }
+}
+
+fn lookout(ctx: Ctx, fb: &mut FrameBuffer) {
+    for offset in Size::new_u16(3, 3).coord_iter_row_major() {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character('#')
+                .with_foreground(colour::LOOKOUT_FOREGROUND)
+                .with_background(colour::LOOKOUT_BACKGROUND),
+        );
+    }
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord::new(1, 1),
+        0,
+        RenderCell::default()
+            .with_character('H')
+            .with_bold(true)
+            .with_foreground(colour::LOOKOUT_FOREGROUND),
+    );
+}
+
+fn tower_leg(ctx: Ctx, fb: &mut FrameBuffer) {
+    for (offset, ch) in [
+        (Coord::new(1, 0), '╳'),
+        (Coord::new(1, 1), '█'),
+        (Coord::new(1, 2), '█'),
+    ] {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_bold(true)
+                .with_foreground(colour::TOWER_LEG),
+        );
+    }
+}
+
+fn table(ctx: Ctx, fb: &mut FrameBuffer) {
+    for (offset, ch) in [
+        (Coord::new(0, 1), '▄'),
+        (Coord::new(1, 1), '▄'),
+        (Coord::new(2, 1), '▄'),
+        (Coord::new(0, 2), '▌'),
+        (Coord::new(2, 2), '▐'),
+    ] {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_foreground(colour::TABLE),
+        );
+    }
+}
+
+fn bookshelf(ctx: Ctx, fb: &mut FrameBuffer) {
+    for offset in Size::new_u16(3, 3).coord_iter_row_major() {
+        let spine = colour::BOOK_SPINES[((offset.x + offset.y) % 3) as usize];
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character('▌')
+                .with_foreground(spine)
+                .with_background(colour::BOOKSHELF),
+        );
+    }
+}
+
+fn ladder(ctx: Ctx, fb: &mut FrameBuffer) {
+    for y in 0..3 {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord::new(1, y),
+            0,
+            RenderCell::default()
+                .with_character('╪')
+                .with_bold(true)
+                .with_foreground(colour::LADDER),
+        );
+    }
+}
+
+/// The top of the ladder, poking up through a hole in the loft floor
+fn hatch(ctx: Ctx, fb: &mut FrameBuffer) {
+    for offset in Size::new_u16(3, 3).coord_iter_row_major() {
+        let cell = if offset.x == 1 {
+            RenderCell::default()
+                .with_character('╪')
+                .with_bold(true)
+                .with_foreground(colour::LADDER)
+        } else {
+            RenderCell::default().with_character(' ')
+        };
+        fb.set_cell_relative_to_ctx(ctx, offset, 0, cell.with_background(colour::HATCH));
+    }
+}
+
+/// Steps that get darker the further down they go
+fn stairs(down: bool, ctx: Ctx, fb: &mut FrameBuffer) {
+    for offset in Size::new_u16(3, 3).coord_iter_row_major() {
+        let depth = if down { offset.y } else { 2 - offset.y } as u32;
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character('▄')
+                .with_foreground(colour::STAIRS.saturating_scalar_mul_div(3 - depth, 3))
+                .with_background(colour::HATCH),
+        );
+    }
+}
+
+fn boat(ctx: Ctx, fb: &mut FrameBuffer) {
+    for (offset, ch) in [
+        (Coord::new(0, 1), '◥'),
+        (Coord::new(1, 1), '═'),
+        (Coord::new(2, 1), '◤'),
+    ] {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_bold(true)
+                .with_foreground(colour::BOAT_HULL),
+        );
+    }
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord::new(1, 0),
+        0,
+        RenderCell::default()
+            .with_character('│')
+            .with_foreground(colour::BOAT_OAR),
+    );
+}
+
+fn cairn(ctx: Ctx, fb: &mut FrameBuffer) {
+    for (offset, ch) in [
+        (Coord::new(1, 0), '▄'),
+        (Coord::new(0, 1), '▗'),
+        (Coord::new(1, 1), '█'),
+        (Coord::new(2, 1), '▖'),
+        (Coord::new(0, 2), '▟'),
+        (Coord::new(1, 2), '█'),
+        (Coord::new(2, 2), '▙'),
+    ] {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_foreground(colour::CAIRN),
+        );
+    }
+}
+
+fn fishing_rod(ctx: Ctx, fb: &mut FrameBuffer) {
+    for (offset, ch) in [(Coord::new(0, 2), '╱'), (Coord::new(1, 1), '╱')] {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_bold(true)
+                .with_foreground(colour::FISHING_ROD),
+        );
+    }
+    for (offset, ch) in [(Coord::new(2, 0), '╮'), (Coord::new(2, 1), '┆')] {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_foreground(colour::FISHING_LINE),
+        );
+    }
+}
+
+fn stove(ctx: Ctx, fb: &mut FrameBuffer) {
+    for (offset, ch) in [
+        (Coord::new(1, 0), '║'),
+        (Coord::new(0, 1), '▄'),
+        (Coord::new(1, 1), '▄'),
+        (Coord::new(2, 1), '▄'),
+        (Coord::new(0, 2), '█'),
+        (Coord::new(2, 2), '█'),
+    ] {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_foreground(colour::STOVE),
+        );
+    }
+    fb.set_cell_relative_to_ctx(
+        ctx,
+        Coord::new(1, 2),
+        0,
+        RenderCell::default()
+            .with_character('▒')
+            .with_foreground(colour::STOVE_FIRE)
+            .with_background(colour::STOVE),
+    );
+}
+
+fn puddle(ctx: Ctx, fb: &mut FrameBuffer) {
+    for offset in Size::new_u16(3, 3).coord_iter_row_major() {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character(if offset.y == 1 { '~' } else { ' ' })
+                .with_foreground(colour::PUDDLE_FOREGROUND)
+                .with_background(colour::PUDDLE_BACKGROUND),
+        );
+    }
+}
+
+/// Drawn at the same depth as the floor so it appears beneath any features, fading as the rain
+/// washes it away
+fn render_footprint(footprint: Footprint, ctx: Ctx, fb: &mut FrameBuffer) {
+    let (ch, offsets) = match footprint.direction {
+        CardinalDirection::North => ('^', [Coord::new(0, 2), Coord::new(2, 0)]),
+        CardinalDirection::South => ('v', [Coord::new(0, 0), Coord::new(2, 2)]),
+        CardinalDirection::East => ('>', [Coord::new(0, 2), Coord::new(2, 0)]),
+        CardinalDirection::West => ('<', [Coord::new(2, 2), Coord::new(0, 0)]),
+    };
+    let colour = colour::MUD_BACKGROUND.linear_interpolate(
+        colour::FOOTPRINT,
+        ((footprint.strength as u32 * 255) / FOOTPRINT_STRENGTH as u32).min(255) as u8,
+    );
+    for offset in offsets {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            offset,
+            0,
+            RenderCell::default()
+                .with_character(ch)
+                .with_bold(true)
+                .with_foreground(colour),
+        );
+    }
+}
+
+fn log_x(ctx: Ctx, fb: &mut FrameBuffer) {
+    for x in 0..3 {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord::new(x, 1),
+            0,
+            RenderCell::default()
+                .with_character('═')
+                .with_foreground(colour::LOG_GRAIN)
+                .with_background(colour::LOG_BARK),
+        );
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord::new(x, 0),
+            0,
+            RenderCell::default()
+                .with_character('▄')
+                .with_foreground(colour::LOG_BARK),
+        );
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord::new(x, 2),
+            0,
+            RenderCell::default()
+                .with_character('▀')
+                .with_foreground(colour::LOG_BARK),
+        );
+    }
+}
+
+fn log_y(ctx: Ctx, fb: &mut FrameBuffer) {
+    for y in 0..3 {
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord::new(1, y),
+            0,
+            RenderCell::default()
+                .with_character('║')
+                .with_foreground(colour::LOG_GRAIN)
+                .with_background(colour::LOG_BARK),
+        );
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord::new(0, y),
+            0,
+            RenderCell::default()
+                .with_character('▐')
+                .with_foreground(colour::LOG_BARK),
+        );
+        fb.set_cell_relative_to_ctx(
+            ctx,
+            Coord::new(2, y),
+            0,
+            RenderCell::default()
+                .with_character('▌')
+                .with_foreground(colour::LOG_BARK),
+        );
+    }
+}
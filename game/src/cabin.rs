@@ -0,0 +1,638 @@
+use crate::{
+    level::Level,
+    terrain::{GenerationError, Landmark},
+    world::World,
+};
+use gridbugs::{
+    direction::{Axis, CardinalDirection, Direction},
+    grid_2d::{Coord, Grid},
+};
+use rand::{
+    seq::{IteratorRandom, SliceRandom},
+    Rng,
+};
+use rational::Rational;
+use std::collections::{HashSet, VecDeque};
+
+const CELLAR_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 2,
+};
+
+/// Pieces of firewood stacked in the cellar
+const CELLAR_FIREWOOD: usize = 2;
+
+/// Width and height of the square around a cabin's centre that holds the cabin and everything
+/// laid out in front of it, out to where the player starts
+pub const SIZE: u32 = 41;
+
+/// The overall shape of the cabin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Archetype {
+    Square,
+    LShaped,
+    TwoRoom,
+    Loft,
+    Porch,
+}
+
+impl Archetype {
+    const ALL: &'static [Self] = &[
+        Self::Square,
+        Self::LShaped,
+        Self::TwoRoom,
+        Self::Loft,
+        Self::Porch,
+    ];
+}
+
+/// A cabin layout relative to its centre, with the front door in the south wall
+struct Blueprint {
+    /// Cells enclosed by the outer walls, including the walls themselves
+    rooms: HashSet<Coord>,
+    /// Covered floor outside the front door
+    porch: HashSet<Coord>,
+    /// Walls dividing the cabin into rooms
+    partition: Vec<Coord>,
+    /// A doorway through the partition
+    inner_door: Option<Coord>,
+    front_door: Coord,
+    /// A ladder up to a loft over the whole cabin, where the bed goes
+    ladder: Option<Coord>,
+    /// Whether there are stairs down to a cellar under the whole cabin
+    cellar: bool,
+    /// Cells where the bed may go. If empty, the bed can go anywhere.
+    bedroom: HashSet<Coord>,
+}
+
+fn rect(top_left: Coord, bottom_right: Coord) -> impl Iterator<Item = Coord> {
+    (top_left.y..=bottom_right.y)
+        .flat_map(move |y| (top_left.x..=bottom_right.x).map(move |x| Coord::new(x, y)))
+}
+
+impl Blueprint {
+    fn new<R: Rng>(archetype: Archetype, rng: &mut R) -> Self {
+        let mut blueprint = Self {
+            rooms: HashSet::new(),
+            porch: HashSet::new(),
+            partition: Vec::new(),
+            inner_door: None,
+            front_door: Coord::new(0, 0),
+            ladder: None,
+            cellar: CELLAR_CHANCE.roll(rng),
+            bedroom: HashSet::new(),
+        };
+        match archetype {
+            Archetype::Square => {
+                blueprint
+                    .rooms
+                    .extend(rect(Coord::new(-4, -4), Coord::new(4, 4)));
+                blueprint.front_door = Coord::new(0, 4);
+            }
+            Archetype::LShaped => {
+                blueprint
+                    .rooms
+                    .extend(rect(Coord::new(-5, -4), Coord::new(5, 0)));
+                blueprint
+                    .rooms
+                    .extend(rect(Coord::new(-5, 0), Coord::new(0, 4)));
+                blueprint.front_door = Coord::new(-2, 4);
+            }
+            Archetype::TwoRoom => {
+                blueprint
+                    .rooms
+                    .extend(rect(Coord::new(-6, -4), Coord::new(6, 4)));
+                blueprint
+                    .partition
+                    .extend(rect(Coord::new(0, -3), Coord::new(0, 3)));
+                blueprint.inner_door = Some(Coord::new(0, rng.gen_range(-3..=3)));
+                blueprint.front_door = Coord::new(-3, 4);
+                blueprint
+                    .bedroom
+                    .extend(rect(Coord::new(1, -3), Coord::new(5, 3)));
+            }
+            Archetype::Loft => {
+                blueprint
+                    .rooms
+                    .extend(rect(Coord::new(-6, -3), Coord::new(6, 3)));
+                blueprint.front_door = Coord::new(0, 3);
+                blueprint.ladder = Some(Coord::new(-5, -2));
+            }
+            Archetype::Porch => {
+                blueprint
+                    .rooms
+                    .extend(rect(Coord::new(-4, -4), Coord::new(4, 2)));
+                blueprint
+                    .porch
+                    .extend(rect(Coord::new(-4, 3), Coord::new(4, 5)));
+                blueprint.front_door = Coord::new(0, 2);
+            }
+        }
+        blueprint
+    }
+
+    /// Cells of the outer wall, which border a cell outside the cabin
+    fn is_outer_wall(&self, coord: Coord) -> bool {
+        self.rooms.contains(&coord)
+            && Direction::all().any(|d| !self.rooms.contains(&(coord + d.coord())))
+    }
+
+    fn is_wall(&self, coord: Coord) -> bool {
+        self.is_outer_wall(coord) || self.partition.contains(&coord)
+    }
+
+    fn is_interior(&self, coord: Coord) -> bool {
+        self.rooms.contains(&coord) && !self.is_wall(coord)
+    }
+
+    /// Windows go in every other cell of the outer walls, avoiding corners and the front door
+    fn is_window(&self, coord: Coord) -> bool {
+        (coord.x + coord.y) % 2 == 0
+            && self.is_outer_wall(coord)
+            && coord.manhattan_distance(self.front_door) > 1
+            && CardinalDirection::all().any(|d| {
+                self.is_interior(coord - d.coord())
+                    && !self.rooms.contains(&(coord + d.coord()))
+                    && self.is_wall(coord + d.left90().coord())
+                    && self.is_wall(coord + d.right90().coord())
+            })
+    }
+}
+
+/// Where the cabin was built, and the things outside it that other features are placed around
+pub struct Cabin {
+    pub door_coord: Coord,
+    /// The direction from the inside of the cabin out through the front door
+    pub door_direction: CardinalDirection,
+    pub player_coord: Coord,
+}
+
+/// Turns a coordinate in a blueprint (which has its front door facing south) to face the door in
+/// the given direction
+fn orient(coord: Coord, door_direction: CardinalDirection, mirror: bool) -> Coord {
+    let mut coord = if mirror {
+        Coord::new(-coord.x, coord.y)
+    } else {
+        coord
+    };
+    let mut direction = CardinalDirection::South;
+    while direction != door_direction {
+        coord = coord.left90();
+        direction = direction.left90();
+    }
+    coord
+}
+
+/// The axis of movement through a door or window, given the step from one side to the other
+fn axis_across(step: Coord) -> Axis {
+    if step.x == 0 {
+        Axis::Y
+    } else {
+        Axis::X
+    }
+}
+
+fn choose_coord<R: Rng, F: Fn(Coord) -> bool>(
+    candidates: &[Coord],
+    predicate: F,
+    rng: &mut R,
+) -> Option<Coord> {
+    candidates
+        .iter()
+        .cloned()
+        .filter(|&coord| predicate(coord))
+        .choose(rng)
+}
+
+/// Builds a cabin of a random archetype around `centre` and furnishes it. Fails if the furniture
+/// can't all be placed without blocking the way around the cabin.
+pub fn build<R: Rng>(
+    world: &mut World,
+    topography_grid: &Grid<f64>,
+    no_trees: &mut Grid<bool>,
+    centre: Coord,
+    rng: &mut R,
+) -> Result<Cabin, GenerationError> {
+    let archetype = *Archetype::ALL.choose(rng).unwrap();
+    build_archetype(archetype, world, topography_grid, no_trees, centre, rng)
+}
+
+fn build_archetype<R: Rng>(
+    archetype: Archetype,
+    world: &mut World,
+    topography_grid: &Grid<f64>,
+    no_trees: &mut Grid<bool>,
+    centre: Coord,
+    rng: &mut R,
+) -> Result<Cabin, GenerationError> {
+    let blueprint = Blueprint::new(archetype, rng);
+    let door_direction = rng.gen::<CardinalDirection>();
+    let mirror = rng.gen();
+    let to_world = |coord: Coord| centre + orient(coord, door_direction, mirror);
+    let mut sorted_rooms = blueprint.rooms.iter().cloned().collect::<Vec<_>>();
+    sorted_rooms.sort_by_key(|coord| (coord.y, coord.x));
+    let mut sorted_porch = blueprint.porch.iter().cloned().collect::<Vec<_>>();
+    sorted_porch.sort_by_key(|coord| (coord.y, coord.x));
+    for &coord in sorted_rooms.iter().chain(sorted_porch.iter()) {
+        let coord = to_world(coord);
+        if !coord.is_valid(world.size()) {
+            return Err(GenerationError::OffMap(Landmark::Cabin));
+        }
+        world.spawn_floor(coord, *topography_grid.get_checked(coord));
+        *no_trees.get_checked_mut(coord) = true;
+    }
+    // the direction across a wall cell, from inside to outside
+    let across = |coord: Coord| {
+        CardinalDirection::all()
+            .find(|d| {
+                blueprint.is_interior(coord - d.coord())
+                    && !blueprint.is_interior(coord + d.coord())
+            })
+            .or_else(|| {
+                CardinalDirection::all().find(|d| {
+                    blueprint.is_interior(coord - d.coord())
+                        && blueprint.is_interior(coord + d.coord())
+                })
+            })
+            .map(|d| orient(d.coord(), door_direction, mirror))
+    };
+    let mut interior = Vec::new();
+    let mut windows = Vec::new();
+    for &coord in &sorted_rooms {
+        let world_coord = to_world(coord);
+        if coord == blueprint.front_door || Some(coord) == blueprint.inner_door {
+            let outward = across(coord).ok_or(GenerationError::NoSite(Landmark::CabinDoor))?;
+            world.spawn_door(world_coord, axis_across(outward));
+            world.spawn_light(world_coord - outward);
+        } else if blueprint.is_window(coord) {
+            let outward = across(coord).ok_or(GenerationError::NoSite(Landmark::Window))?;
+            world.spawn_window(world_coord, axis_across(outward));
+            world.spawn_light(world_coord - outward);
+            windows.push((world_coord, outward));
+        } else if blueprint.is_wall(coord) {
+            world.spawn_wall(world_coord);
+        } else {
+            interior.push(world_coord);
+        }
+    }
+    let door_coord = to_world(blueprint.front_door);
+    let door_outward = orient(Coord::new(0, 1), door_direction, mirror);
+    let door_inside = door_coord - door_outward;
+    let mut entrances = vec![door_inside];
+    let mut passable = interior.clone();
+    if let Some(inner_door) = blueprint.inner_door {
+        let inner_door = to_world(inner_door);
+        passable.push(inner_door);
+        entrances.extend(
+            CardinalDirection::all()
+                .map(|d| inner_door + d.coord())
+                .filter(|coord| interior.contains(coord)),
+        );
+    }
+    // the loft has the same walls and windows as the cabin below, but no doors, and the bed goes
+    // up there, so check there's room for it before building anything
+    let loft = match blueprint.ladder {
+        Some(ladder) => {
+            let ladder = to_world(ladder);
+            let loft_interior = sorted_rooms
+                .iter()
+                .filter(|&&coord| !blueprint.is_outer_wall(coord))
+                .map(|&coord| to_world(coord))
+                .filter(|&world_coord| {
+                    world_coord != ladder
+                        && !windows.iter().any(|&(window, _)| window == world_coord)
+                })
+                .collect::<Vec<_>>();
+            let bed_coord = choose_coord(
+                &loft_interior,
+                |coord| {
+                    coord.manhattan_distance(ladder) > 2
+                        && CardinalDirection::all()
+                            .filter(|d| !loft_interior.contains(&(coord + d.coord())))
+                            .count()
+                            >= 2
+                },
+                rng,
+            )
+            .ok_or(GenerationError::NoSite(Landmark::Bed))?;
+            let ladder_entity = world.spawn_ladder(ladder);
+            world.components.stairs.insert(ladder_entity, Level::Loft);
+            Some((ladder, bed_coord))
+        }
+        None => None,
+    };
+    let cellar_stairs = if blueprint.cellar {
+        let stairs_coord = choose_coord(
+            &interior,
+            |coord| {
+                entrances
+                    .iter()
+                    .all(|&entrance| coord.manhattan_distance(entrance) > 1)
+                    && CardinalDirection::all().any(|d| !interior.contains(&(coord + d.coord())))
+                    && world
+                        .spatial_table
+                        .layers_at_checked(coord)
+                        .feature
+                        .is_none()
+            },
+            rng,
+        )
+        .ok_or(GenerationError::NoSite(Landmark::Cellar))?;
+        world.spawn_stairs_down(stairs_coord);
+        Some(stairs_coord)
+    } else {
+        None
+    };
+    furnish(
+        world, &blueprint, &to_world, &interior, &windows, &entrances, rng,
+    )?;
+    if !is_connected(world, &passable, door_inside) {
+        return Err(GenerationError::Blocked(Landmark::Cabin));
+    }
+    if let Some((ladder, bed_coord)) = loft {
+        let previous = world.enter_level(Level::Loft);
+        for &coord in &sorted_rooms {
+            let world_coord = to_world(coord);
+            world.spawn_floor(world_coord, *topography_grid.get_checked(world_coord));
+            let window = windows.iter().find(|&&(window, _)| window == world_coord);
+            if let Some(&(_, outward)) = window {
+                world.spawn_window(world_coord, axis_across(outward));
+                world.spawn_light(world_coord - outward);
+            } else if blueprint.is_outer_wall(coord) {
+                world.spawn_wall(world_coord);
+            }
+        }
+        world.spawn_hatch(ladder);
+        world.spawn_bed(bed_coord);
+        world.spawn_light(bed_coord);
+        world.enter_level(previous);
+    }
+    if let Some(stairs_coord) = cellar_stairs {
+        // the cellar is dug out under the whole cabin, with no windows
+        let previous = world.enter_level(Level::Cellar);
+        let mut cellar_interior = Vec::new();
+        for &coord in &sorted_rooms {
+            let world_coord = to_world(coord);
+            if blueprint.is_outer_wall(coord) {
+                world.spawn_floor(world_coord, *topography_grid.get_checked(world_coord));
+                world.spawn_wall(world_coord);
+            } else {
+                world.spawn_cellar_floor(world_coord);
+                if world_coord.manhattan_distance(stairs_coord) > 1 {
+                    cellar_interior.push(world_coord);
+                }
+            }
+        }
+        world.spawn_stairs_up(stairs_coord);
+        for &coord in cellar_interior.choose_multiple(rng, CELLAR_FIREWOOD) {
+            world.spawn_firewood(coord);
+        }
+        world.enter_level(previous);
+    }
+    // everything outside is laid out from the front of the porch, if there is one
+    let porch_depth = blueprint
+        .porch
+        .iter()
+        .map(|coord| coord.y - blueprint.front_door.y)
+        .max()
+        .unwrap_or(0);
+    let front = door_coord + door_direction.coord() * porch_depth;
+    *no_trees.get_checked_mut(front + door_direction.coord()) = true;
+    *no_trees.get_checked_mut(front + door_direction.coord() * 2) = true;
+    let lamp_coord = front + (door_direction.coord() * 5) + door_direction.left90().coord();
+    let bulletin_board_coord =
+        front + (door_direction.coord() * 3) + door_direction.right90().coord();
+    let player_coord = front + door_direction.coord() * 14;
+    if !lamp_coord.is_valid(world.size()) || !bulletin_board_coord.is_valid(world.size()) {
+        return Err(GenerationError::OffMap(Landmark::Cabin));
+    }
+    world.spawn_lamp(lamp_coord);
+    world.spawn_bulletin_board(bulletin_board_coord);
+    *no_trees.get_checked_mut(bulletin_board_coord + Coord::new(0, 1)) = true;
+    *no_trees.get_checked_mut(bulletin_board_coord + Coord::new(0, 2)) = true;
+    Ok(Cabin {
+        door_coord,
+        door_direction,
+        player_coord,
+    })
+}
+
+/// Places the furniture according to these rules:
+///  - the bed goes in a corner, away from the doors (and in the bedroom if there is one), unless
+///    it's up in the loft
+///  - the chair faces a window, with the teapot beside it
+///  - the table stands out in the open if there's room
+///  - the bookshelf stands against a wall where it won't block a window
+///  - the stove and kitchen bench stand side by side against the walls, away from the bed
+fn furnish<R: Rng, F: Fn(Coord) -> Coord>(
+    world: &mut World,
+    blueprint: &Blueprint,
+    to_world: &F,
+    interior: &[Coord],
+    windows: &[(Coord, Coord)],
+    entrances: &[Coord],
+    rng: &mut R,
+) -> Result<(), GenerationError> {
+    let is_free = |world: &World, coord: Coord| {
+        interior.contains(&coord)
+            && world
+                .spatial_table
+                .layers_at_checked(coord)
+                .feature
+                .is_none()
+            && entrances
+                .iter()
+                .all(|&entrance| coord.manhattan_distance(entrance) > 1)
+    };
+    let num_walls = |coord: Coord| {
+        CardinalDirection::all()
+            .filter(|d| !interior.contains(&(coord + d.coord())))
+            .count()
+    };
+    let window_at = |coord: Coord| {
+        windows
+            .iter()
+            .find(|&&(window, outward)| coord == window - outward)
+            .map(|&(_, outward)| outward)
+    };
+    let bedroom = blueprint
+        .bedroom
+        .iter()
+        .map(|&coord| to_world(coord))
+        .collect::<HashSet<_>>();
+    // a cabin with a loft has its bed up there
+    let bed_coord = if blueprint.ladder.is_some() {
+        None
+    } else {
+        let bed_coord = choose_coord(
+            interior,
+            |coord| {
+                is_free(world, coord)
+                    && num_walls(coord) >= 2
+                    && (bedroom.is_empty() || bedroom.contains(&coord))
+            },
+            rng,
+        )
+        .ok_or(GenerationError::NoSite(Landmark::Bed))?;
+        world.spawn_bed(bed_coord);
+        world.spawn_light(bed_coord);
+        Some(bed_coord)
+    };
+    let near_bed = |coord: Coord| {
+        bed_coord
+            .map(|bed_coord| coord.manhattan_distance(bed_coord) <= 1)
+            .unwrap_or(false)
+    };
+    // the chair sprites face left or right, so it can only face a window to the east or west
+    let chair_coord = choose_coord(
+        interior,
+        |coord| {
+            is_free(world, coord)
+                && !near_bed(coord)
+                && matches!(window_at(coord), Some(outward) if outward.y == 0)
+        },
+        rng,
+    )
+    .ok_or(GenerationError::NoSite(Landmark::Chair))?;
+    let chair_outward = window_at(chair_coord).unwrap();
+    if chair_outward.x < 0 {
+        world.spawn_chair_left_facing(chair_coord);
+    } else {
+        world.spawn_chair_right_facing(chair_coord);
+    }
+    let teapot_coord = choose_coord(
+        &[
+            chair_coord + chair_outward.left90(),
+            chair_coord + chair_outward.right90(),
+        ],
+        |coord| is_free(world, coord),
+        rng,
+    )
+    .ok_or(GenerationError::NoSite(Landmark::Teapot))?;
+    world.spawn_teapot(teapot_coord);
+    let table_coord = choose_coord(
+        interior,
+        |coord| is_free(world, coord) && !near_bed(coord) && num_walls(coord) == 0,
+        rng,
+    )
+    .or_else(|| {
+        choose_coord(
+            interior,
+            |coord| is_free(world, coord) && !near_bed(coord),
+            rng,
+        )
+    })
+    .ok_or(GenerationError::NoSite(Landmark::Table))?;
+    world.spawn_table(table_coord);
+    let against_wall = |world: &World, coord: Coord| is_free(world, coord) && num_walls(coord) >= 1;
+    let bookshelf_coord = choose_coord(
+        interior,
+        |coord| against_wall(world, coord) && window_at(coord).is_none(),
+        rng,
+    )
+    .ok_or(GenerationError::NoSite(Landmark::Bookshelf))?;
+    world.spawn_bookshelf(bookshelf_coord);
+    let mut kitchen = None;
+    let mut stove_candidates = interior
+        .iter()
+        .cloned()
+        .filter(|&coord| {
+            against_wall(world, coord)
+                && bed_coord
+                    .map(|bed_coord| coord.manhattan_distance(bed_coord) > 2)
+                    .unwrap_or(true)
+        })
+        .collect::<Vec<_>>();
+    stove_candidates.shuffle(rng);
+    for stove_coord in stove_candidates {
+        let bench_coord = CardinalDirection::all()
+            .map(|d| stove_coord + d.coord())
+            .filter(|&coord| against_wall(world, coord))
+            .choose(rng);
+        if let Some(bench_coord) = bench_coord {
+            kitchen = Some((stove_coord, bench_coord));
+            break;
+        }
+    }
+    let (stove_coord, bench_coord) = kitchen.ok_or(GenerationError::NoSite(Landmark::Kitchen))?;
+    world.spawn_stove(stove_coord);
+    world.spawn_kitchen_bench(bench_coord);
+    Ok(())
+}
+
+/// Checks that every empty cell in the cabin can be reached from the door, and that every piece
+/// of furniture is next to a cell that can be reached. `interior` includes any inner doorways,
+/// which the player can open.
+fn is_connected(world: &World, interior: &[Coord], start: Coord) -> bool {
+    let is_passable = |coord: Coord| {
+        interior.contains(&coord)
+            && world
+                .spatial_table
+                .layers_at_checked(coord)
+                .feature
+                .map(|feature| {
+                    !world.components.solid.contains(feature)
+                        || world.components.door_state.contains(feature)
+                })
+                .unwrap_or(true)
+    };
+    let mut reached = HashSet::new();
+    let mut queue = VecDeque::new();
+    reached.insert(start);
+    queue.push_back(start);
+    while let Some(coord) = queue.pop_front() {
+        for d in CardinalDirection::all() {
+            let neighbour = coord + d.coord();
+            if is_passable(neighbour) && reached.insert(neighbour) {
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    interior.iter().all(|&coord| {
+        reached.contains(&coord)
+            || CardinalDirection::all().any(|d| reached.contains(&(coord + d.coord())))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gridbugs::grid_2d::Size;
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    #[test]
+    fn every_archetype_builds_on_flat_ground() {
+        const NUM_SEEDS: u64 = 20;
+        let size = Size::new(SIZE, SIZE);
+        let centre = size.to_coord().unwrap() / 2;
+        for &archetype in Archetype::ALL {
+            let failures = (0..NUM_SEEDS)
+                .filter_map(|seed| {
+                    let mut world = World::new(size);
+                    let topography_grid = Grid::new_copy(size, 0.5);
+                    let mut no_trees = Grid::new_copy(size, false);
+                    let mut rng = Isaac64Rng::seed_from_u64(seed);
+                    build_archetype(
+                        archetype,
+                        &mut world,
+                        &topography_grid,
+                        &mut no_trees,
+                        centre,
+                        &mut rng,
+                    )
+                    .err()
+                })
+                .collect::<Vec<_>>();
+            // furniture sometimes boxes itself in, which is rejected, but every archetype should
+            // build more often than not
+            assert!(
+                failures.len() * 2 < NUM_SEEDS as usize,
+                "{:?} failed {} times: {:?}",
+                archetype,
+                failures.len(),
+                failures
+            );
+        }
+    }
+}
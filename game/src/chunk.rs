@@ -0,0 +1,691 @@
+use crate::{
+    cabin::{self, Cabin},
+    components::{EntityData, Item},
+    forage,
+    level::Level,
+    realtime::flicker::FlickerState,
+    spatial::{Layer, Location},
+    terrain::{self, GenerationError, GenerationStats, Landmark, Terrain, TerrainConfig},
+    world::World,
+};
+use gridbugs::{
+    entity_table::Entity,
+    grid_2d::{Coord, Grid, Size},
+};
+use perlin2::Perlin2;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_isaac::Isaac64Rng;
+use rational::Rational;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Width and height of a chunk, in cells
+pub const CHUNK_SIZE: i32 = 32;
+
+/// The world of the endless forest is a square of this many chunks across, with the player in
+/// the middle one
+const WINDOW_CHUNKS: i32 = 3;
+
+pub const WINDOW_SIZE: Size = Size::new_u16(
+    (CHUNK_SIZE * WINDOW_CHUNKS) as u16,
+    (CHUNK_SIZE * WINDOW_CHUNKS) as u16,
+);
+
+/// Chunks taken out of the world are kept in memory while they're within this many chunks of the
+/// player's chunk, so walking back and forth across a chunk boundary doesn't keep storing and
+/// loading them. Further chunks are handed over to be stored.
+const KEEP_DISTANCE: i32 = 2;
+
+const CABIN_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 8,
+};
+
+const LAMP_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 4,
+};
+
+const FORAGE_PATCH_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 6,
+};
+
+/// How far a cabin may be from the middle of its chunk. Everything the cabin builder places,
+/// including the lamp out the front, stays within 10 cells of the cabin's centre, so this keeps
+/// the whole cabin inside the chunk.
+const CABIN_JITTER: i32 = 2;
+
+/// Distance between chunks, counting diagonal steps as one
+fn chunk_distance(a: Coord, b: Coord) -> i32 {
+    (a.x - b.x).abs().max((a.y - b.y).abs())
+}
+
+fn in_chunk(coord: Coord, corner: Coord) -> bool {
+    let offset = coord - corner;
+    offset.x >= 0 && offset.y >= 0 && offset.x < CHUNK_SIZE && offset.y < CHUNK_SIZE
+}
+
+/// Mixes the forest's seed with a chunk's coordinates (using the splitmix64 finaliser), so every
+/// chunk is generated from its own seed and comes out the same whenever it is generated
+fn chunk_seed(seed: u64, chunk: Coord) -> u64 {
+    let mut z = seed ^ (((chunk.x as u32 as u64) << 32) | chunk.y as u32 as u64);
+    z = z.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// The noise that shapes the whole forest. It is sampled at absolute coordinates so hills and
+/// clearings carry on across the edges of chunks.
+struct Noise {
+    topography: Perlin2,
+    tree_chance: Perlin2,
+}
+
+impl Noise {
+    fn new(seed: u64) -> Self {
+        let mut rng = Isaac64Rng::seed_from_u64(seed);
+        Self {
+            topography: Perlin2::new(&mut rng),
+            tree_chance: Perlin2::new(&mut rng),
+        }
+    }
+
+    fn height(&self, config: &TerrainConfig, coord: Coord) -> f64 {
+        self.topography.noise01((
+            coord.x as f64 * config.topography_spread,
+            coord.y as f64 * config.topography_spread,
+        ))
+    }
+
+    fn tree_chance(&self, config: &TerrainConfig, coord: Coord) -> f64 {
+        self.tree_chance.noise01((
+            coord.x as f64 * config.tree_chance_spread,
+            coord.y as f64 * config.tree_chance_spread,
+        ))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkEntity {
+    /// Relative to the top-left corner of the chunk
+    coord: Coord,
+    level: Level,
+    layer: Option<Layer>,
+    data: EntityData,
+    flicker: Option<FlickerState>,
+}
+
+/// Everything in a chunk that has been taken out of the world
+#[derive(Serialize, Deserialize)]
+pub struct Chunk {
+    coord: Coord,
+    entities: Vec<ChunkEntity>,
+}
+
+impl Chunk {
+    pub fn coord(&self) -> Coord {
+        self.coord
+    }
+}
+
+impl World {
+    /// Entities in the chunk with the given top-left corner, including lights that aren't in a
+    /// layer
+    fn chunk_entities(&self, corner: Coord) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        for offset in Size::new(CHUNK_SIZE as u32, CHUNK_SIZE as u32).coord_iter_row_major() {
+            let layers = self.spatial_table.layers_at_checked(corner + offset);
+            entities.extend(
+                [layers.floor, layers.feature, layers.character, layers.item]
+                    .into_iter()
+                    .flatten(),
+            );
+        }
+        entities.extend(self.components.house_light.entities().filter(|&entity| {
+            self.spatial_table
+                .coord_of(entity)
+                .map(|coord| in_chunk(coord, corner))
+                .unwrap_or(false)
+        }));
+        entities
+    }
+
+    /// Removes everything in the chunk with the given top-left corner from every level of the
+    /// world
+    fn take_chunk(&mut self, corner: Coord) -> Vec<ChunkEntity> {
+        let mut entities = Vec::new();
+        self.for_each_level(|world| {
+            for entity in world.chunk_entities(corner) {
+                let location = *world.spatial_table.location_of(entity).unwrap();
+                world.spatial_table.remove(entity);
+                let data = world.components.remove_entity_data(entity);
+                let flicker = world.realtime_components.flicker.remove(entity);
+                world.entity_allocator.free(entity);
+                entities.push(ChunkEntity {
+                    coord: location.coord - corner,
+                    level: world.level,
+                    layer: location.layer,
+                    data,
+                    flicker,
+                });
+            }
+        });
+        entities
+    }
+
+    fn insert_chunk(&mut self, corner: Coord, entities: Vec<ChunkEntity>) {
+        let current = self.level;
+        for ChunkEntity {
+            coord,
+            level,
+            layer,
+            data,
+            flicker,
+        } in entities
+        {
+            self.enter_level(level);
+            let location = Location {
+                coord: corner + coord,
+                layer,
+            };
+            let entity = self.insert_entity_data(location, data);
+            if let Some(flicker) = flicker {
+                self.realtime_components.flicker.insert(entity, flicker);
+            }
+        }
+        self.enter_level(current);
+    }
+
+    /// Moves everything on every level of the world by `offset`. Nothing may end up off the edge
+    /// of the world.
+    fn shift(&mut self, offset: Coord) {
+        self.for_each_level(|world| world.shift_level(offset));
+    }
+
+    fn shift_level(&mut self, offset: Coord) {
+        let mut locations = Vec::new();
+        for (coord, layers) in self.spatial_table.enumerate() {
+            for (entity, layer) in [
+                (layers.floor, Layer::Floor),
+                (layers.feature, Layer::Feature),
+                (layers.character, Layer::Character),
+                (layers.item, Layer::Item),
+            ] {
+                if let Some(entity) = entity {
+                    locations.push((entity, coord, Some(layer)));
+                }
+            }
+        }
+        for entity in self.components.house_light.entities() {
+            if let Some(coord) = self.spatial_table.coord_of(entity) {
+                locations.push((entity, coord, None));
+            }
+        }
+        self.spatial_table.clear();
+        for (entity, coord, layer) in locations {
+            let location = Location {
+                coord: coord + offset,
+                layer,
+            };
+            self.spatial_table.update(entity, location).unwrap();
+        }
+    }
+}
+
+/// Keeps track of the chunks of the endless forest. The world holds the chunks around the
+/// player, and is moved along when the player leaves the middle chunk. Chunks that leave the world
+/// are kept here until they're far enough away to be handed over for storage. Chunks are
+/// generated the first time the world reaches them.
+#[derive(Serialize, Deserialize)]
+pub struct Chunks {
+    seed: u64,
+    config: TerrainConfig,
+    /// The chunk at the top-left of the world
+    origin: Coord,
+    /// Chunks taken out of the world that are still kept in memory
+    unloaded: HashMap<Coord, Chunk>,
+    /// Chunks that were handed over for storage. They must be handed back before they return to
+    /// the world, rather than being generated again.
+    stored: HashSet<Coord>,
+}
+
+impl Chunks {
+    pub fn new(seed: u64, config: TerrainConfig) -> Self {
+        Self {
+            seed,
+            config,
+            origin: Coord::new(-(WINDOW_CHUNKS / 2), -(WINDOW_CHUNKS / 2)),
+            unloaded: HashMap::new(),
+            stored: HashSet::new(),
+        }
+    }
+
+    /// The absolute coordinate of the top-left cell of the world
+    pub fn world_origin(&self) -> Coord {
+        self.origin * CHUNK_SIZE
+    }
+
+    /// Top-left cell of a chunk in world coordinates
+    fn corner(&self, chunk: Coord) -> Coord {
+        (chunk - self.origin) * CHUNK_SIZE
+    }
+
+    fn chunk_of(&self, coord: Coord) -> Coord {
+        self.origin
+            + Coord::new(
+                coord.x.div_euclid(CHUNK_SIZE),
+                coord.y.div_euclid(CHUNK_SIZE),
+            )
+    }
+
+    fn window(origin: Coord) -> impl Iterator<Item = Coord> {
+        Size::new(WINDOW_CHUNKS as u32, WINDOW_CHUNKS as u32)
+            .coord_iter_row_major()
+            .map(move |offset| origin + offset)
+    }
+
+    fn in_window(chunk: Coord, origin: Coord) -> bool {
+        let offset = chunk - origin;
+        offset.x >= 0 && offset.y >= 0 && offset.x < WINDOW_CHUNKS && offset.y < WINDOW_CHUNKS
+    }
+
+    /// Fills a chunk of the world with forest, and sometimes a cabin, which is always built in
+    /// the `start` chunk. Returns the cabin if one was built.
+    fn generate(
+        &self,
+        world: &mut World,
+        noise: &Noise,
+        chunk: Coord,
+        start: bool,
+    ) -> Option<Cabin> {
+        let config = &self.config;
+        let mut rng = Isaac64Rng::seed_from_u64(chunk_seed(self.seed, chunk));
+        let corner = self.corner(chunk);
+        let world_origin = self.world_origin();
+        let topography_grid = Grid::new_fn(world.size(), |coord| {
+            noise.height(config, world_origin + coord)
+        });
+        let mut no_trees = Grid::new_copy(world.size(), false);
+        let mut cabin = None;
+        if start || CABIN_CHANCE.roll(&mut rng) {
+            loop {
+                let centre = corner
+                    + Coord::new(
+                        CHUNK_SIZE / 2 + rng.gen_range(-CABIN_JITTER..=CABIN_JITTER),
+                        CHUNK_SIZE / 2 + rng.gen_range(-CABIN_JITTER..=CABIN_JITTER),
+                    );
+                let result = cabin::build(world, &topography_grid, &mut no_trees, centre, &mut rng)
+                    .and_then(|cabin| {
+                        if start && !cabin.player_coord.is_valid(world.size()) {
+                            Err(GenerationError::OffMap(Landmark::Player))
+                        } else {
+                            Ok(cabin)
+                        }
+                    });
+                match result {
+                    Ok(built) => {
+                        cabin = Some(built);
+                        break;
+                    }
+                    Err(error) => {
+                        log::warn!("{}", error);
+                        world.take_chunk(corner);
+                        no_trees = Grid::new_copy(world.size(), false);
+                        if !start {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        let free = |world: &World, coord: Coord| {
+            let layers = world.spatial_table.layers_at_checked(coord);
+            layers.floor.is_none() && layers.feature.is_none() && layers.item.is_none()
+        };
+        if LAMP_CHANCE.roll(&mut rng) {
+            let coord = corner
+                + Coord::new(
+                    rng.gen_range(1..(CHUNK_SIZE - 1)),
+                    rng.gen_range(1..(CHUNK_SIZE - 1)),
+                );
+            if free(world, coord) {
+                world.spawn_lamp(coord);
+                *no_trees.get_checked_mut(coord + Coord::new(0, 1)) = true;
+            }
+        }
+        // the ground isn't laid until later, so remember where the patches are for now
+        let mut forage_patches = Vec::new();
+        for (item, radius) in [
+            (Item::Flower, config.flower_patch_radius as i32),
+            (Item::Tea, config.tea_patch_radius as i32),
+        ] {
+            if !FORAGE_PATCH_CHANCE.roll(&mut rng) {
+                continue;
+            }
+            let centre = corner
+                + Coord::new(
+                    rng.gen_range(radius..(CHUNK_SIZE - radius)),
+                    rng.gen_range(radius..(CHUNK_SIZE - radius)),
+                );
+            let candidates = Size::new(radius as u32 * 2, radius as u32 * 2)
+                .coord_iter_row_major()
+                .map(|offset| offset - Coord::new(radius, radius))
+                .filter(|rel_coord| rel_coord.magnitude2() < (radius * radius) as u32)
+                .map(|rel_coord| centre + rel_coord)
+                .filter(|&coord| free(world, coord))
+                .collect::<Vec<_>>();
+            for &coord in candidates.choose_multiple(&mut rng, forage::PATCH_CAPACITY) {
+                match item {
+                    Item::Tea => world.spawn_tea(coord),
+                    _ => world.spawn_flower(coord),
+                };
+                *no_trees.get_checked_mut(coord) = true;
+            }
+            forage_patches.extend(candidates.into_iter().map(|coord| (coord, item)));
+        }
+        let mut rock_candidates = Vec::new();
+        for offset in Size::new(CHUNK_SIZE as u32, CHUNK_SIZE as u32).coord_iter_row_major() {
+            let coord = corner + offset;
+            let layers = world.spatial_table.layers_at_checked(coord);
+            if layers.floor.is_some() {
+                continue;
+            }
+            if !no_trees.get_checked(coord) && layers.feature.is_none() {
+                let noise = noise.tree_chance(config, world_origin + coord);
+                if rng.gen::<f64>() < (noise - config.tree_chance_offset) * config.tree_chance_scale
+                {
+                    world.spawn_tree(coord, &mut rng);
+                } else if (noise < config.grass_threshold && rng.gen::<f64>() * 0.8 > noise)
+                    || rng.gen::<f64>() < config.grass_chance
+                {
+                    world.spawn_grass(coord);
+                } else if layers.item.is_none() {
+                    rock_candidates.push(coord);
+                }
+            }
+            world.spawn_ground(coord, *topography_grid.get_checked(coord));
+        }
+        for (coord, item) in forage_patches {
+            if let Some(floor) = world.spatial_table.layers_at_checked(coord).floor {
+                world.components.forage_patch.insert(floor, item);
+            }
+        }
+        // as many rocks per cell as in a generated map of the configured size
+        let num_rocks = config.num_rocks * (CHUNK_SIZE * CHUNK_SIZE) as usize
+            / (config.width * config.height).max(1) as usize;
+        for &coord in rock_candidates.choose_multiple(&mut rng, num_rocks) {
+            world.spawn_rock(coord);
+        }
+        cabin
+    }
+
+    /// Generates the world around the first chunk, with a cabin in the middle and the player
+    /// standing outside it
+    pub fn generate_start(&self, player_data: EntityData) -> Terrain {
+        let mut world = World::new(WINDOW_SIZE);
+        let noise = Noise::new(self.seed);
+        let start = self.chunk_of(Coord::new(CHUNK_SIZE, CHUNK_SIZE));
+        let mut cabin = None;
+        for chunk in Self::window(self.origin) {
+            let built = self.generate(&mut world, &noise, chunk, chunk == start);
+            if chunk == start {
+                cabin = built;
+            }
+        }
+        let cabin = cabin.expect("no cabin in the starting chunk");
+        let player_coord = cabin.player_coord;
+        let layers = *world.spatial_table.layers_at_checked(player_coord);
+        for entity in [layers.feature, layers.item].into_iter().flatten() {
+            world.spatial_table.remove(entity);
+            world.components.remove_entity(entity);
+        }
+        let player_location = Location {
+            coord: player_coord,
+            layer: Some(Layer::Character),
+        };
+        let player = world.insert_entity_data(player_location, player_data);
+        let topography_grid = Grid::new_fn(world.size(), |coord| {
+            noise.height(&self.config, self.world_origin() + coord)
+        });
+        let targets = [(Landmark::CabinDoor, cabin.door_coord)];
+        let trees_cleared =
+            match terrain::ensure_reachable(&mut world, &topography_grid, player_coord, &targets) {
+                Ok(trees_cleared) => trees_cleared,
+                Err(error) => {
+                    log::warn!("{}", error);
+                    0
+                }
+            };
+        Terrain {
+            world,
+            player,
+            cabin_direction: cabin.door_direction.opposite(),
+            stats: GenerationStats {
+                attempts: 1,
+                trees_cleared,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// When the player has left the middle chunk of the world, moves the world along so they're
+    /// in the middle again. Chunks left behind are taken out of the world, and chunks coming
+    /// into it are brought back from memory or generated. Returns how far everything moved.
+    pub fn recentre(&mut self, world: &mut World, player_coord: Coord) -> Option<Coord> {
+        let origin = self.chunk_of(player_coord) - Coord::new(WINDOW_CHUNKS / 2, WINDOW_CHUNKS / 2);
+        let old_origin = self.origin;
+        if origin == old_origin {
+            return None;
+        }
+        for chunk in Self::window(old_origin) {
+            if !Self::in_window(chunk, origin) {
+                let entities = world.take_chunk(self.corner(chunk));
+                self.unloaded.insert(
+                    chunk,
+                    Chunk {
+                        coord: chunk,
+                        entities,
+                    },
+                );
+            }
+        }
+        let offset = (old_origin - origin) * CHUNK_SIZE;
+        world.shift(offset);
+        self.origin = origin;
+        let noise = Noise::new(self.seed);
+        for chunk in Self::window(origin) {
+            if Self::in_window(chunk, old_origin) {
+                continue;
+            }
+            match self.unloaded.remove(&chunk) {
+                Some(Chunk { entities, .. }) => world.insert_chunk(self.corner(chunk), entities),
+                None => {
+                    if self.stored.remove(&chunk) {
+                        log::warn!(
+                            "chunk ({}, {}) wasn't handed back from storage in time, so it was generated again",
+                            chunk.x,
+                            chunk.y
+                        );
+                    }
+                    self.generate(world, &noise, chunk, false);
+                }
+            }
+        }
+        Some(offset)
+    }
+
+    /// Takes the chunks kept in memory that are now far from the player, to be stored
+    pub fn take_far(&mut self, player_coord: Coord) -> Vec<Chunk> {
+        let player_chunk = self.chunk_of(player_coord);
+        let far = self
+            .unloaded
+            .keys()
+            .filter(|&&chunk| chunk_distance(chunk, player_chunk) > KEEP_DISTANCE)
+            .cloned()
+            .collect::<Vec<_>>();
+        far.into_iter()
+            .filter_map(|chunk| {
+                self.stored.insert(chunk);
+                self.unloaded.remove(&chunk)
+            })
+            .collect()
+    }
+
+    /// Stored chunks that the player is getting close to, which should be handed back
+    pub fn wanted(&self, player_coord: Coord) -> Vec<Coord> {
+        let player_chunk = self.chunk_of(player_coord);
+        self.stored
+            .iter()
+            .filter(|&&chunk| chunk_distance(chunk, player_chunk) <= KEEP_DISTANCE)
+            .cloned()
+            .collect()
+    }
+
+    /// Hands back a stored chunk. If it couldn't be loaded, pass `None` and it will be generated
+    /// again when the world reaches it.
+    pub fn restore(&mut self, coord: Coord, chunk: Option<Chunk>) {
+        if !self.stored.remove(&coord) {
+            return;
+        }
+        match chunk {
+            Some(chunk) => {
+                self.unloaded.insert(coord, chunk);
+            }
+            None => log::warn!(
+                "chunk ({}, {}) couldn't be loaded, so it will be generated again",
+                coord.x,
+                coord.y
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::make_player;
+
+    const SEED: u64 = 0x5eed;
+
+    /// Describes everything on every level of the chunk with the given top-left corner, relative
+    /// to that corner, in an order that doesn't depend on the order things were spawned
+    fn chunk_contents(world: &mut World, corner: Coord) -> Vec<String> {
+        let mut contents = Vec::new();
+        world.for_each_level(|world| {
+            for entity in world.chunk_entities(corner) {
+                let location = world.spatial_table.location_of(entity).unwrap();
+                contents.push(format!(
+                    "{:?} {:?} {:?} {:?} {:?}",
+                    world.level,
+                    location.coord - corner,
+                    location.layer,
+                    world.components.clone_entity_data(entity),
+                    world.realtime_components.flicker.get(entity),
+                ));
+            }
+        });
+        contents.sort();
+        contents
+    }
+
+    fn world_contents(world: &mut World) -> Vec<String> {
+        let mut contents = Vec::new();
+        for chunk in Chunks::window(Coord::new(0, 0)) {
+            let corner = chunk * CHUNK_SIZE;
+            contents.extend(
+                chunk_contents(world, corner)
+                    .into_iter()
+                    .map(|entity| format!("{:?} {}", chunk, entity)),
+            );
+        }
+        contents.sort();
+        contents
+    }
+
+    #[test]
+    fn chunks_are_the_same_wherever_they_are_generated() {
+        let config = TerrainConfig::default();
+        let noise = Noise::new(SEED);
+        let chunk = Coord::new(3, -2);
+        let mut contents = Vec::new();
+        // the chunk at the top-left, bottom-right and middle of the world
+        for origin in [Coord::new(3, -2), Coord::new(1, -4), Coord::new(2, -3)] {
+            let mut chunks = Chunks::new(SEED, config);
+            chunks.origin = origin;
+            let mut world = World::new(WINDOW_SIZE);
+            chunks.generate(&mut world, &noise, chunk, false);
+            contents.push(chunk_contents(&mut world, chunks.corner(chunk)));
+        }
+        assert!(!contents[0].is_empty());
+        assert_eq!(contents[0], contents[1]);
+        assert_eq!(contents[0], contents[2]);
+    }
+
+    #[test]
+    fn take_chunk_then_insert_chunk_restores_every_level() {
+        // find a forest whose starting cabin has a loft or cellar
+        let mut terrain = (0..100)
+            .map(|seed| Chunks::new(seed, TerrainConfig::default()).generate_start(make_player()))
+            .find(|terrain| !terrain.world.levels.is_empty())
+            .expect("no cabin with a loft or cellar");
+        let world = &mut terrain.world;
+        let before = world_contents(world);
+        let corner = Coord::new(CHUNK_SIZE, CHUNK_SIZE);
+        let entities = world.take_chunk(corner);
+        assert!(entities.iter().any(|entity| entity.level != Level::Ground));
+        assert!(chunk_contents(world, corner).is_empty());
+        world.insert_chunk(corner, entities);
+        assert_eq!(world_contents(world), before);
+    }
+
+    /// Moves the player one chunk in the given direction, storing and handing back chunks the
+    /// way the app does
+    fn walk(
+        chunks: &mut Chunks,
+        world: &mut World,
+        stored: &mut HashMap<Coord, Chunk>,
+        direction: Coord,
+    ) {
+        // the player stays in the middle chunk, which is where they are after recentring
+        let middle = Coord::new(CHUNK_SIZE + 1, CHUNK_SIZE + 1);
+        let offset = chunks
+            .recentre(world, middle + direction * CHUNK_SIZE)
+            .unwrap();
+        assert_eq!(offset, direction * -CHUNK_SIZE);
+        for chunk in chunks.take_far(middle) {
+            stored.insert(chunk.coord(), chunk);
+        }
+        for coord in chunks.wanted(middle) {
+            chunks.restore(coord, stored.remove(&coord));
+        }
+    }
+
+    #[test]
+    fn walking_away_and_back_restores_the_world() {
+        let mut chunks = Chunks::new(SEED, TerrainConfig::default());
+        let mut world = chunks.generate_start(make_player()).world;
+        // a change that would be lost if the leftmost chunks were generated again
+        let floor = world
+            .spatial_table
+            .layers_at_checked(Coord::new(1, 1))
+            .floor;
+        world.components.height.insert(floor.unwrap(), 100.);
+        let before = world_contents(&mut world);
+        let mut stored = HashMap::new();
+        for _ in 0..3 {
+            walk(&mut chunks, &mut world, &mut stored, Coord::new(1, 0));
+        }
+        assert_eq!(stored.len(), 2 * WINDOW_CHUNKS as usize);
+        for _ in 0..3 {
+            walk(&mut chunks, &mut world, &mut stored, Coord::new(-1, 0));
+        }
+        // the chunks walked past on the way out were stored in turn
+        assert!(stored
+            .keys()
+            .all(|&chunk| chunk.x > 1 && !Chunks::in_window(chunk, chunks.origin)));
+        assert_eq!(chunks.origin, Coord::new(-1, -1));
+        assert_eq!(world_contents(&mut world), before);
+    }
+}
@@ -1,7 +1,7 @@
-use crate::visibility::Light;
+use crate::{level::Level, visibility::Light};
 use gridbugs::{
-    entity_table::declare_entity_module, grid_2d::coord_2d::Axis, rgb_int::Rgb24,
-    shadowcast::vision_distance::Circle,
+    direction::CardinalDirection, entity_table::declare_entity_module, grid_2d::coord_2d::Axis,
+    rgb_int::Rgb24, shadowcast::vision_distance::Circle,
 };
 use rational::Rational;
 use serde::{Deserialize, Serialize};
@@ -35,6 +35,25 @@ declare_entity_module! {
         equipment: Equipment,
         push: (),
         ditch: (),
+        fallen_log: (),
+        mud: (),
+        footprint: Footprint,
+        lake_depth: u32,
+        boat: (),
+        cairn: (),
+        stove: (),
+        kitchen_bench: (),
+        trail: (),
+        signpost: String,
+        stream: (),
+        ford: (),
+        bridge: (),
+        lookout: (),
+        ladder: (),
+        stairs: Level,
+        cellar: (),
+        forage_patch: Item,
+        mushroom: u32,
     }
 }
 pub use components::Components;
@@ -77,6 +96,30 @@ pub enum Tile {
     Lantern,
     Crowbar,
     Ditch,
+    Log(Axis),
+    Axe,
+    Firewood,
+    Mud,
+    Puddle,
+    Boat,
+    Cairn,
+    FishingRod,
+    Stove,
+    Mushroom,
+    KitchenBench,
+    Trail,
+    Signpost,
+    Stream,
+    Ford,
+    Bridge,
+    Lookout,
+    TowerLeg,
+    Table,
+    Bookshelf,
+    Ladder,
+    Hatch,
+    StairsDown,
+    StairsUp,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -84,6 +127,8 @@ pub enum Item {
     Flower,
     Tea,
     Rock,
+    Firewood,
+    Mushroom,
 }
 
 impl Item {
@@ -92,6 +137,8 @@ impl Item {
             Self::Flower => "flower",
             Self::Tea => "tea",
             Self::Rock => "rock",
+            Self::Firewood => "firewood",
+            Self::Mushroom => "mushroom",
         }
         .to_string()
     }
@@ -106,6 +153,15 @@ pub enum Equipment {
     WeatherReport,
     Lantern,
     Crowbar,
+    Axe,
+    FishingRod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Footprint {
+    pub direction: CardinalDirection,
+    /// Decreases as the rain washes the footprint away
+    pub strength: u8,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
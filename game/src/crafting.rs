@@ -0,0 +1,129 @@
+use crate::components::Item;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+/// Seconds spent preparing a recipe
+pub const CRAFT_TIME: u32 = 900;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Ingredient {
+    Fish,
+    Mushroom,
+    Tea,
+    Flower,
+    Firewood,
+}
+
+impl Ingredient {
+    /// The ingredient that an item can be used as, if any
+    pub fn from_item(item: Item) -> Option<Self> {
+        match item {
+            Item::Mushroom => Some(Self::Mushroom),
+            Item::Tea => Some(Self::Tea),
+            Item::Flower => Some(Self::Flower),
+            Item::Firewood => Some(Self::Firewood),
+            Item::Rock => None,
+        }
+    }
+}
+
+impl fmt::Display for Ingredient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Fish => "fish",
+            Self::Mushroom => "mushroom",
+            Self::Tea => "tea",
+            Self::Flower => "flower",
+            Self::Firewood => "firewood",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+pub struct Recipe {
+    pub name: &'static str,
+    pub ingredients: &'static [(Ingredient, u32)],
+    pub motivation: i32,
+    pub flavour: &'static str,
+}
+
+impl Recipe {
+    pub fn ingredients_string(&self) -> String {
+        self.ingredients
+            .iter()
+            .map(|(ingredient, count)| format!("{} {}", count, ingredient))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Everything that can be made at the kitchen bench. Each recipe can be made once per day.
+pub const RECIPES: &[Recipe] = &[
+    Recipe {
+        name: "Mushroom Soup",
+        ingredients: &[(Ingredient::Mushroom, 2)],
+        motivation: 350,
+        flavour: "You simmer the mushrooms into a thick, earthy soup. The windows steam up while you eat.",
+    },
+    Recipe {
+        name: "Fish Stew",
+        ingredients: &[(Ingredient::Fish, 1), (Ingredient::Mushroom, 1)],
+        motivation: 450,
+        flavour: "You stew the fish with a mushroom until it falls apart. It tastes of the lake and the forest.",
+    },
+    Recipe {
+        name: "Smoked Fish",
+        ingredients: &[(Ingredient::Fish, 1), (Ingredient::Firewood, 1)],
+        motivation: 400,
+        flavour: "You smoke the fish slowly over a bed of firewood. The whole cabin smells wonderful.",
+    },
+    Recipe {
+        name: "Flower Tea",
+        ingredients: &[(Ingredient::Tea, 1), (Ingredient::Flower, 1)],
+        motivation: 300,
+        flavour: "You steep the tea leaves with a few petals. It's fragrant and a little sweet.",
+    },
+    Recipe {
+        name: "Bouquet",
+        ingredients: &[(Ingredient::Flower, 3)],
+        motivation: 300,
+        flavour: "You arrange the flowers in a jar on the windowsill. The cabin feels a little more like home.",
+    },
+];
+
+/// Ingredients that have been left on the kitchen bench
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Pantry {
+    counts: BTreeMap<Ingredient, u32>,
+}
+
+impl Pantry {
+    pub fn add(&mut self, ingredient: Ingredient) {
+        *self.counts.entry(ingredient).or_insert(0) += 1;
+    }
+
+    pub fn count(&self, ingredient: Ingredient) -> u32 {
+        self.counts.get(&ingredient).cloned().unwrap_or(0)
+    }
+
+    /// Removes up to `count` of an ingredient, returning how many were removed
+    pub fn take(&mut self, ingredient: Ingredient, count: u32) -> u32 {
+        let stored = self.counts.entry(ingredient).or_insert(0);
+        let taken = count.min(*stored);
+        *stored -= taken;
+        taken
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.values().all(|&count| count == 0)
+    }
+
+    pub fn contents_string(&self) -> String {
+        self.counts
+            .iter()
+            .filter(|(_, &count)| count > 0)
+            .map(|(ingredient, count)| format!("{} {}", count, ingredient))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
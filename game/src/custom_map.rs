@@ -0,0 +1,763 @@
+use crate::{
+    components::{EntityData, Tile},
+    game::{RainLevel, Time},
+    prefab::{PrefabLibrary, Spawn, Transform},
+    spatial::{Layer, Location},
+    terrain::{GenerationStats, Terrain, TerrainConfig},
+    world::World,
+};
+use gridbugs::{
+    direction::CardinalDirection,
+    entity_table::Entity,
+    grid_2d::{coord_2d::Axis, Coord, Grid, Size},
+};
+use rand::Rng;
+use std::collections::BTreeMap;
+
+/// Number of days in the rain schedule
+const NUM_DAYS: usize = 6;
+
+/// The rain schedule gives a level for each 4 hour period of the day
+const PERIODS_PER_DAY: usize = 6;
+
+/// Height of cells not covered by the heights section, unless the map sets its own default
+const DEFAULT_HEIGHT: f64 = 0.;
+
+/// The characters that can be used in a map without being declared, and what they stand for.
+/// Maps can redefine any of these with the "legend" keyword.
+const DEFAULT_LEGEND: &[(char, &str)] = &[
+    ('.', "ground"),
+    (',', "floor"),
+    ('#', "floor wall"),
+    ('+', "floor door"),
+    ('%', "floor window"),
+    ('L', "floor light"),
+    ('M', "floor light"),
+    ('b', "floor bed"),
+    ('<', "floor chair_left"),
+    ('>', "floor chair_right"),
+    ('c', "floor teapot"),
+    ('n', "floor table"),
+    ('h', "floor bookshelf"),
+    ('O', "floor stove"),
+    ('k', "floor kitchen_bench"),
+    ('H', "floor ladder"),
+    ('1', "floor gumboots"),
+    ('2', "floor umbrella"),
+    ('3', "floor shovel"),
+    ('4', "floor map"),
+    ('5', "floor weather_report"),
+    ('6', "ground lantern"),
+    ('7', "ground crowbar"),
+    ('8', "ground axe"),
+    ('9', "ground fishing_rod"),
+    ('w', "ground firewood"),
+    ('&', "ground tree"),
+    ('"', "ground grass"),
+    ('*', "ground rock"),
+    ('m', "ground mushroom"),
+    ('F', "ground flower_patch flower"),
+    ('T', "ground tea_patch tea"),
+    ('-', "ground log_x"),
+    ('|', "ground log_y"),
+    ('N', "ground bulletin_board"),
+    ('l', "ground lamp"),
+    ('S', "ground signpost"),
+    ('C', "ground cairn"),
+    ('I', "ground tower_leg"),
+    ('Y', "lookout"),
+    ('t', "trail"),
+    ('s', "stream"),
+    ('o', "ford"),
+    ('=', "bridge"),
+    ('r', "ruins_floor"),
+    ('R', "ruins_floor ruins_wall"),
+    ('A', "ruins_floor altar"),
+    ('p', "pier_floor"),
+    ('P', "end_of_pier"),
+    ('~', "water"),
+    ('W', "lake"),
+    ('B', "lake boat"),
+    ('@', "ground player"),
+];
+
+/// What to place in a cell of the map
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct Cell {
+    spawns: Vec<Spawn>,
+    /// The player starts in this cell
+    player: bool,
+    /// Text for a signpost in this cell
+    sign: Option<String>,
+}
+
+impl Cell {
+    fn parse(names: &[&str]) -> Result<Self, String> {
+        let mut cell = Self::default();
+        for &name in names {
+            if name == "player" {
+                cell.player = true;
+            } else {
+                let spawn =
+                    Spawn::from_name(name).ok_or_else(|| format!("unknown spawn {}", name))?;
+                let clash = cell
+                    .spawns
+                    .iter()
+                    .find(|other| spawn.layer().is_some() && other.layer() == spawn.layer());
+                if let Some(other) = clash {
+                    return Err(format!("{} can't go in the same cell as {:?}", name, other));
+                }
+                cell.spawns.push(spawn);
+            }
+        }
+        Ok(cell)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.spawns.is_empty() && !self.player
+    }
+
+    /// The names that make up this cell's entry in the legend
+    fn names(&self) -> String {
+        let mut names = self
+            .spawns
+            .iter()
+            .map(|spawn| spawn.name())
+            .collect::<Vec<_>>();
+        if self.player {
+            names.push("player");
+        }
+        names.join(" ")
+    }
+
+    /// Adds a spawn to the cell, replacing anything already in the same layer. Floors go first
+    /// so they're spawned before whatever stands on them. Painting "clear" removes everything
+    /// but the floor.
+    fn paint(&mut self, spawn: Spawn) {
+        if spawn == Spawn::Clear {
+            self.spawns
+                .retain(|spawn| spawn.layer() == Some(Layer::Floor));
+            self.sign = None;
+            return;
+        }
+        match spawn.layer() {
+            Some(layer) => {
+                self.spawns.retain(|other| other.layer() != Some(layer));
+                if layer == Layer::Feature {
+                    self.sign = None;
+                }
+            }
+            None => {
+                if self.spawns.contains(&spawn) {
+                    return;
+                }
+            }
+        }
+        if spawn.layer() == Some(Layer::Floor) {
+            self.spawns.insert(0, spawn);
+        } else {
+            self.spawns.push(spawn);
+        }
+    }
+
+    /// Removes the last thing painted into the cell. A floor that's all that is left turns back
+    /// into ground instead, so the cell always has something to stand on.
+    fn erase(&mut self) {
+        match self.spawns.as_slice() {
+            [Spawn::Ground] => (),
+            [floor] if floor.layer() == Some(Layer::Floor) => self.spawns = vec![Spawn::Ground],
+            _ => {
+                if self.spawns.pop() == Some(Spawn::Signpost) {
+                    self.sign = None;
+                }
+            }
+        }
+    }
+}
+
+fn default_legend() -> BTreeMap<char, Cell> {
+    DEFAULT_LEGEND
+        .iter()
+        .map(|&(ch, names)| {
+            let names = names.split(' ').collect::<Vec<_>>();
+            (ch, Cell::parse(&names).unwrap())
+        })
+        .collect()
+}
+
+fn rain_level_from_name(name: &str) -> Option<RainLevel> {
+    match name {
+        "light" => Some(RainLevel::Light),
+        "medium" => Some(RainLevel::Medium),
+        "heavy" => Some(RainLevel::Heavy),
+        _ => None,
+    }
+}
+
+fn rain_level_name(rain_level: RainLevel) -> &'static str {
+    match rain_level {
+        RainLevel::Light => "light",
+        RainLevel::Medium => "medium",
+        RainLevel::Heavy => "heavy",
+    }
+}
+
+/// A hand-authored map loaded from a text file. See the "Custom Maps" section of the README for
+/// the format.
+#[derive(Debug, Clone)]
+pub struct CustomMap {
+    name: String,
+    start_time: Option<Time>,
+    rain: BTreeMap<usize, [RainLevel; PERIODS_PER_DAY]>,
+    default_height: f64,
+    cells: Grid<Cell>,
+    /// Digits from the heights section, where one was given
+    heights: Grid<Option<u8>>,
+}
+
+impl CustomMap {
+    /// A map of bare ground with the player in the middle
+    pub fn new(name: &str, size: Size) -> Self {
+        let mut cells = Grid::new_fn(size, |_| Cell {
+            spawns: vec![Spawn::Ground],
+            ..Default::default()
+        });
+        if let Some(cell) = cells.get_mut(size.to_coord().unwrap_or_default() / 2) {
+            cell.player = true;
+        }
+        Self {
+            name: name.to_string(),
+            start_time: None,
+            rain: BTreeMap::new(),
+            default_height: DEFAULT_HEIGHT,
+            cells,
+            heights: Grid::new_copy(size, None),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut name = "Custom Map".to_string();
+        let mut start_time = None;
+        let mut rain = BTreeMap::new();
+        let mut default_height = DEFAULT_HEIGHT;
+        let mut legend = default_legend();
+        let mut signs = BTreeMap::new();
+        let mut lines = text.split('\n').map(|line| line.trim_end_matches('\r'));
+        for line in lines.by_ref() {
+            if line.is_empty() || line.starts_with("# ") {
+                continue;
+            }
+            let mut words = line.split(' ');
+            let keyword = words.next().unwrap_or("");
+            let args = words.collect::<Vec<_>>();
+            let numbers = |count: usize| -> Result<Vec<f64>, String> {
+                let numbers = args
+                    .iter()
+                    .take(count)
+                    .map(|arg| arg.parse::<f64>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| format!("expected {} number(s) after \"{}\"", count, keyword))?;
+                if numbers.len() < count {
+                    return Err(format!(
+                        "expected {} number(s) after \"{}\"",
+                        count, keyword
+                    ));
+                }
+                Ok(numbers)
+            };
+            let character = || -> Result<char, String> {
+                let mut chars = args.first().map(|arg| arg.chars()).into_iter().flatten();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => Ok(ch),
+                    _ => Err(format!("expected a character after \"{}\"", keyword)),
+                }
+            };
+            match keyword {
+                "map" => break,
+                "name" => name = args.join(" "),
+                "start_time" => {
+                    let numbers = numbers(3)?;
+                    let (day, hour, minute) = (numbers[0] as u32, numbers[1] as u32, numbers[2]);
+                    if day as usize >= NUM_DAYS || hour >= 24 || minute >= 60. {
+                        return Err(format!("invalid start time in \"{}\"", line));
+                    }
+                    start_time = Some(Time::new(day, hour, minute as u32, 0));
+                }
+                "rain" => {
+                    let day = numbers(1)?[0] as usize;
+                    if day >= NUM_DAYS {
+                        return Err(format!("no day {} in the rain schedule", day));
+                    }
+                    let levels = args[1..]
+                        .iter()
+                        .map(|&name| {
+                            rain_level_from_name(name)
+                                .ok_or_else(|| format!("unknown rain level {}", name))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let levels =
+                        <[RainLevel; PERIODS_PER_DAY]>::try_from(levels).map_err(|_| {
+                            format!("expected {} rain levels for day {}", PERIODS_PER_DAY, day)
+                        })?;
+                    rain.insert(day, levels);
+                }
+                "height" => default_height = numbers(1)?[0],
+                "legend" => {
+                    let ch = character()?;
+                    legend.insert(ch, Cell::parse(&args[1..])?);
+                }
+                "sign" => {
+                    let ch = character()?;
+                    signs.insert(ch, args[1..].join(" ").replace("\\n", "\n"));
+                }
+                other => return Err(format!("unknown keyword \"{}\"", other)),
+            }
+        }
+        let mut rows = Vec::new();
+        let mut heights = Vec::new();
+        let mut in_heights = false;
+        for line in lines {
+            if line == "heights" && !in_heights {
+                in_heights = true;
+            } else if in_heights {
+                heights.push(line.chars().collect::<Vec<_>>());
+            } else {
+                rows.push(line.chars().collect::<Vec<_>>());
+            }
+        }
+        for section in [&mut rows, &mut heights] {
+            while section.last().map(|row| row.is_empty()).unwrap_or(false) {
+                section.pop();
+            }
+        }
+        if rows.iter().all(|row| row.is_empty()) {
+            return Err("empty map".to_string());
+        }
+        let mut num_players = 0;
+        for row in &rows {
+            for ch in row {
+                match legend.get(ch) {
+                    Some(cell) => {
+                        if cell.player {
+                            num_players += 1;
+                        }
+                    }
+                    None if *ch == ' ' => (),
+                    None => return Err(format!("unexpected char {}", ch)),
+                }
+            }
+        }
+        if num_players != 1 {
+            return Err(format!(
+                "the player must start in exactly one cell, but {} are marked",
+                num_players
+            ));
+        }
+        for row in &heights {
+            for ch in row {
+                if !ch.is_ascii_digit() && *ch != ' ' {
+                    return Err(format!("unexpected char {} in heights", ch));
+                }
+            }
+        }
+        for ch in signs.keys() {
+            if !legend.contains_key(ch) {
+                return Err(format!("sign {} isn't in the legend", ch));
+            }
+        }
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        if heights.len() > rows.len() || heights.iter().any(|row| row.len() > width) {
+            return Err("the heights section is bigger than the map".to_string());
+        }
+        let size = Size::new_u16(width as u16, rows.len() as u16);
+        let cells = Grid::new_fn(size, |coord| {
+            let ch = rows[coord.y as usize].get(coord.x as usize);
+            let mut cell = ch
+                .and_then(|ch| legend.get(ch))
+                .cloned()
+                .unwrap_or_default();
+            cell.sign = ch.and_then(|ch| signs.get(ch)).cloned();
+            cell
+        });
+        let heights = Grid::new_fn(size, |coord| {
+            heights
+                .get(coord.y as usize)
+                .and_then(|row| row.get(coord.x as usize))
+                .and_then(|ch| ch.to_digit(10))
+                .map(|digit| digit as u8)
+        });
+        Ok(Self {
+            name,
+            start_time,
+            rain,
+            default_height,
+            cells,
+            heights,
+        })
+    }
+
+    /// Writes the map in the format read by `parse`. Cells that match an entry in the default
+    /// legend use its character, and new characters are declared for any others.
+    pub fn to_text(&self) -> String {
+        let mut default_chars = BTreeMap::new();
+        for (ch, cell) in default_legend() {
+            default_chars.entry(cell).or_insert(ch);
+        }
+        let mut spare_chars = ('!'..='~')
+            .chain('\u{a1}'..=char::MAX)
+            .filter(|ch| !ch.is_whitespace() && !ch.is_control())
+            .filter(|ch| !DEFAULT_LEGEND.iter().any(|&(other, _)| other == *ch));
+        let mut chars = BTreeMap::new();
+        let mut legend = Vec::new();
+        for cell in self.cells.iter() {
+            if cell.is_empty() || chars.contains_key(cell) {
+                continue;
+            }
+            let ch = match default_chars.get(cell) {
+                Some(&ch) => ch,
+                None => {
+                    let ch = spare_chars.next().expect("ran out of characters");
+                    legend.push((ch, cell));
+                    ch
+                }
+            };
+            chars.insert(cell, ch);
+        }
+        let mut text = String::new();
+        text.push_str("# Written by the map editor\n");
+        text.push_str(&format!("name {}\n", self.name));
+        if let Some(time) = self.start_time {
+            text.push_str(&format!(
+                "start_time {} {} {}\n",
+                time.day(),
+                time.hour(),
+                time.minute()
+            ));
+        }
+        for (day, levels) in self.rain() {
+            let levels = levels
+                .iter()
+                .map(|&level| rain_level_name(level))
+                .collect::<Vec<_>>();
+            text.push_str(&format!("rain {} {}\n", day, levels.join(" ")));
+        }
+        if self.default_height != DEFAULT_HEIGHT {
+            text.push_str(&format!("height {}\n", self.default_height));
+        }
+        for (ch, cell) in legend {
+            text.push_str(&format!("legend {} {}\n", ch, cell.names()));
+            if let Some(sign) = cell.sign.as_ref() {
+                text.push_str(&format!("sign {} {}\n", ch, sign.replace('\n', "\\n")));
+            }
+        }
+        text.push_str("map\n");
+        for row in self.cells.rows() {
+            let row = row
+                .iter()
+                .map(|cell| chars.get(cell).copied().unwrap_or(' '))
+                .collect::<String>();
+            text.push_str(&row);
+            text.push('\n');
+        }
+        if self.heights.iter().any(|height| height.is_some()) {
+            text.push_str("heights\n");
+            for row in self.heights.rows() {
+                let row = row
+                    .iter()
+                    .map(|height| {
+                        height
+                            .and_then(|digit| char::from_digit(digit as u32, 10))
+                            .unwrap_or(' ')
+                    })
+                    .collect::<String>();
+                text.push_str(row.trim_end());
+                text.push('\n');
+            }
+        }
+        text
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn start_time(&self) -> Option<Time> {
+        self.start_time
+    }
+
+    pub fn set_start_time(&mut self, start_time: Option<Time>) {
+        self.start_time = start_time;
+    }
+
+    /// The rain levels the map sets for particular days, by day
+    pub fn rain(&self) -> impl '_ + Iterator<Item = (usize, [RainLevel; PERIODS_PER_DAY])> {
+        self.rain.iter().map(|(&day, &levels)| (day, levels))
+    }
+
+    pub fn size(&self) -> Size {
+        self.cells.size()
+    }
+
+    /// Digits in the heights section give heights from 0 for '0' to 1 for '9'
+    pub fn height(&self, coord: Coord) -> f64 {
+        self.heights
+            .get(coord)
+            .copied()
+            .flatten()
+            .map(|digit| digit as f64 / 9.)
+            .unwrap_or(self.default_height)
+    }
+
+    pub fn player_coord(&self) -> Coord {
+        self.cells
+            .enumerate()
+            .find(|(_, cell)| cell.player)
+            .map(|(coord, _)| coord)
+            .expect("map has no player")
+    }
+
+    /// Adds something to a cell, replacing whatever was in the same layer
+    pub fn paint(&mut self, coord: Coord, spawn: Spawn) {
+        if let Some(cell) = self.cells.get_mut(coord) {
+            cell.paint(spawn);
+        }
+    }
+
+    /// Removes the last thing added to a cell
+    pub fn erase(&mut self, coord: Coord) {
+        if let Some(cell) = self.cells.get_mut(coord) {
+            cell.erase();
+        }
+    }
+
+    /// Moves the player's starting position
+    pub fn set_player(&mut self, coord: Coord) {
+        if self.cells.get(coord).is_none() {
+            return;
+        }
+        for cell in self.cells.iter_mut() {
+            cell.player = false;
+        }
+        self.cells.get_checked_mut(coord).player = true;
+    }
+
+    /// Raises (or lowers, for negative `delta`) every cell within `radius` of `centre` by
+    /// `delta` steps of the heights section
+    pub fn adjust_height(&mut self, centre: Coord, radius: u32, delta: i32) {
+        let default_digit = (self.default_height * 9.).round().clamp(0., 9.) as i32;
+        let radius_squared = (radius * radius) as i32;
+        for (coord, height) in self.heights.enumerate_mut() {
+            if (coord - centre).magnitude2() as i32 > radius_squared {
+                continue;
+            }
+            let digit = height.map(|digit| digit as i32).unwrap_or(default_digit);
+            *height = Some((digit + delta).clamp(0, 9) as u8);
+        }
+    }
+
+    /// The names of the prefabs that can be stamped onto a map
+    pub fn prefab_names() -> Vec<&'static str> {
+        PrefabLibrary::names().collect()
+    }
+
+    /// Paints the cells of a prefab from the library onto the map, centred on `centre`
+    pub fn stamp_prefab(&mut self, centre: Coord, name: &str) {
+        let library = PrefabLibrary::new(&TerrainConfig::default());
+        for (offset, spawns) in library.get(name).cells(Transform::default()) {
+            for &spawn in spawns {
+                self.paint(centre + offset, spawn);
+            }
+        }
+    }
+
+    /// Spawns everything in the map into a new world
+    pub fn build<R: Rng>(&self, player_data: EntityData, rng: &mut R) -> Terrain {
+        let mut world = World::new(self.size());
+        let mut player_data = Some(player_data);
+        let mut player = None;
+        let mut openings = Vec::new();
+        for (coord, cell) in self.cells.enumerate() {
+            let height = self.height(coord);
+            for &spawn in &cell.spawns {
+                world.spawn_prefab_cell(spawn, coord, height, rng);
+                if spawn == Spawn::Door || spawn == Spawn::Window {
+                    openings.push(coord);
+                }
+            }
+            if let Some(text) = cell.sign.as_ref() {
+                if let Some(feature) = world.spatial_table.layers_at_checked(coord).feature {
+                    world.components.signpost.insert(feature, text.clone());
+                }
+            }
+            if cell.player {
+                let location = Location {
+                    coord,
+                    layer: Some(Layer::Character),
+                };
+                player = Some(world.insert_entity_data(location, player_data.take().unwrap()));
+            }
+        }
+        for coord in openings {
+            orient_opening(&mut world, coord);
+        }
+        let player = player.expect("map was checked for a player when parsed");
+        let cabin_direction = cabin_direction(&world, player);
+        Terrain {
+            world,
+            player,
+            cabin_direction,
+            stats: GenerationStats::default(),
+        }
+    }
+}
+
+/// Turns the door or window at a coordinate so that it lies along the wall it's part of
+fn orient_opening(world: &mut World, coord: Coord) {
+    let is_wall = |offset: Coord| {
+        world
+            .spatial_table
+            .layers_at(coord + offset)
+            .and_then(|layers| layers.feature)
+            .and_then(|feature| world.components.tile.get(feature))
+            .map(|tile| {
+                matches!(
+                    tile,
+                    Tile::Wall | Tile::DoorClosed(_) | Tile::Window(_) | Tile::RuinsWall
+                )
+            })
+            .unwrap_or(false)
+    };
+    let axis = if is_wall(Coord::new(-1, 0)) || is_wall(Coord::new(1, 0)) {
+        Axis::Y
+    } else {
+        Axis::X
+    };
+    if let Some(feature) = world.spatial_table.layers_at_checked(coord).feature {
+        let tile = match world.components.tile.get(feature) {
+            Some(Tile::DoorClosed(_)) => Tile::DoorClosed(axis),
+            Some(Tile::Window(_)) => Tile::Window(axis),
+            _ => return,
+        };
+        world.components.tile.insert(feature, tile);
+    }
+}
+
+/// The direction from the player to the nearest bed, which the intro uses to point the way to
+/// the cabin
+fn cabin_direction(world: &World, player: Entity) -> CardinalDirection {
+    let player_coord = world.spatial_table.coord_of(player).unwrap();
+    world
+        .components
+        .bed
+        .entities()
+        .filter_map(|bed| world.spatial_table.coord_of(bed))
+        .min_by_key(|&bed| bed.manhattan_distance(player_coord))
+        .map(|bed| {
+            let delta = bed - player_coord;
+            if delta.x.abs() > delta.y.abs() {
+                if delta.x > 0 {
+                    CardinalDirection::East
+                } else {
+                    CardinalDirection::West
+                }
+            } else if delta.y > 0 {
+                CardinalDirection::South
+            } else {
+                CardinalDirection::North
+            }
+        })
+        .unwrap_or(CardinalDirection::West)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAP: &str = "\
+name Test Map
+start_time 0 6 30
+rain 1 light light medium heavy medium light
+legend x ground tree
+sign S A sign\\nover two lines
+map
+#####
+#b,+.x
+#####.S@
+heights
+11111
+1234
+";
+
+    #[test]
+    fn parse_and_write_round_trip() {
+        let map = CustomMap::parse(MAP).unwrap();
+        assert_eq!(map.name(), "Test Map");
+        assert_eq!(map.size(), Size::new(8, 3));
+        assert_eq!(map.player_coord(), Coord::new(7, 2));
+        let text = map.to_text();
+        let reparsed = CustomMap::parse(&text).unwrap();
+        assert_eq!(reparsed.to_text(), text);
+        assert_eq!(reparsed.cells, map.cells);
+        assert_eq!(reparsed.heights, map.heights);
+        assert_eq!(reparsed.rain().count(), 1);
+        assert_eq!(reparsed.start_time().map(|time| time.hour()), Some(6));
+    }
+
+    #[test]
+    fn ragged_rows_are_padded_with_empty_cells() {
+        let map = CustomMap::parse("map\n..\n.@.\n").unwrap();
+        assert_eq!(map.size(), Size::new(3, 2));
+        assert!(map.cells.get_checked(Coord::new(2, 0)).is_empty());
+    }
+
+    #[test]
+    fn erasing_leaves_ground() {
+        let mut map = CustomMap::parse("legend x trail rock\nmap\nx@\n").unwrap();
+        let coord = Coord::new(0, 0);
+        map.erase(coord);
+        assert_eq!(map.cells.get_checked(coord).spawns, vec![Spawn::Trail]);
+        map.erase(coord);
+        assert_eq!(map.cells.get_checked(coord).spawns, vec![Spawn::Ground]);
+        map.erase(coord);
+        assert_eq!(map.cells.get_checked(coord).spawns, vec![Spawn::Ground]);
+    }
+
+    #[test]
+    fn reject_bad_maps() {
+        let reject = |text: &str| CustomMap::parse(text).unwrap_err();
+        assert_eq!(reject("map\n.x@\n"), "unexpected char x");
+        assert_eq!(
+            reject("legend x ground dragon\nmap\n.x@\n"),
+            "unknown spawn dragon"
+        );
+        assert_eq!(
+            reject("sign x hello\nmap\n.@\n"),
+            "sign x isn't in the legend"
+        );
+        assert_eq!(
+            reject("map\n...\n"),
+            "the player must start in exactly one cell, but 0 are marked"
+        );
+        assert_eq!(
+            reject("map\n@@\n"),
+            "the player must start in exactly one cell, but 2 are marked"
+        );
+        assert_eq!(
+            reject("map\n.@\nheights\n123\n"),
+            "the heights section is bigger than the map"
+        );
+        assert_eq!(
+            reject("map\n.@\nheights\n1x\n"),
+            "unexpected char x in heights"
+        );
+        assert_eq!(
+            reject("height tall\nmap\n@\n"),
+            "expected 1 number(s) after \"height\""
+        );
+        assert_eq!(
+            reject("rain 1 light\nmap\n@\n"),
+            "expected 6 rain levels for day 1"
+        );
+        assert_eq!(reject("map\n"), "empty map");
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{components::Tile, world::World};
+use gridbugs::{direction::CardinalDirection, entity_table::Entity, grid_2d::Coord};
+use rand::{seq::IteratorRandom, Rng};
+use rational::Rational;
+
+/// Number of turns of uninterrupted heavy rain before the ground starts to erode
+pub const SUSTAINED_HEAVY_RAIN_TURNS: u32 = 60;
+
+/// Number of ground cells considered for erosion each turn
+const CELLS_PER_TURN: usize = 200;
+
+/// Height difference between neighbouring cells above which soil starts washing downhill
+const STEEP_SLOPE: f64 = 0.025;
+
+/// Fraction of the excess slope that washes downhill each time a cell erodes
+const EROSION_RATE: f64 = 0.25;
+
+/// Height difference above which a mud slide can occur
+const SLIDE_SLOPE: f64 = 0.04;
+
+/// Chance of a mud slide starting each time a sufficiently steep cell erodes
+const SLIDE_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 200,
+};
+
+/// Chance that a slide spills sideways onto the cells either side of its path
+const SLIDE_SPREAD_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 2,
+};
+
+const SLIDE_MAX_LENGTH: usize = 10;
+const SLIDE_HEIGHT: f64 = 0.05;
+
+impl World {
+    fn ground_at(&self, coord: Coord) -> Option<Entity> {
+        self.spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.floor)
+            .filter(|&floor| self.components.ground.contains(floor))
+    }
+
+    /// The lowest neighbouring ground cell, if it's lower than the given cell
+    fn downhill_neighbour(&self, coord: Coord) -> Option<(Coord, Entity, f64)> {
+        let height = self
+            .ground_at(coord)
+            .and_then(|e| self.components.height.get(e))?;
+        CardinalDirection::all()
+            .filter_map(|d| {
+                let neighbour_coord = coord + d.coord();
+                let neighbour = self.ground_at(neighbour_coord)?;
+                let neighbour_height = *self.components.height.get(neighbour)?;
+                Some((neighbour_coord, neighbour, height - neighbour_height))
+            })
+            .filter(|&(_, _, slope)| slope > 0.)
+            .max_by(|a, b| a.2.partial_cmp(&b.2).unwrap())
+    }
+
+    /// Washes soil downhill from steep slopes, occasionally triggering a mud slide. Called once
+    /// per turn during sustained heavy rain.
+    pub fn erode<R: Rng>(&mut self, rng: &mut R) {
+        let cells = self
+            .components
+            .ground
+            .entities()
+            .choose_multiple(rng, CELLS_PER_TURN);
+        for entity in cells {
+            let coord = match self.spatial_table.coord_of(entity) {
+                Some(coord) => coord,
+                None => continue,
+            };
+            let (_, downhill, slope) = match self.downhill_neighbour(coord) {
+                Some(neighbour) => neighbour,
+                None => continue,
+            };
+            if slope < STEEP_SLOPE {
+                continue;
+            }
+            if slope > SLIDE_SLOPE && SLIDE_CHANCE.roll(rng) {
+                self.mud_slide(coord, rng);
+                continue;
+            }
+            let amount = (slope - STEEP_SLOPE) * EROSION_RATE;
+            if let Some(height) = self.components.height.get_mut(entity) {
+                *height -= amount;
+            }
+            if let Some(height) = self.components.height.get_mut(downhill) {
+                *height += amount;
+            }
+        }
+    }
+
+    /// Sends a slide of mud downhill from the given cell, covering the ground it passes over
+    fn mud_slide<R: Rng>(&mut self, start: Coord, rng: &mut R) {
+        let mut path = Vec::new();
+        let mut coord = start;
+        while path.len() < SLIDE_MAX_LENGTH {
+            match self.downhill_neighbour(coord) {
+                Some((next, _, _)) => {
+                    path.push((coord, next - coord));
+                    coord = next;
+                }
+                None => break,
+            }
+        }
+        if path.is_empty() {
+            return;
+        }
+        if let Some(height) = self
+            .ground_at(start)
+            .and_then(|e| self.components.height.get_mut(e))
+        {
+            *height -= SLIDE_HEIGHT;
+        }
+        let deposit = SLIDE_HEIGHT / path.len() as f64;
+        for (coord, step) in path {
+            let next = coord + step;
+            if let Some(height) = self
+                .ground_at(next)
+                .and_then(|e| self.components.height.get_mut(e))
+            {
+                *height += deposit;
+            }
+            self.cover_with_mud(next);
+            if SLIDE_SPREAD_CHANCE.roll(rng) {
+                self.cover_with_mud(next + Coord::new(step.y, step.x));
+                self.cover_with_mud(next - Coord::new(step.y, step.x));
+            }
+        }
+    }
+
+    /// True if the floor of the given cell is mud that isn't under water
+    pub fn is_mud_at(&self, coord: Coord) -> bool {
+        self.spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.floor)
+            .map(|floor| self.components.tile.get(floor) == Some(&Tile::Mud))
+            .unwrap_or(false)
+    }
+
+    /// Mud fills in any ditch it lands in
+    pub fn cover_with_mud(&mut self, coord: Coord) {
+        if let Some(ground) = self.ground_at(coord) {
+            self.components.ditch.remove(ground);
+            self.components.mud.insert(ground, ());
+            if !self.components.water.contains(ground) {
+                self.components.tile.insert(ground, Tile::Mud);
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::game::RainLevel;
+use rand::Rng;
+use rand_range::UniformInclusiveRange;
+use rational::Rational;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Seconds spent on each cast
+pub const CAST_TIME: u32 = 600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Fish {
+    Trout,
+    Perch,
+    Eel,
+}
+
+impl Fish {
+    /// Eels only come out at night, when the trout and perch have stopped feeding
+    fn choose<R: Rng>(hour: u32, rng: &mut R) -> Self {
+        if is_night(hour) {
+            if rng.gen_range(0..3) == 0 {
+                Self::Trout
+            } else {
+                Self::Eel
+            }
+        } else if rng.gen() {
+            Self::Trout
+        } else {
+            Self::Perch
+        }
+    }
+
+    fn length_cm(&self) -> UniformInclusiveRange<u32> {
+        let (low, high) = match self {
+            Self::Trout => (20, 45),
+            Self::Perch => (15, 30),
+            Self::Eel => (40, 80),
+        };
+        UniformInclusiveRange { low, high }
+    }
+}
+
+impl fmt::Display for Fish {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Trout => "trout",
+            Self::Perch => "perch",
+            Self::Eel => "eel",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A single fish that was caught during the trip
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Catch {
+    pub fish: Fish,
+    pub length_cm: u32,
+    pub day: u32,
+}
+
+impl Catch {
+    pub fn new<R: Rng>(day: u32, hour: u32, rng: &mut R) -> Self {
+        let fish = Fish::choose(hour, rng);
+        Self {
+            fish,
+            length_cm: fish.length_cm().choose(rng),
+            day,
+        }
+    }
+}
+
+fn is_night(hour: u32) -> bool {
+    !(5..21).contains(&hour)
+}
+
+fn is_dawn_or_dusk(hour: u32) -> bool {
+    (5..8).contains(&hour) || (17..21).contains(&hour)
+}
+
+/// The chance of a fish biting on a single cast. Rain stirs up insects and brings the fish to the
+/// surface, and they feed most at dawn and dusk.
+pub fn bite_chance(rain_level: RainLevel, hour: u32) -> Rational {
+    let denominator = match rain_level {
+        RainLevel::Light => 8,
+        RainLevel::Medium => 6,
+        RainLevel::Heavy => 4,
+    };
+    let numerator = if is_dawn_or_dusk(hour) { 2 } else { 1 };
+    let denominator = if is_night(hour) {
+        denominator * 2
+    } else {
+        denominator
+    };
+    Rational {
+        numerator,
+        denominator,
+    }
+}
+
+/// The text shown at the end of the trip
+pub fn summary(catch_log: &[Catch]) -> String {
+    if catch_log.is_empty() {
+        return "You didn't catch any fish this trip.".to_string();
+    }
+    let mut lines = vec![format!("You caught {} fish this trip:", catch_log.len())];
+    for catch in catch_log {
+        lines.push(format!(
+            "Day {}: a {}cm {}",
+            catch.day, catch.length_cm, catch.fish
+        ));
+    }
+    if let Some(biggest) = catch_log.iter().max_by_key(|catch| catch.length_cm) {
+        lines.push(format!(
+            "Your best catch was a {}cm {}.",
+            biggest.length_cm, biggest.fish
+        ));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,128 @@
+use crate::{
+    components::{Item, Tile},
+    game::RainLevel,
+    world::World,
+};
+use gridbugs::grid_2d::Coord;
+use rand::{seq::IteratorRandom, Rng};
+use rand_range::UniformInclusiveRange;
+use rational::Rational;
+
+/// Number of flowers or tea plants a patch can support at once
+pub const PATCH_CAPACITY: usize = 12;
+
+/// Once the ground is wetter than this, mushrooms start to sprout on the muddy low ground
+pub const MUSHROOM_WETNESS_THRESHOLD: u32 = 360;
+
+/// Chance each turn of a mushroom sprouting while the ground is wet enough
+pub const MUSHROOM_SPROUT_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 20,
+};
+
+const MAX_MUSHROOMS: usize = 20;
+
+/// Number of turns a mushroom lasts before rotting away (between one and two days)
+pub const MUSHROOM_LIFETIME: UniformInclusiveRange<u32> = UniformInclusiveRange {
+    low: 720,
+    high: 1440,
+};
+
+/// Chance each turn of a flower or tea plant growing back in its patch. Plants grow faster in
+/// the rain.
+pub fn regrow_chance(rain_level: RainLevel) -> Rational {
+    let denominator = match rain_level {
+        RainLevel::Light => 240,
+        RainLevel::Medium => 180,
+        RainLevel::Heavy => 120,
+    };
+    Rational {
+        numerator: 1,
+        denominator,
+    }
+}
+
+impl World {
+    fn is_clear_ground(&self, coord: Coord) -> bool {
+        let layers = self.spatial_table.layers_at_checked(coord);
+        layers.item.is_none()
+            && layers.feature.is_none()
+            && layers.character.is_none()
+            && layers
+                .floor
+                .map(|floor| {
+                    self.components.ground.contains(floor) && !self.components.water.contains(floor)
+                })
+                .unwrap_or(false)
+    }
+
+    /// Grows a new plant in a random empty cell of the patch where the given item grows, unless
+    /// the patch is already full
+    pub fn regrow<R: Rng>(&mut self, item: Item, rng: &mut R) {
+        let mut num_growing = 0;
+        let mut empty = Vec::new();
+        for (floor, &patch_item) in self.components.forage_patch.iter() {
+            if patch_item != item {
+                continue;
+            }
+            let coord = match self.spatial_table.coord_of(floor) {
+                Some(coord) => coord,
+                None => continue,
+            };
+            let growing = self
+                .spatial_table
+                .layers_at_checked(coord)
+                .item
+                .and_then(|entity| self.components.item.get(entity))
+                == Some(&item);
+            if growing {
+                num_growing += 1;
+            } else if self.is_clear_ground(coord) {
+                empty.push(coord);
+            }
+        }
+        if num_growing >= PATCH_CAPACITY {
+            return;
+        }
+        if let Some(coord) = empty.into_iter().choose(rng) {
+            match item {
+                Item::Flower => self.spawn_flower(coord),
+                Item::Tea => self.spawn_tea(coord),
+                _ => return,
+            };
+        }
+    }
+
+    /// Sprouts a mushroom on a random muddy cell
+    pub fn sprout_mushroom<R: Rng>(&mut self, rng: &mut R) {
+        if self.components.mushroom.entities().count() >= MAX_MUSHROOMS {
+            return;
+        }
+        let coord = self
+            .components
+            .ground
+            .entities()
+            .filter(|&floor| self.components.tile.get(floor) == Some(&Tile::Mud))
+            .filter_map(|floor| self.spatial_table.coord_of(floor))
+            .filter(|&coord| self.is_clear_ground(coord))
+            .choose(rng);
+        if let Some(coord) = coord {
+            self.spawn_mushroom(coord, MUSHROOM_LIFETIME.choose(rng));
+        }
+    }
+
+    /// Mushrooms rot away a day or two after sprouting
+    pub fn decay_mushrooms(&mut self) {
+        let mut rotten = Vec::new();
+        for (entity, turns_left) in self.components.mushroom.iter_mut() {
+            *turns_left = turns_left.saturating_sub(1);
+            if *turns_left == 0 {
+                rotten.push(entity);
+            }
+        }
+        for entity in rotten {
+            self.spatial_table.remove(entity);
+            self.components.remove_entity(entity);
+        }
+    }
+}
@@ -1,10 +1,22 @@
 use crate::{
-    components::{self, DoorState, EntityData, Equipment, Item, Tile},
+    chunk::{Chunk, Chunks},
+    components::{self, DoorState, EntityData, Equipment, Footprint, Item, Tile},
+    crafting::{self, Ingredient, Pantry},
+    custom_map::CustomMap,
+    erosion,
+    fishing::{self, Catch},
+    forage,
+    lake::LakeMode,
+    level::{self, Level},
+    lighting::{LightingCurve, MoonPhase, Sky},
     realtime::AnimationContext,
     spatial::Layer,
     spatial::Location,
-    terrain::{self, Terrain},
-    visibility::{Omniscient, VisibilityGrid},
+    spatial::UpdateError,
+    storm::{self, LightningStrike, Thunder},
+    terrain::{self, GenerationFailed, GenerationStats, Terrain, TerrainConfig},
+    visibility::{self, LightningFlash, Lookout, Omniscient, VisibilityGrid},
+    wetness,
     world::World,
 };
 use gridbugs::{
@@ -14,9 +26,13 @@ use gridbugs::{
     rgb_int::Rgb24,
     shadowcast::{self, Context as ShadowcastContext},
 };
-use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand::{
+    seq::{IteratorRandom, SliceRandom},
+    Rng, SeedableRng,
+};
 use rand_isaac::Isaac64Rng;
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, mem};
 
 const INITIAL_MOTIVATION: i32 = 1000;
 pub const MAX_MOTIVATION: i32 = 1000;
@@ -28,6 +44,9 @@ mod motivation {
     pub const LAKE: i32 = 250;
     pub const TEA: i32 = 250;
     pub const FLOWER: i32 = 250;
+    pub const CAIRN: i32 = 500;
+    pub const MEAL: i32 = 300;
+    pub const LOOKOUT: i32 = 250;
 
     pub fn chair(rain_level: RainLevel) -> i32 {
         match rain_level {
@@ -39,8 +58,11 @@ mod motivation {
 }
 
 pub mod prompts {
-    use super::{motivation, RainLevel};
+    use super::{motivation, MotivationModifier, RainLevel};
     use crate::components::Item;
+    use crate::fishing::Catch;
+    use crate::lake::LakeMode;
+    use crate::level::Level;
     use gridbugs::direction::CardinalDirection;
 
     pub fn sleep() -> String {
@@ -60,6 +82,16 @@ pub mod prompts {
         format!("You've booked five days at a cabin in the forest. You arrive, exhausted, looking forward to falling asleep to the sound of rain. You see the lights of the cabin through the trees to the {}.", direction)
     }
 
+    pub fn endless_intro(cabin_direction: CardinalDirection) -> String {
+        let direction = match cabin_direction {
+            CardinalDirection::North => "north",
+            CardinalDirection::East => "east",
+            CardinalDirection::South => "south",
+            CardinalDirection::West => "west",
+        };
+        format!("You've packed everything you need to wander the forest for as long as you like. There are cabins scattered among the trees, and the first of them lies through the trees to the {}.", direction)
+    }
+
     pub fn pick_up(item: Item) -> String {
         format!("You pick up the {}.", item.to_string())
     }
@@ -89,9 +121,58 @@ pub mod prompts {
         )
     }
 
+    pub fn enter_lake(mode: LakeMode) -> String {
+        match mode {
+            LakeMode::Wading => format!(
+                "Wade into the lake? You'll get soaked.\n\nMotivation decreases by {} each turn you spend wading.",
+                -MotivationModifier::Wading.value()
+            ),
+            LakeMode::Swimming => format!(
+                "The water is too deep to wade any further. Start swimming?\n\nMotivation decreases by {} each turn you spend swimming.",
+                -MotivationModifier::Swimming.value()
+            ),
+        }
+    }
+
+    pub fn board_boat() -> String {
+        "You climb into the rowing boat. Row across the lake with the movement keys, and step onto dry land to get out.".to_string()
+    }
+
+    pub fn cairn() -> String {
+        format!("At the heart of the island stands a cairn of weathered stones, each placed by someone who rowed out here before you. You add a stone of your own.\n\nMotivation increased by {}.", motivation::CAIRN)
+    }
+
+    pub fn catch(catch: &Catch) -> String {
+        format!(
+            "You catch a {}cm {}! Cook it on the stove in the cabin.",
+            catch.length_cm, catch.fish
+        )
+    }
+
+    pub fn cook_fish() -> String {
+        format!("You cook a fish on the stove and enjoy a hot meal out of the rain.\n\nMotivation increased by {}.", motivation::MEAL)
+    }
+
+    pub fn cook_mushroom() -> String {
+        format!("You fry the mushroom on the stove. Something good came of all that rain.\n\nMotivation increased by {}.", motivation::MEAL)
+    }
+
     pub fn lake() -> String {
         format!("Contemplating the vastness of this lake puts your life into perspective.\n\nMotivation increased by {}.", motivation::LAKE)
     }
+
+    pub fn lookout() -> String {
+        format!("From the top of the tower the forest stretches away in every direction. You pick out the cabin roof far below.\n\nMotivation increased by {}.", motivation::LOOKOUT)
+    }
+
+    /// Shown the first time the player climbs up to the loft or down to the cellar
+    pub fn first_visit(level: Level) -> Option<String> {
+        match level {
+            Level::Loft => Some("You climb the ladder into the loft. The rain drums on the roof just above your head, but it's dry up here whatever the weather.".to_string()),
+            Level::Cellar => Some("You go down the stairs into the cellar. It's cold and dark, and smells of damp earth. When the rain is at its heaviest, water seeps up through the floor.".to_string()),
+            Level::Ground => None,
+        }
+    }
 }
 
 pub enum TickOutput {
@@ -100,14 +181,20 @@ pub enum TickOutput {
 
 pub enum ControlFlow {
     Prompt(String),
+    EnterLake {
+        direction: CardinalDirection,
+        mode: LakeMode,
+    },
     Win,
     GameOver,
     Sleep,
+    Craft,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct RainSchedule {
     per_day: Vec<Vec<RainLevel>>,
+    wind_per_day: Vec<Vec<WindLevel>>,
 }
 
 impl RainSchedule {
@@ -124,7 +211,24 @@ impl RainSchedule {
         for v in &mut per_day {
             v.shuffle(rng);
         }
-        Self { per_day }
+        let wind_per_day = per_day
+            .iter()
+            .map(|v| v.iter().map(|&rain| WindLevel::random(rain, rng)).collect())
+            .collect();
+        Self {
+            per_day,
+            wind_per_day,
+        }
+    }
+
+    /// Replaces the rain for one day with a level for each 4 hour period, picking new winds to
+    /// suit it
+    pub fn set_day<R: Rng>(&mut self, day: usize, levels: [RainLevel; 6], rng: &mut R) {
+        self.per_day[day] = levels.to_vec();
+        self.wind_per_day[day] = levels
+            .iter()
+            .map(|&rain| WindLevel::random(rain, rng))
+            .collect();
     }
 
     fn at_time(&self, time: Time) -> RainLevel {
@@ -134,9 +238,35 @@ impl RainSchedule {
             .unwrap_or(RainLevel::Heavy)
     }
 
+    fn wind_at_time(&self, time: Time) -> WindLevel {
+        self.wind_per_day
+            .get(time.day() as usize)
+            .and_then(|a| a.get(time.hour() as usize / 4).cloned())
+            .unwrap_or(WindLevel::Gale)
+    }
+
+    /// Adds days to the end of the schedule until it covers `num_days` days. Each new day has the
+    /// rain of one of the first six days, in a new order.
+    fn extend_to<R: Rng>(&mut self, num_days: usize, rng: &mut R) {
+        while self.per_day.len() < num_days {
+            let mut levels = self.per_day[rng.gen_range(0..6)].clone();
+            levels.shuffle(rng);
+            let winds = levels
+                .iter()
+                .map(|&rain| WindLevel::random(rain, rng))
+                .collect();
+            self.per_day.push(levels);
+            self.wind_per_day.push(winds);
+        }
+    }
+
     pub fn get(&self, day: usize, time: usize) -> RainLevel {
         self.per_day[day][time]
     }
+
+    pub fn get_wind(&self, day: usize, time: usize) -> WindLevel {
+        self.wind_per_day[day][time]
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -149,6 +279,11 @@ pub enum TopographyCell {
     Flowers,
     Tea,
     Cabin,
+    Island,
+    FallenLog,
+    Mud,
+    Trail,
+    Lookout,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -200,9 +335,8 @@ impl Time {
         }
     }
 
-    fn is_night(&self) -> bool {
-        let h = self.hour();
-        h < 5 || h > 17
+    fn minute_of_day(&self) -> u32 {
+        (self.seconds % 86400) / 60
     }
 }
 
@@ -214,6 +348,14 @@ pub enum RainLevel {
 }
 
 impl RainLevel {
+    fn cloud_cover(&self) -> u8 {
+        match self {
+            Self::Light => 60,
+            Self::Medium => 150,
+            Self::Heavy => 230,
+        }
+    }
+
     pub fn to_string(&self) -> String {
         match self {
             Self::Light => "Light Rain",
@@ -224,6 +366,38 @@ impl RainLevel {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindLevel {
+    Calm,
+    Gale,
+}
+
+impl WindLevel {
+    /// Gales only blow during heavier rain
+    fn random<R: Rng>(rain_level: RainLevel, rng: &mut R) -> Self {
+        let chance = match rain_level {
+            RainLevel::Light => return Self::Calm,
+            RainLevel::Medium => storm::MEDIUM_RAIN_GALE_CHANCE,
+            RainLevel::Heavy => storm::HEAVY_RAIN_GALE_CHANCE,
+        };
+        if chance.roll(rng) {
+            Self::Gale
+        } else {
+            Self::Calm
+        }
+    }
+}
+
+impl fmt::Display for WindLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Calm => "Calm",
+            Self::Gale => "Gale",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum MotivationModifier {
     PassageOfTime,
@@ -236,6 +410,11 @@ pub enum MotivationModifier {
     FlattenedGrass,
     Gumboots,
     Umbrella,
+    Wading,
+    Swimming,
+    WetClothes,
+    RainOnTheLake,
+    RainOnTheRoof(RainLevel),
 }
 
 impl MotivationModifier {
@@ -253,6 +432,13 @@ impl MotivationModifier {
             Self::Tired => -5,
             Self::InTheDark => -10,
             Self::FlattenedGrass => 1,
+            Self::Wading => -15,
+            Self::Swimming => -30,
+            Self::WetClothes => -3,
+            Self::RainOnTheLake => 3,
+            Self::RainOnTheRoof(RainLevel::Light) => 1,
+            Self::RainOnTheRoof(RainLevel::Medium) => 2,
+            Self::RainOnTheRoof(RainLevel::Heavy) => 3,
         }
     }
 
@@ -270,6 +456,11 @@ impl MotivationModifier {
             Self::FlattenedGrass => "Flattened some Grass",
             Self::Gumboots => "Gumboots",
             Self::Umbrella => "Umbrella",
+            Self::Wading => "Wading in the Lake",
+            Self::Swimming => "Swimming in the Lake",
+            Self::WetClothes => "Wet Clothes",
+            Self::RainOnTheLake => "Rain on the Lake",
+            Self::RainOnTheRoof(_) => "Rain on the Roof",
         }
         .to_string()
     }
@@ -281,6 +472,10 @@ struct MotivationFlags {
     chair: bool,
     tea: bool,
     flower: bool,
+    meal: bool,
+    lookout: bool,
+    /// Indices into `crafting::RECIPES` of the recipes made today
+    crafted: Vec<usize>,
 }
 
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
@@ -292,6 +487,8 @@ pub struct Equipped {
     pub gumboots: bool,
     pub crowbar: bool,
     pub lantern: bool,
+    pub axe: bool,
+    pub fishing_rod: bool,
 }
 
 impl Equipped {
@@ -304,14 +501,21 @@ impl Equipped {
             gumboots: true,
             crowbar: true,
             lantern: true,
+            axe: true,
+            fishing_rod: true,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Config {
     pub omniscient: bool,
     pub debug: bool,
+    pub terrain: TerrainConfig,
+    /// Play on a hand-authored map instead of generating one
+    pub map: Option<CustomMap>,
+    /// Wander an endless forest that is generated as the player explores it
+    pub endless: bool,
 }
 
 pub enum ActionError {
@@ -327,9 +531,22 @@ impl ActionError {
     }
 }
 
+impl From<UpdateError> for ActionError {
+    fn from(error: UpdateError) -> Self {
+        let message = match error {
+            UpdateError::OccupiedBy(_) => "Something is blocking the way!",
+            UpdateError::DestinationOutOfBounds => "You can't walk there!",
+        };
+        Self::Message(message.to_string())
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Game {
+    /// Visibility of the level the player is on
     visibility_grid: VisibilityGrid,
+    /// Visibility of the levels the player has been on but isn't on now
+    level_visibility: HashMap<Level, VisibilityGrid>,
     shadowcast_context: ShadowcastContext<u8>,
     world: World,
     player: Entity,
@@ -349,10 +566,30 @@ pub struct Game {
     equipped: Equipped,
     first: bool,
     cabin_direction: CardinalDirection,
+    lighting_curve: LightingCurve,
+    lamps_on: bool,
+    lunar_cycle_offset: u32,
+    lightning: Option<LightningStrike>,
+    heavy_rain_turns: u32,
+    turn_remainder: u32,
+    ground_wetness: u32,
+    puddle_level: u32,
+    player_wetness: u32,
+    player_boat: Option<Entity>,
+    cairn_visited: bool,
+    fish_held: u32,
+    catch_log: Vec<Catch>,
+    pantry: Pantry,
+    fords_swollen: bool,
+    /// The chunks of the endless forest, if this is one
+    chunks: Option<Chunks>,
+    /// What it took to generate the world. This isn't saved, so it's only known for new games.
+    #[serde(skip)]
+    generation_stats: GenerationStats,
 }
 
 impl Game {
-    pub fn new<R: Rng>(config: &Config, base_rng: &mut R) -> Self {
+    pub fn new<R: Rng>(config: &Config, base_rng: &mut R) -> Result<Self, GenerationFailed> {
         let mut rng = Isaac64Rng::from_rng(base_rng).unwrap();
         let player_data = components::make_player();
         let (
@@ -360,28 +597,52 @@ impl Game {
                 world,
                 player,
                 cabin_direction,
+                stats: generation_stats,
             },
             equipped,
-        ) = if config.debug {
+            chunks,
+        ) = if let Some(map) = config.map.as_ref() {
+            (map.build(player_data, &mut rng), Equipped::default(), None)
+        } else if config.debug {
+            let demo = CustomMap::parse(include_str!("demo_terrain.txt"))
+                .unwrap_or_else(|message| panic!("invalid demo terrain: {}", message));
+            (demo.build(player_data, &mut rng), Equipped::all(), None)
+        } else if config.endless {
+            // there's no equipment to find in the endless forest, so the player brings it all
+            let chunks = Chunks::new(rng.gen(), config.terrain);
             (
-                terrain::from_str(include_str!("demo_terrain.txt"), player_data, &mut rng),
+                chunks.generate_start(player_data),
                 Equipped::all(),
+                Some(chunks),
             )
         } else {
             (
-                terrain::generate(player_data, &mut rng),
+                terrain::generate(&config.terrain, player_data, &mut rng)?,
                 Equipped::default(),
+                None,
             )
         };
+        let mut world = world;
+        world.compute_lake_depths();
         let visibility_grid = VisibilityGrid::new(world.size());
+        let lunar_cycle_offset = MoonPhase::random_offset(&mut rng);
+        let mut rain_schedule = RainSchedule::new(&mut rng);
+        let mut time = Time::new(0, 23, 18, 00);
+        if let Some(map) = config.map.as_ref() {
+            for (day, levels) in map.rain() {
+                rain_schedule.set_day(day, levels, &mut rng);
+            }
+            time = map.start_time().unwrap_or(time);
+        }
         let mut game = Self {
             visibility_grid,
+            level_visibility: HashMap::new(),
             shadowcast_context: ShadowcastContext::default(),
             world,
             player,
             animation_context: AnimationContext::default(),
-            time: Time::new(0, 23, 18, 00),
-            rain_schedule: RainSchedule::new(&mut rng),
+            time,
+            rain_schedule,
             num_flooded: 0.,
             rng,
             last_sleep: None,
@@ -395,19 +656,131 @@ impl Game {
             equipped,
             first: true,
             cabin_direction,
+            lighting_curve: LightingCurve::default(),
+            lamps_on: true,
+            lunar_cycle_offset,
+            lightning: None,
+            heavy_rain_turns: 0,
+            turn_remainder: 0,
+            ground_wetness: 0,
+            puddle_level: 0,
+            player_wetness: 0,
+            player_boat: None,
+            cairn_visited: false,
+            fish_held: 0,
+            catch_log: Vec::new(),
+            pantry: Pantry::default(),
+            fords_swollen: false,
+            chunks,
+            generation_stats,
         };
         game.after_turn(0, config);
         game.update_motivation();
         game.motivation = INITIAL_MOTIVATION;
-        game
+        Ok(game)
+    }
+
+    /// Makes every cell visible, lit the way the cells beside the player are at the current
+    /// time of day, along with any lamps or other lights. The map editor uses this to preview
+    /// lighting across the whole map.
+    pub fn reveal_lit_map(&mut self) {
+        let player_coord = self.player_coord();
+        let player_light_colour = self
+            .world
+            .components
+            .light
+            .get(self.player)
+            .expect("player lacks light")
+            .colour;
+        self.visibility_grid.update(
+            player_coord,
+            &self.world,
+            &mut self.shadowcast_context,
+            Some(Omniscient::with_player_light(player_light_colour)),
+            None,
+            None,
+        );
     }
 
     pub fn pushing(&self) -> bool {
         self.player_pushing
     }
 
+    /// There's no end to a trip through the endless forest
     pub fn is_won(&self) -> bool {
-        self.time.day() > 5
+        self.chunks.is_none() && self.time.day() > 5
+    }
+
+    pub fn is_endless(&self) -> bool {
+        self.chunks.is_some()
+    }
+
+    pub fn level(&self) -> Level {
+        self.world.level
+    }
+
+    pub fn generation_stats(&self) -> &GenerationStats {
+        &self.generation_stats
+    }
+
+    /// Absolute coordinate of the top-left cell of the world. Only the endless forest moves, as
+    /// it follows the player.
+    pub fn world_origin(&self) -> Coord {
+        self.chunks
+            .as_ref()
+            .map(|chunks| chunks.world_origin())
+            .unwrap_or_else(|| Coord::new(0, 0))
+    }
+
+    pub fn take_far_chunks(&mut self) -> Vec<Chunk> {
+        let player_coord = self.player_coord();
+        self.chunks
+            .as_mut()
+            .map(|chunks| chunks.take_far(player_coord))
+            .unwrap_or_default()
+    }
+
+    pub fn wanted_chunks(&self) -> Vec<Coord> {
+        self.chunks
+            .as_ref()
+            .map(|chunks| chunks.wanted(self.player_coord()))
+            .unwrap_or_default()
+    }
+
+    pub fn restore_chunk(&mut self, coord: Coord, chunk: Option<Chunk>) {
+        if let Some(chunks) = self.chunks.as_mut() {
+            chunks.restore(coord, chunk);
+        }
+    }
+
+    /// Moves the world of the endless forest along if the player has left its middle chunk
+    fn recentre(&mut self) {
+        let player_coord = self.player_coord();
+        let chunks = match self.chunks.as_mut() {
+            Some(chunks) => chunks,
+            None => return,
+        };
+        // new chunks are generated on the ground level
+        let level = self.world.enter_level(Level::Ground);
+        let offset = chunks.recentre(&mut self.world, player_coord);
+        self.world.enter_level(level);
+        let offset = match offset {
+            Some(offset) => offset,
+            None => return,
+        };
+        self.visibility_grid.shift(offset);
+        for visibility_grid in self.level_visibility.values_mut() {
+            visibility_grid.shift(offset);
+        }
+        if let Some(lightning) = self.lightning.as_mut() {
+            lightning.coord += offset;
+        }
+        // lamps in new chunks start out lit, whatever the time of day
+        if self.lamps_on {
+            self.world.turn_lamps_on();
+        } else {
+            self.world.turn_lamps_off();
+        }
     }
 
     pub fn world_size(&self) -> Size {
@@ -445,14 +818,54 @@ impl Game {
         self.rain_schedule.at_time(self.time)
     }
 
-    fn update_visibility(&mut self, config: &Config) {
+    pub fn wind_level(&self) -> WindLevel {
+        self.rain_schedule.wind_at_time(self.time)
+    }
+
+    fn is_storm(&self) -> bool {
+        matches!(self.rain_level(), RainLevel::Heavy) && self.wind_level() == WindLevel::Gale
+    }
+
+    pub fn moon_phase(&self) -> MoonPhase {
+        MoonPhase::on_day(self.lunar_cycle_offset, self.time.day())
+    }
+
+    fn sky(&self) -> Sky {
+        Sky {
+            moon_phase: self.moon_phase(),
+            cloud_cover: self.rain_level().cloud_cover(),
+        }
+    }
+
+    fn update_visibility(&mut self, config: &Config, lightning_flash: bool) {
         if let Some(player_coord) = self.world.entity_coord(self.player) {
             self.visibility_grid.update(
                 player_coord,
                 &self.world,
                 &mut self.shadowcast_context,
                 if config.omniscient {
-                    Some(Omniscient)
+                    Some(Omniscient::FULL_LIGHT)
+                } else {
+                    None
+                },
+                if lightning_flash {
+                    Some(LightningFlash {
+                        colour: storm::FLASH_COLOUR,
+                    })
+                } else {
+                    None
+                },
+                if self.world.is_lookout(player_coord) {
+                    let light = self
+                        .world
+                        .components
+                        .light
+                        .get(self.player)
+                        .expect("player lacks light");
+                    Some(Lookout {
+                        vision_distance: visibility::LOOKOUT_VISION_DISTANCE,
+                        colour: light.colour,
+                    })
                 } else {
                     None
                 },
@@ -464,21 +877,25 @@ impl Game {
         self.rain_schedule.clone()
     }
 
+    /// The map shows the ground level, wherever the player is
     pub fn topography_grid(&self) -> Grid<TopographyCell> {
         let mut flowers = false;
         let mut tea = false;
+        let player_coord = self.player_coord();
+        let spatial_table = self.world.ground_spatial_table();
         Grid::new_fn(self.world_size(), |coord| {
-            let layers = self.world.spatial_table.layers_at_checked(coord);
-            if let Some(character) = layers.character {
-                if self.world.components.player.contains(character) {
-                    return TopographyCell::Player;
-                }
+            if coord == player_coord {
+                return TopographyCell::Player;
             }
+            let layers = spatial_table.layers_at_checked(coord);
             if let Some(feature) = layers.feature {
                 if let Some(tile) = self.world.components.tile.get(feature) {
                     match tile {
                         Tile::Altar => return TopographyCell::Ruins,
-                        Tile::Bed => return TopographyCell::Cabin,
+                        // cabins with a loft have their bed up there
+                        Tile::Bed | Tile::Ladder => return TopographyCell::Cabin,
+                        Tile::Cairn => return TopographyCell::Island,
+                        Tile::Log(_) => return TopographyCell::FallenLog,
                         _ => (),
                     }
                 }
@@ -503,8 +920,18 @@ impl Game {
                 }
             }
             if let Some(floor) = layers.floor {
-                if self.world.components.water.contains(floor) {
+                if self.world.components.lookout.contains(floor) {
+                    TopographyCell::Lookout
+                } else if self.world.components.bridge.contains(floor) {
+                    TopographyCell::Trail
+                } else if self.world.components.water.contains(floor)
+                    || self.world.components.stream.contains(floor)
+                {
                     TopographyCell::Water
+                } else if self.world.components.tile.get(floor) == Some(&Tile::Mud) {
+                    TopographyCell::Mud
+                } else if self.world.components.trail.contains(floor) {
+                    TopographyCell::Trail
                 } else if let Some(&height) = self.world.components.height.get(floor) {
                     TopographyCell::Height(height)
                 } else {
@@ -536,6 +963,28 @@ impl Game {
                     .push(MotivationModifier::UnderTree);
             }
         }
+        if self.player_boat.is_some() && !self.should_hide_rain(player_coord) {
+            self.last_motivation_modifiers
+                .push(MotivationModifier::RainOnTheLake);
+        }
+        if self.world.level == Level::Loft {
+            self.last_motivation_modifiers
+                .push(MotivationModifier::RainOnTheRoof(self.rain_level()));
+        }
+        match self.player_lake_mode() {
+            Some(LakeMode::Wading) => self
+                .last_motivation_modifiers
+                .push(MotivationModifier::Wading),
+            Some(LakeMode::Swimming) => self
+                .last_motivation_modifiers
+                .push(MotivationModifier::Swimming),
+            None => {
+                if self.player_wetness > 0 {
+                    self.last_motivation_modifiers
+                        .push(MotivationModifier::WetClothes);
+                }
+            }
+        }
         if self.is_player_in_flood_water() {
             self.last_motivation_modifiers
                 .push(MotivationModifier::InFloodWater);
@@ -611,13 +1060,45 @@ impl Game {
             .layers_at_checked(player_coord)
             .floor
         {
-            if self.world.components.water.contains(floor) {
+            if self.world.components.water.contains(floor)
+                && !self.world.components.lake.contains(floor)
+            {
                 return true;
             }
         }
         false
     }
 
+    /// How the player is moving through the lake, if they're in it (rather than in the boat)
+    fn player_lake_mode(&self) -> Option<LakeMode> {
+        if self.player_boat.is_some() {
+            None
+        } else {
+            self.world.lake_mode(self.player_coord())
+        }
+    }
+
+    const WADING_WETNESS: u32 = 120;
+    const SWIMMING_WETNESS: u32 = 240;
+    const DRYING_INDOORS_RATE: u32 = 4;
+
+    /// Gets wet in the lake and dries off elsewhere, faster when indoors
+    fn update_player_wetness(&mut self) {
+        let player_coord = self.player_coord();
+        self.player_wetness = match self.player_lake_mode() {
+            Some(LakeMode::Wading) => self.player_wetness.max(Self::WADING_WETNESS),
+            Some(LakeMode::Swimming) => self.player_wetness.max(Self::SWIMMING_WETNESS),
+            None => {
+                if self.should_hide_rain(player_coord) {
+                    self.player_wetness
+                        .saturating_sub(Self::DRYING_INDOORS_RATE)
+                } else {
+                    self.player_wetness.saturating_sub(1)
+                }
+            }
+        };
+    }
+
     fn is_player_on_stepping_stone(&self) -> bool {
         let player_coord = self.player_coord();
         let cell = self.world.spatial_table.layers_at_checked(player_coord);
@@ -643,9 +1124,20 @@ impl Game {
 
     pub fn tick(&mut self) -> Option<TickOutput> {
         self.animation_context.tick(&mut self.world);
+        if let Some(lightning) = self.lightning.as_mut() {
+            lightning.tick();
+            if lightning.is_finished() {
+                self.lightning = None;
+            }
+        }
         if self.first {
             self.first = false;
-            Some(TickOutput::Prompt(prompts::intro(self.cabin_direction)))
+            let intro = if self.is_endless() {
+                prompts::endless_intro(self.cabin_direction)
+            } else {
+                prompts::intro(self.cabin_direction)
+            };
+            Some(TickOutput::Prompt(intro))
         } else {
             None
         }
@@ -653,41 +1145,26 @@ impl Game {
 
     const FLOOD_STEP: usize = 1200;
 
+    /// The endless forest never floods more than this many cells, and drains in light rain
+    const ENDLESS_MAX_FLOODED: f64 = 1000.;
+
     fn after_turn(&mut self, time_delta: u32, config: &Config) {
+        self.recentre();
         let old_time = self.time;
         self.time.seconds += time_delta;
-        if old_time.is_night() && !self.time.is_night() {
+        let ambient = self
+            .lighting_curve
+            .at_minute_of_day(self.time.minute_of_day());
+        let lamps_should_be_on = self.lighting_curve.lamps_should_be_on(ambient);
+        if self.lamps_on && !lamps_should_be_on {
             self.world.turn_lamps_off();
-        } else if !old_time.is_night() && self.time.is_night() {
+        } else if !self.lamps_on && lamps_should_be_on {
             self.world.turn_lamps_on();
         }
-        let (mut player_light_colour, mut player_light_distance) = match self.time.hour() {
-            0 => (Rgb24::new(64, 64, 80), 25),
-            1 => (Rgb24::new(64, 64, 80), 25),
-            2 => (Rgb24::new(64, 64, 80), 25),
-            3 => (Rgb24::new(64, 64, 80), 25),
-            4 => (Rgb24::new(64, 64, 80), 25),
-            5 => (Rgb24::new(120, 100, 80), 50),
-            6 => (Rgb24::new(130, 110, 100), 80),
-            7 => (Rgb24::new(140, 120, 120), 120),
-            8 => (Rgb24::new(150, 140, 140), 160),
-            9 => (Rgb24::new(160, 160, 160), 200),
-            10 => (Rgb24::new(180, 180, 180), 200),
-            11 => (Rgb24::new(200, 200, 200), 200),
-            12 => (Rgb24::new(200, 200, 200), 200),
-            13 => (Rgb24::new(200, 200, 200), 200),
-            14 => (Rgb24::new(200, 200, 200), 200),
-            15 => (Rgb24::new(200, 200, 200), 200),
-            16 => (Rgb24::new(200, 200, 150), 200),
-            17 => (Rgb24::new(200, 150, 20), 120),
-            18 => (Rgb24::new(150, 120, 80), 80),
-            19 => (Rgb24::new(80, 80, 80), 25),
-            20 => (Rgb24::new(80, 80, 80), 25),
-            21 => (Rgb24::new(80, 80, 80), 25),
-            22 => (Rgb24::new(80, 80, 80), 25),
-            23 => (Rgb24::new(80, 80, 80), 25),
-            _ => panic!(),
-        };
+        self.lamps_on = lamps_should_be_on;
+        let ambient = self.lighting_curve.apply_sky(ambient, self.sky());
+        let mut player_light_colour = ambient.colour;
+        let mut player_light_distance = ambient.vision_distance_squared;
         if self.player_lantern {
             player_light_colour = player_light_colour.saturating_add(World::LANTERN_LIGHT.colour);
             if player_light_distance < World::LANTERN_LIGHT.vision_distance.distance_squared() {
@@ -705,16 +1182,184 @@ impl Game {
             light.vision_distance =
                 shadowcast::vision_distance::Circle::new_squared(player_light_distance);
         }
-        self.num_flooded += (Self::FLOOD_STEP as f64 / 86400.) * time_delta as f64;
-        self.world
-            .flood(self.num_flooded.floor() as usize, &mut self.rng);
         if old_time.day() != self.time.day() {
             self.motivation_flags = MotivationFlags::default();
+            if self.is_endless() {
+                // keep a five day forecast ahead
+                self.rain_schedule
+                    .extend_to(self.time.day() as usize + 6, &mut self.rng);
+            }
+        }
+        // actions don't all take a whole number of turns, so carry over the leftover time
+        let num_turns = (self.turn_remainder + time_delta) / Self::TURN_TIME;
+        self.turn_remainder = (self.turn_remainder + time_delta) % Self::TURN_TIME;
+        let player_coord = self.player_coord();
+        // the weather acts on the ground level, wherever the player is
+        let level = self.world.enter_level(Level::Ground);
+        self.update_wetness(num_turns);
+        let flood_delta = (Self::FLOOD_STEP as f64 / 86400.) * time_delta as f64;
+        if self.is_endless() {
+            let flood_delta = match self.rain_level() {
+                RainLevel::Light => -flood_delta,
+                RainLevel::Medium | RainLevel::Heavy => flood_delta,
+            };
+            self.num_flooded =
+                (self.num_flooded + flood_delta).clamp(0., Self::ENDLESS_MAX_FLOODED);
+        } else {
+            self.num_flooded += flood_delta;
+        }
+        self.world.flood(
+            self.num_flooded.floor() as usize,
+            wetness::num_muddy_cells(self.ground_wetness),
+            &mut self.rng,
+        );
+        self.world.fill_puddles(self.puddle_level);
+        let fords_swollen = matches!(self.rain_level(), RainLevel::Heavy);
+        if fords_swollen != self.fords_swollen {
+            self.world.set_fords_swollen(fords_swollen);
+            self.fords_swollen = fords_swollen;
+        }
+        let lightning_flash = if let RainLevel::Heavy = self.rain_level() {
+            self.lightning_strikes(num_turns, player_coord)
+        } else {
+            false
+        };
+        if self.is_storm() {
+            self.storm_damage(num_turns);
         }
-        for _ in 0..(time_delta / Self::TURN_TIME) {
+        self.erosion(num_turns);
+        self.update_forage(num_turns);
+        self.world.enter_level(level);
+        self.update_cellars(num_turns);
+        for _ in 0..num_turns {
+            self.update_player_wetness();
             self.update_motivation();
         }
-        self.update_visibility(config);
+        self.update_visibility(config, lightning_flash);
+    }
+
+    /// Floods cellars from below once the heavy rain has gone on for long enough, and lets them
+    /// drain again when it eases off
+    fn update_cellars(&mut self, num_turns: u32) {
+        for _ in 0..num_turns {
+            if self.heavy_rain_turns > level::CELLAR_FLOOD_TURNS {
+                if level::CELLAR_SEEP_CHANCE.roll(&mut self.rng) {
+                    self.world.seep_into_cellar(&mut self.rng);
+                }
+            } else if self.heavy_rain_turns == 0 && level::CELLAR_DRAIN_CHANCE.roll(&mut self.rng) {
+                self.world.drain_cellar(&mut self.rng);
+            }
+        }
+    }
+
+    /// Soaks or dries out the ground, adjusts the size of puddles and washes away footprints
+    fn update_wetness(&mut self, num_turns: u32) {
+        let rain_level = self.rain_level();
+        let target_puddle_level = wetness::puddle_level(rain_level);
+        for _ in 0..num_turns {
+            self.ground_wetness = (self.ground_wetness as i32 + wetness::wetness_delta(rain_level))
+                .clamp(0, wetness::MAX_GROUND_WETNESS as i32)
+                as u32;
+            if self.puddle_level != target_puddle_level
+                && wetness::PUDDLE_CHANGE_CHANCE.roll(&mut self.rng)
+            {
+                if self.puddle_level < target_puddle_level {
+                    self.puddle_level += 1;
+                } else {
+                    self.puddle_level -= 1;
+                }
+            }
+            self.world.fade_footprints(rain_level);
+        }
+    }
+
+    /// Erodes steep slopes once the heavy rain has been going for long enough
+    fn erosion(&mut self, num_turns: u32) {
+        for _ in 0..num_turns {
+            if let RainLevel::Heavy = self.rain_level() {
+                self.heavy_rain_turns += 1;
+            } else {
+                self.heavy_rain_turns = 0;
+            }
+            if self.heavy_rain_turns > erosion::SUSTAINED_HEAVY_RAIN_TURNS {
+                self.world.erode(&mut self.rng);
+            }
+        }
+    }
+
+    /// Regrows flowers and tea in their patches, and sprouts and rots mushrooms
+    fn update_forage(&mut self, num_turns: u32) {
+        let regrow_chance = forage::regrow_chance(self.rain_level());
+        for _ in 0..num_turns {
+            for item in [Item::Flower, Item::Tea] {
+                if regrow_chance.roll(&mut self.rng) {
+                    self.world.regrow(item, &mut self.rng);
+                }
+            }
+            self.world.decay_mushrooms();
+            if self.ground_wetness > forage::MUSHROOM_WETNESS_THRESHOLD
+                && forage::MUSHROOM_SPROUT_CHANCE.roll(&mut self.rng)
+            {
+                self.world.sprout_mushroom(&mut self.rng);
+            }
+        }
+    }
+
+    /// Rolls for trees blown over by the wind over the given number of turns
+    fn storm_damage(&mut self, num_turns: u32) {
+        for _ in 0..num_turns {
+            if !storm::TREE_FALL_CHANCE.roll(&mut self.rng) {
+                continue;
+            }
+            if let Some(tree) = self.world.components.tree.entities().choose(&mut self.rng) {
+                let direction = self.rng.gen::<CardinalDirection>();
+                self.world.fell_tree(tree, direction);
+            }
+        }
+    }
+
+    /// Rolls for lightning strikes over the given number of turns, returning true if there was at
+    /// least one strike
+    fn lightning_strikes(&mut self, num_turns: u32, player_coord: Coord) -> bool {
+        let mut any_strikes = false;
+        for _ in 0..num_turns {
+            if !storm::STRIKE_CHANCE.roll(&mut self.rng) {
+                continue;
+            }
+            let tree = if storm::TREE_STRIKE_CHANCE.roll(&mut self.rng) {
+                self.world.components.tree.entities().choose(&mut self.rng)
+            } else {
+                None
+            };
+            // strike somewhere at random if there are no trees to hit
+            let tree_coord =
+                tree.and_then(|tree| self.world.entity_coord(tree).map(|coord| (tree, coord)));
+            let coord = if let Some((tree, coord)) = tree_coord {
+                let direction = self.rng.gen::<CardinalDirection>();
+                self.world.fell_tree(tree, direction);
+                coord
+            } else {
+                let size = self.world.size();
+                Coord::new(
+                    self.rng.gen_range(0..size.width() as i32),
+                    self.rng.gen_range(0..size.height() as i32),
+                )
+            };
+            self.lightning = Some(LightningStrike::new(coord, player_coord));
+            any_strikes = true;
+        }
+        any_strikes
+    }
+
+    pub fn lightning_flash_intensity(&self) -> u8 {
+        self.lightning
+            .as_ref()
+            .map(|l| l.flash_intensity())
+            .unwrap_or(0)
+    }
+
+    pub fn thunder(&self) -> Option<Thunder> {
+        self.lightning.as_ref().and_then(|l| l.thunder())
     }
 
     fn to_push(&self, start: Coord, direction: CardinalDirection) -> Vec<Entity> {
@@ -769,6 +1414,15 @@ impl Game {
             .coord_of(self.player)
             .expect("can't get coord of player");
         let destination = player_coord + direction.coord();
+        if !self.world.can_cross_stream(destination) {
+            return if self.world.is_ford(destination) {
+                ActionError::err_msg(
+                    "The ford is under water while the stream is swollen by the rain!",
+                )
+            } else {
+                ActionError::err_msg("The stream is too deep and fast to cross here!")
+            };
+        }
         if let Some(&layers) = self.world.spatial_table.layers_at(destination) {
             if let Some(item) = layers.item {
                 if let Some(equipment) = self.world.components.equipment.get(item) {
@@ -783,6 +1437,8 @@ impl Game {
                             self.equipped.lantern = true;
                             self.player_lantern = true;
                         }
+                        Equipment::Axe => self.equipped.axe = true,
+                        Equipment::FishingRod => self.equipped.fishing_rod = true,
                     }
                     let text = match equipment {
                         Equipment::Shovel => "You equip the shovel. You can now dig ditches by pressing 'e'.",
@@ -792,6 +1448,8 @@ impl Game {
                         Equipment::Gumboots => "You equip gumboots. Motivation loss by flood water is reduced.",
                         Equipment::Crowbar => "You equip the crowbar. You can now push rocks. Toggle pushing mode by pressing 'p'.",
                         Equipment::Lantern => "You equip the lantern. Toggle the light by pressing 'f'.",
+                        Equipment::Axe => "You equip the axe. Chop up fallen logs by pressing 'c'.",
+                        Equipment::FishingRod => "You equip the fishing rod. Fish from the edge of the lake by pressing 'F'.",
                     };
                     self.world.components.remove_entity(item);
                     self.world.spatial_table.remove(item);
@@ -811,12 +1469,10 @@ impl Game {
                     }
                 }
             }
-            if let Some(floor) = layers.floor {
-                if self.world.components.lake.contains(floor) {
-                    return ActionError::err_msg("Refusing to walk into the lake");
-                }
-            }
             if let Some(feature) = layers.feature {
+                if let Some(&level) = self.world.components.stairs.get(feature) {
+                    return self.climb(level, destination);
+                }
                 if self.world.components.chair.contains(feature) {
                     if self.motivation_flags.chair {
                         return Ok(Some(ControlFlow::Prompt(format!(
@@ -854,6 +1510,57 @@ impl Game {
                         ))));
                     }
                 }
+                if self.world.components.cairn.contains(feature) {
+                    if self.cairn_visited {
+                        return Ok(Some(ControlFlow::Prompt(
+                            "Your stone still sits atop the cairn.".to_string(),
+                        )));
+                    } else {
+                        self.cairn_visited = true;
+                        self.increase_motivation(motivation::CAIRN);
+                        return Ok(Some(ControlFlow::Prompt(prompts::cairn())));
+                    }
+                }
+                if self.world.components.stove.contains(feature) {
+                    let holding_mushroom = self.player_item() == Some(Item::Mushroom);
+                    if self.fish_held == 0 && !holding_mushroom {
+                        return Ok(Some(ControlFlow::Prompt(
+                            "A wood stove. You could cook a fish on it, if you caught one or found some mushrooms..."
+                                .to_string(),
+                        )));
+                    } else if self.motivation_flags.meal {
+                        return Ok(Some(ControlFlow::Prompt(
+                            "You've already had a hot meal today.".to_string(),
+                        )));
+                    } else if holding_mushroom {
+                        self.player_item = None;
+                        self.motivation_flags.meal = true;
+                        self.increase_motivation(motivation::MEAL);
+                        return Ok(Some(ControlFlow::Prompt(prompts::cook_mushroom())));
+                    } else {
+                        self.fish_held -= 1;
+                        self.motivation_flags.meal = true;
+                        self.increase_motivation(motivation::MEAL);
+                        return Ok(Some(ControlFlow::Prompt(prompts::cook_fish())));
+                    }
+                }
+                if self.world.components.kitchen_bench.contains(feature) {
+                    if let Some(item) = self.player_item() {
+                        if let Some(ingredient) = Ingredient::from_item(item) {
+                            self.player_item = None;
+                            self.pantry.add(ingredient);
+                            return Ok(Some(ControlFlow::Prompt(format!(
+                                "You leave the {} on the kitchen bench.",
+                                ingredient
+                            ))));
+                        }
+                        return Ok(Some(ControlFlow::Prompt(format!(
+                            "There's no use for a {} in the kitchen.",
+                            item.to_string()
+                        ))));
+                    }
+                    return Ok(Some(ControlFlow::Craft));
+                }
                 if self.world.components.tea_pot.contains(feature) {
                     if let Some(item) = self.player_item.as_ref() {
                         if item.item.unwrap() == Item::Tea {
@@ -878,6 +1585,9 @@ impl Game {
                         ))));
                     }
                 }
+                if let Some(text) = self.world.components.signpost.get(feature) {
+                    return Ok(Some(ControlFlow::Prompt(text.clone())));
+                }
                 if self.world.components.bulletin_board.contains(feature) {
                     return Ok(Some(ControlFlow::Prompt(format!(
                         "\"Enjoy your stay in our cabin!\""
@@ -891,6 +1601,14 @@ impl Game {
                     }
                     return Ok(Some(ControlFlow::Sleep));
                 }
+                if self.world.components.fallen_log.contains(feature) {
+                    // climb onto the log (the extra time is accounted for by the caller)
+                    let _ = self
+                        .world
+                        .spatial_table
+                        .update_coord(self.player, destination);
+                    return Ok(None);
+                }
                 if self.world.components.solid.contains(feature) {
                     if self.world.components.door_state.contains(feature) {
                         self.world.open_door(feature);
@@ -919,10 +1637,34 @@ impl Game {
                     }
                 }
             }
+            if let Some(boat) = self.player_boat {
+                if self.world.lake_mode(destination).is_some() {
+                    // row the boat, taking the player along with it
+                    let _ = self.world.spatial_table.update_coord(boat, destination);
+                    let _ = self
+                        .world
+                        .spatial_table
+                        .update_coord(self.player, destination);
+                    return Ok(None);
+                }
+                self.player_boat = None;
+            } else if let Some(boat) = self.boat_at(destination) {
+                self.player_boat = Some(boat);
+                let _ = self
+                    .world
+                    .spatial_table
+                    .update_coord(self.player, destination);
+                return Ok(Some(ControlFlow::Prompt(prompts::board_boat())));
+            } else if let Some(mode) = self.world.lake_mode(destination) {
+                if self.world.lake_mode(player_coord) < Some(mode) {
+                    return Ok(Some(ControlFlow::EnterLake { direction, mode }));
+                }
+            }
             let _ = self
                 .world
                 .spatial_table
                 .update_coord(self.player, destination);
+            self.world.leave_footprint(destination, direction);
             if let Some(floor) = self
                 .world
                 .spatial_table
@@ -935,6 +1677,18 @@ impl Game {
                     self.increase_motivation(motivation::LAKE);
                     return Ok(Some(ControlFlow::Prompt(prompts::lake())));
                 }
+                if self.world.components.lookout.contains(floor) {
+                    self.visibility_grid.reveal(
+                        &self.world,
+                        destination,
+                        visibility::LOOKOUT_VISION_DISTANCE_SQUARED,
+                    );
+                    if !self.motivation_flags.lookout {
+                        self.motivation_flags.lookout = true;
+                        self.increase_motivation(motivation::LOOKOUT);
+                        return Ok(Some(ControlFlow::Prompt(prompts::lookout())));
+                    }
+                }
             }
         } else {
             return ActionError::err_cant_walk_there();
@@ -943,17 +1697,141 @@ impl Game {
     }
 
     const TURN_TIME: u32 = 120;
+    const CHOP_LOG_TIME: u32 = 1200;
+
+    fn fallen_log_at(&self, coord: Coord) -> Option<Entity> {
+        self.world
+            .spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.feature)
+            .filter(|&feature| self.world.components.fallen_log.contains(feature))
+    }
+
+    const ROWING_TIME: u32 = 180;
+    const CLIMB_TIME: u32 = 240;
+
+    /// Climbs the ladder or stairs in a neighbouring cell to another level, arriving in the cell
+    /// the player climbed from, or beside the way back if that's blocked
+    fn climb(
+        &mut self,
+        level: Level,
+        stairs_coord: Coord,
+    ) -> Result<Option<ControlFlow>, ActionError> {
+        let player_coord = self.player_coord();
+        self.world.spatial_table.remove(self.player);
+        let previous = self.world.enter_level(level);
+        let arrival = std::iter::once(player_coord)
+            .chain(CardinalDirection::all().map(|d| stairs_coord + d.coord()))
+            .find(|&coord| {
+                self.world.walk_time(stairs_coord, coord).is_some()
+                    && self
+                        .world
+                        .spatial_table
+                        .layers_at_checked(coord)
+                        .character
+                        .is_none()
+            });
+        let character_at = |coord| Location {
+            coord,
+            layer: Some(Layer::Character),
+        };
+        let arrived = match arrival {
+            Some(arrival) => self
+                .world
+                .spatial_table
+                .update(self.player, character_at(arrival))
+                .map_err(ActionError::from),
+            None => ActionError::err_msg("Something is blocking the way!"),
+        };
+        if let Err(error) = arrived {
+            // stay where they were
+            self.world.enter_level(previous);
+            self.world
+                .spatial_table
+                .update(self.player, character_at(player_coord))?;
+            return Err(error);
+        }
+        let first_visit = !self.level_visibility.contains_key(&level);
+        let visibility_grid = self
+            .level_visibility
+            .remove(&level)
+            .unwrap_or_else(|| VisibilityGrid::new(self.world.size()));
+        let previous_visibility_grid = mem::replace(&mut self.visibility_grid, visibility_grid);
+        self.level_visibility
+            .insert(previous, previous_visibility_grid);
+        Ok(if first_visit {
+            prompts::first_visit(level).map(ControlFlow::Prompt)
+        } else {
+            None
+        })
+    }
+
+    fn boat_at(&self, coord: Coord) -> Option<Entity> {
+        self.world
+            .spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.feature)
+            .filter(|&feature| self.world.components.boat.contains(feature))
+    }
+
+    /// The time it takes the player to walk onto the given neighbouring cell. Actions that
+    /// don't move the player, such as opening a door, take a single turn.
+    fn walk_time(&self, destination: Coord) -> u32 {
+        if self.player_boat.is_some() && self.world.lake_mode(destination).is_some() {
+            return Self::ROWING_TIME;
+        }
+        if self.world.stairs_at(destination).is_some() {
+            return Self::CLIMB_TIME;
+        }
+        self.world
+            .walk_time(self.player_coord(), destination)
+            .unwrap_or(Self::TURN_TIME)
+    }
+
+    /// The expected time to walk onto a cell, for display purposes
+    pub fn expected_walk_time(&self, coord: Coord) -> Option<u32> {
+        if coord.manhattan_distance(self.player_coord()) == 1 {
+            self.world.walk_time(self.player_coord(), coord)
+        } else {
+            self.world.expected_walk_time(coord)
+        }
+    }
+
+    pub fn footprint(&self, coord: Coord) -> Option<Footprint> {
+        self.world.footprint(coord)
+    }
 
     pub fn player_walk(
         &mut self,
         direction: CardinalDirection,
         config: &Config,
     ) -> Result<Option<ControlFlow>, ActionError> {
+        let time = self.walk_time(self.player_coord() + direction.coord());
         let control_flow = self.player_walk_inner(direction)?;
-        self.after_turn(Self::TURN_TIME, config);
+        if let Some(ControlFlow::EnterLake { .. }) = control_flow {
+            // no time passes until the player confirms they want to get wet
+            return Ok(control_flow);
+        }
+        self.after_turn(time, config);
         Ok(self.check_endgame().or(control_flow))
     }
 
+    /// Called once the player has confirmed that they want to wade or swim into the lake
+    pub fn player_enter_lake(
+        &mut self,
+        direction: CardinalDirection,
+        config: &Config,
+    ) -> Option<ControlFlow> {
+        let destination = self.player_coord() + direction.coord();
+        let time = self.walk_time(destination);
+        let _ = self
+            .world
+            .spatial_table
+            .update_coord(self.player, destination);
+        self.after_turn(time, config);
+        self.check_endgame()
+    }
+
     pub fn player_walk_until_collide(
         &mut self,
         direction: CardinalDirection,
@@ -970,14 +1848,15 @@ impl Game {
                 if layers.feature.is_some() {
                     break None;
                 }
-                if let Some(floor) = layers.floor {
-                    if self.world.components.lake.contains(floor) {
-                        break None;
-                    }
+                if self.player_boat.is_none()
+                    && self.world.lake_mode(destination) > self.world.lake_mode(player_coord)
+                {
+                    break None;
                 }
             }
+            let time = self.walk_time(destination);
             if let Ok(maybe_control_flow) = self.player_walk_inner(direction) {
-                self.after_turn(Self::TURN_TIME, config);
+                self.after_turn(time, config);
                 if let Some(control_flow) = maybe_control_flow {
                     break Some(control_flow);
                 }
@@ -1094,4 +1973,230 @@ impl Game {
         self.after_turn(Self::TURN_TIME, config);
         Ok(self.check_endgame())
     }
+
+    pub fn player_chop(&mut self, config: &Config) -> Result<Option<ControlFlow>, ActionError> {
+        if !self.equipped.axe {
+            return ActionError::err_msg("You don't have the axe equipped!");
+        }
+        let player_coord = self.player_coord();
+        let log = std::iter::once(player_coord)
+            .chain(CardinalDirection::all().map(|d| player_coord + d.coord()))
+            .find_map(|coord| self.fallen_log_at(coord));
+        let log = match log {
+            Some(log) => log,
+            None => return ActionError::err_msg("There is no fallen log here to chop!"),
+        };
+        let message = if self.world.chop_log(log) {
+            "You chop up the fallen log and stack the firewood."
+        } else {
+            "You chop up the fallen log."
+        };
+        self.after_turn(Self::CHOP_LOG_TIME, config);
+        Ok(self
+            .check_endgame()
+            .or(Some(ControlFlow::Prompt(message.to_string()))))
+    }
+
+    pub fn player_fish(&mut self, config: &Config) -> Result<Option<ControlFlow>, ActionError> {
+        if !self.equipped.fishing_rod {
+            return ActionError::err_msg("You don't have the fishing rod equipped!");
+        }
+        let player_coord = self.player_coord();
+        let next_to_lake = std::iter::once(player_coord)
+            .chain(CardinalDirection::all().map(|d| player_coord + d.coord()))
+            .any(|coord| self.world.lake_mode(coord).is_some());
+        if !next_to_lake {
+            return ActionError::err_msg("You need to be next to the lake to fish!");
+        }
+        let bite_chance = fishing::bite_chance(self.rain_level(), self.time.hour());
+        let message = if bite_chance.roll(&mut self.rng) {
+            let catch = Catch::new(self.time.day(), self.time.hour(), &mut self.rng);
+            self.fish_held += 1;
+            self.catch_log.push(catch);
+            prompts::catch(&catch)
+        } else {
+            "You cast your line, but nothing bites.".to_string()
+        };
+        self.after_turn(fishing::CAST_TIME, config);
+        Ok(self.check_endgame().or(Some(ControlFlow::Prompt(message))))
+    }
+
+    /// Number of an ingredient available for crafting, counting both the ingredients left on the
+    /// kitchen bench and the fish the player is carrying
+    pub fn ingredient_count(&self, ingredient: Ingredient) -> u32 {
+        let carried = match ingredient {
+            Ingredient::Fish => self.fish_held,
+            _ => 0,
+        };
+        self.pantry.count(ingredient) + carried
+    }
+
+    pub fn pantry_contents(&self) -> String {
+        if self.pantry.is_empty() {
+            "(nothing)".to_string()
+        } else {
+            self.pantry.contents_string()
+        }
+    }
+
+    pub fn crafted_today(&self, index: usize) -> bool {
+        self.motivation_flags.crafted.contains(&index)
+    }
+
+    pub fn can_craft(&self, index: usize) -> bool {
+        crafting::RECIPES[index]
+            .ingredients
+            .iter()
+            .all(|&(ingredient, count)| self.ingredient_count(ingredient) >= count)
+    }
+
+    pub fn player_craft(
+        &mut self,
+        index: usize,
+        config: &Config,
+    ) -> Result<Option<ControlFlow>, ActionError> {
+        let recipe = &crafting::RECIPES[index];
+        if self.crafted_today(index) {
+            return Err(ActionError::Message(format!(
+                "You've already made {} today.",
+                recipe.name
+            )));
+        }
+        if !self.can_craft(index) {
+            return Err(ActionError::Message(format!(
+                "You need {} to make {}.",
+                recipe.ingredients_string(),
+                recipe.name
+            )));
+        }
+        for &(ingredient, count) in recipe.ingredients {
+            let remaining = count - self.pantry.take(ingredient, count);
+            if let Ingredient::Fish = ingredient {
+                self.fish_held -= remaining;
+            }
+        }
+        self.motivation_flags.crafted.push(index);
+        self.increase_motivation(recipe.motivation);
+        self.after_turn(crafting::CRAFT_TIME, config);
+        let message = format!(
+            "{}\n\nMotivation increased by {}.",
+            recipe.flavour, recipe.motivation
+        );
+        Ok(self.check_endgame().or(Some(ControlFlow::Prompt(message))))
+    }
+
+    /// Number of caught fish that haven't been cooked yet
+    pub fn fish_held(&self) -> u32 {
+        self.fish_held
+    }
+
+    pub fn catch_summary(&self) -> String {
+        fishing::summary(&self.catch_log)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The player starts two cells east of a ladder, with ground to the east for stairs down
+    const MAP: &str = "map\n......\n.H.@..\n......\n";
+    const LADDER: Coord = Coord::new(1, 1);
+    const STAIRS_DOWN: Coord = Coord::new(5, 1);
+
+    /// A game on a small custom map, with a loft and a cellar added by hand since custom maps
+    /// only have the ground level
+    fn game_with_levels() -> (Game, Config) {
+        let config = Config {
+            omniscient: false,
+            debug: false,
+            terrain: TerrainConfig::default(),
+            map: Some(CustomMap::parse(MAP).unwrap()),
+            endless: false,
+        };
+        let mut game = Game::new(&config, &mut Isaac64Rng::seed_from_u64(0)).unwrap();
+        let world = &mut game.world;
+        let size = world.size();
+        let ladder = world
+            .spatial_table
+            .layers_at_checked(LADDER)
+            .feature
+            .unwrap();
+        world.components.stairs.insert(ladder, Level::Loft);
+        world.spawn_stairs_down(STAIRS_DOWN);
+        world.enter_level(Level::Loft);
+        for coord in size.coord_iter_row_major() {
+            world.spawn_floor(coord, 0.);
+        }
+        world.spawn_hatch(LADDER);
+        world.enter_level(Level::Cellar);
+        for coord in size.coord_iter_row_major() {
+            world.spawn_cellar_floor(coord);
+        }
+        world.spawn_stairs_up(STAIRS_DOWN);
+        world.enter_level(Level::Ground);
+        (game, config)
+    }
+
+    fn walk(game: &mut Game, direction: CardinalDirection, config: &Config) {
+        if let Err(ActionError::Message(message)) = game.player_walk(direction, config) {
+            panic!("{}", message);
+        }
+    }
+
+    #[test]
+    fn climb_ladder_and_stairs() {
+        use CardinalDirection::*;
+        let (mut game, config) = game_with_levels();
+        assert_eq!(game.player_coord(), Coord::new(3, 1));
+        walk(&mut game, West, &config);
+        // the player arrives in the cell they climbed from
+        walk(&mut game, West, &config);
+        assert_eq!(game.level(), Level::Loft);
+        assert_eq!(game.player_coord(), Coord::new(2, 1));
+        walk(&mut game, West, &config);
+        assert_eq!(game.level(), Level::Ground);
+        assert_eq!(game.player_coord(), Coord::new(2, 1));
+        walk(&mut game, East, &config);
+        walk(&mut game, East, &config);
+        walk(&mut game, East, &config);
+        assert_eq!(game.level(), Level::Cellar);
+        assert_eq!(game.player_coord(), Coord::new(4, 1));
+        walk(&mut game, East, &config);
+        assert_eq!(game.level(), Level::Ground);
+        assert_eq!(game.player_coord(), Coord::new(4, 1));
+    }
+
+    #[test]
+    fn climb_beside_the_ladder_when_the_cell_above_is_taken() {
+        use CardinalDirection::*;
+        let (mut game, config) = game_with_levels();
+        game.world.enter_level(Level::Loft);
+        game.world.spawn_wall(Coord::new(2, 1));
+        game.world.enter_level(Level::Ground);
+        walk(&mut game, West, &config);
+        walk(&mut game, West, &config);
+        assert_eq!(game.level(), Level::Loft);
+        assert_eq!(game.player_coord(), LADDER + North.coord());
+    }
+
+    #[test]
+    fn stay_put_when_there_is_nowhere_to_arrive() {
+        use CardinalDirection::*;
+        let (mut game, config) = game_with_levels();
+        game.world.enter_level(Level::Loft);
+        for coord in [
+            Coord::new(2, 1),
+            Coord::new(1, 0),
+            Coord::new(1, 2),
+            Coord::new(0, 1),
+        ] {
+            game.world.spawn_wall(coord);
+        }
+        game.world.enter_level(Level::Ground);
+        walk(&mut game, West, &config);
+        assert!(game.player_walk(West, &config).is_err());
+        assert_eq!(game.level(), Level::Ground);
+        assert_eq!(game.player_coord(), Coord::new(2, 1));
+    }
 }
@@ -0,0 +1,70 @@
+use crate::world::World;
+use gridbugs::{direction::CardinalDirection, grid_2d::Coord};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Lake cells at most this many cells from the shore are shallow enough to wade through
+const MAX_WADING_DEPTH: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LakeMode {
+    Wading,
+    Swimming,
+}
+
+impl World {
+    /// Records the depth of each lake cell as its distance from the nearest cell that isn't part
+    /// of the lake. Cells on the edge of the map don't count as shore.
+    pub fn compute_lake_depths(&mut self) {
+        self.components.lake_depth.clear();
+        let mut queue = VecDeque::new();
+        for lake in self.components.lake.entities() {
+            let coord = match self.spatial_table.coord_of(lake) {
+                Some(coord) => coord,
+                None => continue,
+            };
+            let on_shore = CardinalDirection::all().any(|d| {
+                self.spatial_table
+                    .layers_at(coord + d.coord())
+                    .and_then(|layers| layers.floor)
+                    .map(|floor| !self.components.lake.contains(floor))
+                    .unwrap_or(false)
+            });
+            if on_shore {
+                queue.push_back((lake, coord, 1));
+            }
+        }
+        while let Some((lake, coord, depth)) = queue.pop_front() {
+            if self.components.lake_depth.contains(lake) {
+                continue;
+            }
+            self.components.lake_depth.insert(lake, depth);
+            for d in CardinalDirection::all() {
+                if let Some(floor) = self
+                    .spatial_table
+                    .layers_at(coord + d.coord())
+                    .and_then(|layers| layers.floor)
+                {
+                    if self.components.lake.contains(floor)
+                        && !self.components.lake_depth.contains(floor)
+                    {
+                        queue.push_back((floor, coord + d.coord(), depth + 1));
+                    }
+                }
+            }
+        }
+    }
+
+    /// How the player would move through the water at the given coordinate, or `None` if it isn't
+    /// part of the lake
+    pub fn lake_mode(&self, coord: Coord) -> Option<LakeMode> {
+        let floor = self.spatial_table.layers_at(coord)?.floor?;
+        if !self.components.lake.contains(floor) {
+            return None;
+        }
+        match self.components.lake_depth.get(floor) {
+            Some(&depth) if depth <= MAX_WADING_DEPTH => Some(LakeMode::Wading),
+            _ => Some(LakeMode::Swimming),
+        }
+    }
+}
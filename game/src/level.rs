@@ -0,0 +1,108 @@
+use crate::{components::Tile, spatial::SpatialTable, world::World};
+use gridbugs::grid_2d::Coord;
+use rand::{seq::IteratorRandom, Rng};
+use rational::Rational;
+use serde::{Deserialize, Serialize};
+use std::mem;
+
+/// Number of turns of uninterrupted heavy rain before water starts seeping up into cellars
+pub const CELLAR_FLOOD_TURNS: u32 = 90;
+
+/// Chance each turn of another cell of a cellar floor flooding, once the heavy rain has been
+/// going for long enough
+pub const CELLAR_SEEP_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 3,
+};
+
+/// Chance each turn of a flooded cell of a cellar floor drying out when the rain isn't heavy
+pub const CELLAR_DRAIN_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 10,
+};
+
+/// Everywhere but the ground level is inside a cabin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Level {
+    Cellar,
+    Ground,
+    Loft,
+}
+
+impl World {
+    /// Makes the given level's spatial table the world's spatial table, putting the current one
+    /// aside. Returns the level that was current, so it can be switched back to afterwards.
+    pub fn enter_level(&mut self, level: Level) -> Level {
+        let previous = self.level;
+        if level != previous {
+            let size = self.size();
+            let spatial_table = self
+                .levels
+                .remove(&level)
+                .unwrap_or_else(|| SpatialTable::new(size));
+            let previous_spatial_table = mem::replace(&mut self.spatial_table, spatial_table);
+            self.levels.insert(previous, previous_spatial_table);
+            self.level = level;
+        }
+        previous
+    }
+
+    /// The spatial table of the ground level, wherever the player is
+    pub fn ground_spatial_table(&self) -> &SpatialTable {
+        if self.level == Level::Ground {
+            &self.spatial_table
+        } else {
+            &self.levels[&Level::Ground]
+        }
+    }
+
+    /// Calls `f` with each level that has a spatial table entered in turn, then goes back to the
+    /// current level
+    pub fn for_each_level<F: FnMut(&mut Self)>(&mut self, mut f: F) {
+        let current = self.level;
+        let mut levels = self.levels.keys().cloned().collect::<Vec<_>>();
+        levels.push(current);
+        for level in levels {
+            self.enter_level(level);
+            f(self);
+        }
+        self.enter_level(current);
+    }
+
+    /// The level that the stairs or ladder in the given cell lead to
+    pub fn stairs_at(&self, coord: Coord) -> Option<Level> {
+        self.spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.feature)
+            .and_then(|feature| self.components.stairs.get(feature).cloned())
+    }
+
+    /// Water seeps up through a random dry patch of cellar floor
+    pub fn seep_into_cellar<R: Rng>(&mut self, rng: &mut R) {
+        let dry = self
+            .components
+            .cellar
+            .entities()
+            .filter(|entity| !self.components.water.contains(*entity))
+            .choose(rng);
+        if let Some(floor) = dry {
+            self.become_water(floor, rng);
+        }
+    }
+
+    pub fn drain_cellar<R: Rng>(&mut self, rng: &mut R) {
+        let flooded = self
+            .components
+            .cellar
+            .entities()
+            .filter(|entity| self.components.water.contains(*entity))
+            .choose(rng);
+        if let Some(floor) = flooded {
+            self.components.tile.insert(floor, Tile::Floor);
+            self.components.realtime.remove(floor);
+            self.components.water.remove(floor);
+            self.components.colour_hint.remove(floor);
+            self.realtime_components.flicker.remove(floor);
+        }
+    }
+}
@@ -1,15 +1,44 @@
+mod cabin;
+mod chunk;
 mod components;
+mod crafting;
+mod custom_map;
+mod erosion;
+mod fishing;
+mod forage;
 mod game;
+mod lake;
+mod level;
+mod lighting;
+mod movement;
+mod prefab;
+mod reachability;
 mod realtime;
 mod spatial;
 mod spawn;
+mod storm;
+mod stream;
 mod terrain;
+mod trail;
 mod visibility;
+mod wetness;
 pub mod witness;
 mod world;
 
-pub use components::{Item, Tile};
-pub use game::{ActionError, Config, RainLevel, RainSchedule, TopographyCell, MAX_MOTIVATION};
+pub use chunk::Chunk;
+pub use components::{Footprint, Item, Tile};
+pub use crafting::{Ingredient, Recipe, RECIPES};
+pub use custom_map::CustomMap;
+pub use game::{
+    ActionError, Config, RainLevel, RainSchedule, Time, TopographyCell, WindLevel, MAX_MOTIVATION,
+};
 pub use gridbugs::entity_table::Entity;
+pub use lake::LakeMode;
+pub use level::Level;
+pub use lighting::MoonPhase;
+pub use prefab::Spawn;
+pub use storm::Thunder;
+pub use terrain::{GenerationFailed, GenerationStats, TerrainConfig};
 pub use visibility::{CellVisibility, EntityTile, Omniscient, VisibilityCell, VisibilityGrid};
+pub use wetness::FOOTPRINT_STRENGTH;
 pub use witness::Game;
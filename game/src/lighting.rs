@@ -0,0 +1,328 @@
+use gridbugs::rgb_int::Rgb24;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const LUNAR_CYCLE_DAYS: u32 = 29;
+const MOONLIGHT: Ambient = Ambient {
+    colour: Rgb24::new(90, 90, 100),
+    vision_distance_squared: 120,
+};
+
+/// The ambient light at a particular minute of the day
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Ambient {
+    pub colour: Rgb24,
+    pub vision_distance_squared: u32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct Keyframe {
+    minute_of_day: u32,
+    ambient: Ambient,
+}
+
+/// Keyframed description of how the ambient light changes over the course of a day. Values
+/// between keyframes are linearly interpolated, wrapping around at midnight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightingCurve {
+    keyframes: Vec<Keyframe>,
+    lamp_threshold: u8,
+}
+
+impl Default for LightingCurve {
+    fn default() -> Self {
+        Self::parse(include_str!("lighting_curve.txt"))
+    }
+}
+
+impl LightingCurve {
+    pub fn parse(s: &str) -> Self {
+        let mut keyframes = Vec::new();
+        let mut lamp_threshold = None;
+        for line in s.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            match fields.as_slice() {
+                ["lamp_threshold", threshold] => {
+                    lamp_threshold = Some(threshold.parse().expect("invalid lamp threshold"));
+                }
+                [time, r, g, b, vision_distance_squared] => {
+                    let (hour, minute) = time.split_once(':').expect("invalid keyframe time");
+                    let hour: u32 = hour.parse().expect("invalid keyframe hour");
+                    let minute: u32 = minute.parse().expect("invalid keyframe minute");
+                    keyframes.push(Keyframe {
+                        minute_of_day: hour * 60 + minute,
+                        ambient: Ambient {
+                            colour: Rgb24::new(
+                                r.parse().expect("invalid red channel"),
+                                g.parse().expect("invalid green channel"),
+                                b.parse().expect("invalid blue channel"),
+                            ),
+                            vision_distance_squared: vision_distance_squared
+                                .parse()
+                                .expect("invalid vision distance"),
+                        },
+                    });
+                }
+                _ => panic!("unexpected line in lighting curve: {}", line),
+            }
+        }
+        if keyframes.is_empty() {
+            panic!("lighting curve has no keyframes");
+        }
+        keyframes.sort_by_key(|k| k.minute_of_day);
+        Self {
+            keyframes,
+            lamp_threshold: lamp_threshold.expect("lighting curve has no lamp threshold"),
+        }
+    }
+
+    pub fn at_minute_of_day(&self, minute_of_day: u32) -> Ambient {
+        let minute_of_day = minute_of_day % MINUTES_PER_DAY;
+        let next_index = self
+            .keyframes
+            .iter()
+            .position(|k| k.minute_of_day > minute_of_day)
+            .unwrap_or(0);
+        let prev_index = (next_index + self.keyframes.len() - 1) % self.keyframes.len();
+        let prev = self.keyframes[prev_index];
+        let next = self.keyframes[next_index];
+        let span = (next.minute_of_day + MINUTES_PER_DAY - prev.minute_of_day) % MINUTES_PER_DAY;
+        if span == 0 {
+            return prev.ambient;
+        }
+        let elapsed = (minute_of_day + MINUTES_PER_DAY - prev.minute_of_day) % MINUTES_PER_DAY;
+        let by = ((elapsed * 255) / span) as u8;
+        let prev_distance = prev.ambient.vision_distance_squared as i64;
+        let next_distance = next.ambient.vision_distance_squared as i64;
+        Ambient {
+            colour: prev
+                .ambient
+                .colour
+                .linear_interpolate(next.ambient.colour, by),
+            vision_distance_squared: (prev_distance
+                + ((next_distance - prev_distance) * elapsed as i64) / span as i64)
+                as u32,
+        }
+    }
+
+    pub fn lamps_should_be_on(&self, ambient: Ambient) -> bool {
+        ambient.colour.max_channel() < self.lamp_threshold
+    }
+
+    /// How dark the given ambient light is relative to the darkest and brightest keyframes, where
+    /// 0 is broad daylight and 255 is the middle of the night
+    fn darkness(&self, ambient: Ambient) -> u8 {
+        let brightest = self
+            .keyframes
+            .iter()
+            .map(|k| k.ambient.colour.max_channel() as u32)
+            .max()
+            .unwrap();
+        let darkest = self
+            .keyframes
+            .iter()
+            .map(|k| k.ambient.colour.max_channel() as u32)
+            .min()
+            .unwrap();
+        if brightest == darkest {
+            return 0;
+        }
+        let current = (ambient.colour.max_channel() as u32).clamp(darkest, brightest);
+        (((brightest - current) * 255) / (brightest - darkest)) as u8
+    }
+
+    /// Modulates ambient light according to the state of the sky. Clouds dim the light (most
+    /// noticeably at night) and the moon lights up the night when it isn't hidden by clouds.
+    pub fn apply_sky(&self, ambient: Ambient, sky: Sky) -> Ambient {
+        let darkness = self.darkness(ambient) as u32;
+        let cloud_cover = sky.cloud_cover as u32;
+        let dim = (cloud_cover * (32 + darkness / 2)) / 255;
+        let moonlight =
+            (sky.moon_phase.brightness() as u32 * (255 - cloud_cover) * darkness) / (255 * 255);
+        Ambient {
+            colour: ambient
+                .colour
+                .saturating_scalar_mul_div(255 - dim, 255)
+                .saturating_add(MOONLIGHT.colour.saturating_scalar_mul_div(moonlight, 255)),
+            vision_distance_squared: (ambient.vision_distance_squared * (255 - dim)) / 255
+                + (MOONLIGHT.vision_distance_squared * moonlight) / 255,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MoonPhase {
+    day_in_cycle: u32,
+}
+
+impl MoonPhase {
+    /// The phase of the moon on a given day, where `offset` is the point in the lunar cycle on
+    /// the first day of the trip
+    pub fn on_day(offset: u32, day: u32) -> Self {
+        Self {
+            day_in_cycle: (offset + day) % LUNAR_CYCLE_DAYS,
+        }
+    }
+
+    pub fn random_offset<R: Rng>(rng: &mut R) -> u32 {
+        rng.gen_range(0..LUNAR_CYCLE_DAYS)
+    }
+
+    /// 0 at new moon, 255 at full moon
+    pub fn brightness(&self) -> u8 {
+        let half_cycle = LUNAR_CYCLE_DAYS / 2;
+        let from_full = (self.day_in_cycle as i32 - half_cycle as i32).unsigned_abs();
+        (255 - (from_full * 255) / half_cycle) as u8
+    }
+}
+
+impl fmt::Display for MoonPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let half_cycle = LUNAR_CYCLE_DAYS / 2;
+        let name = match self.day_in_cycle {
+            0 => "New Moon",
+            d if d < half_cycle / 2 => "Waxing Crescent",
+            d if d < half_cycle => "Waxing Gibbous",
+            d if d == half_cycle => "Full Moon",
+            d if d < half_cycle + half_cycle / 2 => "Waning Gibbous",
+            _ => "Waning Crescent",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The parts of the weather that affect how much light reaches the ground
+#[derive(Debug, Clone, Copy)]
+pub struct Sky {
+    pub moon_phase: MoonPhase,
+    /// 0 is a clear sky, 255 is complete overcast
+    pub cloud_cover: u8,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Bright at 6am and dark at 6pm, with the keyframes out of order
+    const CURVE: &str = "\
+# a test curve
+lamp_threshold 100
+
+18:00 0 0 0 40
+06:00 200 200 200 400
+";
+
+    fn at(curve: &LightingCurve, hour: u32, minute: u32) -> Ambient {
+        curve.at_minute_of_day(hour * 60 + minute)
+    }
+
+    #[test]
+    fn parse_curve() {
+        let curve = LightingCurve::parse(CURVE);
+        assert_eq!(curve.lamp_threshold, 100);
+        let minutes = curve
+            .keyframes
+            .iter()
+            .map(|k| k.minute_of_day)
+            .collect::<Vec<_>>();
+        assert_eq!(minutes, vec![6 * 60, 18 * 60]);
+        assert_eq!(curve.keyframes[0].ambient.colour, Rgb24::new(200, 200, 200));
+        assert_eq!(curve.keyframes[0].ambient.vision_distance_squared, 400);
+        // the built-in curve parses too
+        assert!(!LightingCurve::default().keyframes.is_empty());
+    }
+
+    #[test]
+    fn interpolate_between_keyframes() {
+        let curve = LightingCurve::parse(CURVE);
+        let morning = at(&curve, 6, 0);
+        assert_eq!(morning.colour, Rgb24::new(200, 200, 200));
+        assert_eq!(morning.vision_distance_squared, 400);
+        let midday = at(&curve, 12, 0);
+        assert_eq!(midday.vision_distance_squared, 220);
+        assert!((99..=101).contains(&midday.colour.r));
+        let evening = at(&curve, 18, 0);
+        assert_eq!(evening.colour, Rgb24::new(0, 0, 0));
+        assert_eq!(evening.vision_distance_squared, 40);
+    }
+
+    #[test]
+    fn interpolate_past_midnight() {
+        let curve = LightingCurve::parse(CURVE);
+        let midnight = at(&curve, 0, 0);
+        assert_eq!(midnight.vision_distance_squared, 220);
+        assert!((99..=101).contains(&midnight.colour.r));
+        // the hour before the first keyframe is still on its way from the last one
+        assert!(at(&curve, 5, 0).vision_distance_squared > 220);
+        // minutes past the end of the day wrap around to the next day
+        assert_eq!(
+            curve
+                .at_minute_of_day(MINUTES_PER_DAY + 18 * 60)
+                .vision_distance_squared,
+            40
+        );
+    }
+
+    #[test]
+    fn lamps_on_in_the_dark() {
+        let curve = LightingCurve::parse(CURVE);
+        assert!(!curve.lamps_should_be_on(at(&curve, 6, 0)));
+        assert!(!curve.lamps_should_be_on(at(&curve, 12, 0)));
+        assert!(curve.lamps_should_be_on(at(&curve, 18, 0)));
+        assert!(curve.lamps_should_be_on(at(&curve, 21, 0)));
+    }
+
+    #[test]
+    fn moon_phases() {
+        let phase = |day| MoonPhase::on_day(0, day);
+        assert_eq!(phase(0).brightness(), 0);
+        assert_eq!(phase(14).brightness(), 255);
+        assert!(phase(7).brightness() > phase(3).brightness());
+        let names = [0, 3, 10, 14, 18, 25]
+            .map(|day| phase(day).to_string())
+            .to_vec();
+        assert_eq!(
+            names,
+            vec![
+                "New Moon",
+                "Waxing Crescent",
+                "Waxing Gibbous",
+                "Full Moon",
+                "Waning Gibbous",
+                "Waning Crescent"
+            ]
+        );
+        // the cycle starts again after the last day
+        assert_eq!(MoonPhase::on_day(20, 9).to_string(), "New Moon");
+    }
+
+    #[test]
+    fn moonlight_and_clouds() {
+        let curve = LightingCurve::parse(CURVE);
+        let night = at(&curve, 18, 0);
+        let sky = |day, cloud_cover| Sky {
+            moon_phase: MoonPhase::on_day(0, day),
+            cloud_cover,
+        };
+        let new_moon = curve.apply_sky(night, sky(0, 0));
+        assert_eq!(new_moon.colour, night.colour);
+        assert_eq!(new_moon.vision_distance_squared, 40);
+        let full_moon = curve.apply_sky(night, sky(14, 0));
+        assert_eq!(full_moon.colour, MOONLIGHT.colour);
+        assert_eq!(full_moon.vision_distance_squared, 160);
+        // clouds hide the moon
+        let overcast = curve.apply_sky(night, sky(14, 255));
+        assert!(overcast.vision_distance_squared < night.vision_distance_squared);
+        // and dim the day a little
+        let day = at(&curve, 6, 0);
+        let cloudy_day = curve.apply_sky(day, sky(14, 255));
+        assert!(cloudy_day.colour.r < day.colour.r);
+        assert!(cloudy_day.colour.r > day.colour.r / 2);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{components::Tile, lake::LakeMode, world::World};
+use gridbugs::{direction::CardinalDirection, grid_2d::Coord};
+
+/// Seconds taken to step onto each kind of floor
+mod floor_time {
+    pub const CABIN: u32 = 60;
+    pub const PIER: u32 = 90;
+    pub const BRIDGE: u32 = 90;
+    pub const FORD: u32 = 300;
+    /// Climbing the ladder to the top of the lookout tower
+    pub const LOOKOUT: u32 = 600;
+    pub const RUINS: u32 = 120;
+    pub const GROUND: u32 = 120;
+    pub const TRAIL: u32 = 90;
+    pub const DITCH: u32 = 180;
+    pub const FLOOD_WATER: u32 = 240;
+    pub const STEPPING_STONE: u32 = 150;
+    pub const MUD: u32 = 360;
+    pub const PUDDLE: u32 = 150;
+    pub const WADING: u32 = 300;
+    pub const SWIMMING: u32 = 360;
+}
+
+/// Extra seconds taken to push through long grass
+const LONG_GRASS_TIME: u32 = 60;
+
+/// Seconds taken to climb over a fallen log, regardless of what it's lying on
+const CLIMB_OVER_LOG_TIME: u32 = 600;
+
+/// Extra seconds taken per unit of height climbed. Going downhill is no faster.
+const UPHILL_TIME_PER_HEIGHT: f64 = 1500.;
+
+impl World {
+    fn floor_walk_time(&self, coord: Coord) -> Option<u32> {
+        let layers = self.spatial_table.layers_at(coord)?;
+        let floor = layers.floor?;
+        if let Some(mode) = self.lake_mode(coord) {
+            return Some(match mode {
+                LakeMode::Wading => floor_time::WADING,
+                LakeMode::Swimming => floor_time::SWIMMING,
+            });
+        }
+        if self.components.stream.contains(floor) {
+            return match self.components.tile.get(floor)? {
+                Tile::Bridge => Some(floor_time::BRIDGE),
+                Tile::Ford => Some(floor_time::FORD),
+                _ => None,
+            };
+        }
+        if self.components.water.contains(floor) {
+            let on_stepping_stone = layers
+                .item
+                .map(|item| self.components.rock.contains(item))
+                .unwrap_or(false);
+            return Some(if on_stepping_stone {
+                floor_time::STEPPING_STONE
+            } else {
+                floor_time::FLOOD_WATER
+            });
+        }
+        if self.is_mud_at(coord) {
+            return Some(floor_time::MUD);
+        }
+        Some(match self.components.tile.get(floor)? {
+            Tile::Floor => floor_time::CABIN,
+            Tile::PierFloor => floor_time::PIER,
+            Tile::RuinsFloor => floor_time::RUINS,
+            Tile::Ditch => floor_time::DITCH,
+            Tile::Puddle => floor_time::PUDDLE,
+            Tile::Trail => floor_time::TRAIL,
+            Tile::Lookout => floor_time::LOOKOUT,
+            _ => floor_time::GROUND,
+        })
+    }
+
+    fn uphill_time(&self, from: Coord, to: Coord) -> u32 {
+        let height_at = |coord| {
+            self.spatial_table
+                .layers_at(coord)
+                .and_then(|layers| layers.floor)
+                .and_then(|floor| self.components.height.get(floor).cloned())
+        };
+        match (height_at(from), height_at(to)) {
+            (Some(from), Some(to)) if to > from => ((to - from) * UPHILL_TIME_PER_HEIGHT) as u32,
+            _ => 0,
+        }
+    }
+
+    /// The number of seconds it takes to step from one cell onto a neighbouring cell, or `None`
+    /// if the destination can't be walked onto
+    pub fn walk_time(&self, from: Coord, to: Coord) -> Option<u32> {
+        let layers = self.spatial_table.layers_at(to)?;
+        let floor_time = self.floor_walk_time(to)?;
+        let feature_time = match layers.feature {
+            Some(feature) if self.components.fallen_log.contains(feature) => {
+                return Some(CLIMB_OVER_LOG_TIME)
+            }
+            Some(feature) if self.components.solid.contains(feature) => return None,
+            Some(feature) if self.components.tile.get(feature) == Some(&Tile::Grass) => {
+                LONG_GRASS_TIME
+            }
+            _ => 0,
+        };
+        Some(floor_time + feature_time + self.uphill_time(from, to))
+    }
+
+    /// The expected number of seconds it takes to step onto a cell, averaged over all the
+    /// neighbouring cells it could be approached from
+    pub fn expected_walk_time(&self, to: Coord) -> Option<u32> {
+        let times = CardinalDirection::all()
+            .map(|d| to + d.coord())
+            .filter(|&from| self.floor_walk_time(from).is_some())
+            .filter_map(|from| self.walk_time(from, to))
+            .collect::<Vec<_>>();
+        if times.is_empty() {
+            None
+        } else {
+            Some(times.iter().sum::<u32>() / times.len() as u32)
+        }
+    }
+}
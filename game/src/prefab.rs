@@ -0,0 +1,747 @@
+use crate::{components::Item, forage, spatial::Layer, terrain::TerrainConfig, world::World};
+use gridbugs::grid_2d::{coord_2d::Axis, Coord, Size};
+use rand::Rng;
+use std::collections::BTreeMap;
+
+/// Something a prefab or custom map can place in a cell. Cells can list several of these, which
+/// are spawned in order (so the floor should come before the feature on top of it).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Spawn {
+    Ground,
+    Floor,
+    Trail,
+    Stream,
+    Ford,
+    Bridge,
+    RuinsFloor,
+    PierFloor,
+    EndOfPier,
+    Water,
+    Lake,
+    Wall,
+    RuinsWall,
+    Tree,
+    /// Doors and windows are spawned across the x axis. Custom maps turn them to suit the
+    /// surrounding walls once every cell has been spawned.
+    Door,
+    Window,
+    Light,
+    Altar,
+    BulletinBoard,
+    Lamp,
+    TowerLeg,
+    Lookout,
+    Grass,
+    Rock,
+    Flower,
+    Tea,
+    /// Marks the floor as a place where flowers grow back
+    FlowerPatch,
+    /// Marks the floor as a place where tea grows back
+    TeaPatch,
+    Mushroom,
+    Signpost,
+    Bed,
+    ChairLeft,
+    ChairRight,
+    Stove,
+    KitchenBench,
+    Table,
+    Bookshelf,
+    Ladder,
+    Teapot,
+    LogX,
+    LogY,
+    Boat,
+    Cairn,
+    Gumboots,
+    Umbrella,
+    Shovel,
+    Map,
+    WeatherReport,
+    Lantern,
+    Crowbar,
+    Axe,
+    FishingRod,
+    Firewood,
+    /// Leaves the cell empty but stops trees growing there
+    Clear,
+}
+
+impl Spawn {
+    /// Everything that can be spawned, in the order the editor offers them
+    pub const ALL: &'static [Self] = &[
+        Self::Ground,
+        Self::Floor,
+        Self::Trail,
+        Self::Stream,
+        Self::Ford,
+        Self::Bridge,
+        Self::RuinsFloor,
+        Self::PierFloor,
+        Self::EndOfPier,
+        Self::Water,
+        Self::Lake,
+        Self::Wall,
+        Self::RuinsWall,
+        Self::Tree,
+        Self::Door,
+        Self::Window,
+        Self::Light,
+        Self::Altar,
+        Self::BulletinBoard,
+        Self::Lamp,
+        Self::TowerLeg,
+        Self::Lookout,
+        Self::Grass,
+        Self::Rock,
+        Self::Flower,
+        Self::Tea,
+        Self::FlowerPatch,
+        Self::TeaPatch,
+        Self::Mushroom,
+        Self::Signpost,
+        Self::Bed,
+        Self::ChairLeft,
+        Self::ChairRight,
+        Self::Stove,
+        Self::KitchenBench,
+        Self::Table,
+        Self::Bookshelf,
+        Self::Ladder,
+        Self::Teapot,
+        Self::LogX,
+        Self::LogY,
+        Self::Boat,
+        Self::Cairn,
+        Self::Gumboots,
+        Self::Umbrella,
+        Self::Shovel,
+        Self::Map,
+        Self::WeatherReport,
+        Self::Lantern,
+        Self::Crowbar,
+        Self::Axe,
+        Self::FishingRod,
+        Self::Firewood,
+        Self::Clear,
+    ];
+
+    /// The name used for this in custom map legends
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ground => "ground",
+            Self::Floor => "floor",
+            Self::Trail => "trail",
+            Self::Stream => "stream",
+            Self::Ford => "ford",
+            Self::Bridge => "bridge",
+            Self::RuinsFloor => "ruins_floor",
+            Self::PierFloor => "pier_floor",
+            Self::EndOfPier => "end_of_pier",
+            Self::Water => "water",
+            Self::Lake => "lake",
+            Self::Wall => "wall",
+            Self::RuinsWall => "ruins_wall",
+            Self::Tree => "tree",
+            Self::Door => "door",
+            Self::Window => "window",
+            Self::Light => "light",
+            Self::Altar => "altar",
+            Self::BulletinBoard => "bulletin_board",
+            Self::Lamp => "lamp",
+            Self::TowerLeg => "tower_leg",
+            Self::Lookout => "lookout",
+            Self::Grass => "grass",
+            Self::Rock => "rock",
+            Self::Flower => "flower",
+            Self::Tea => "tea",
+            Self::FlowerPatch => "flower_patch",
+            Self::TeaPatch => "tea_patch",
+            Self::Mushroom => "mushroom",
+            Self::Signpost => "signpost",
+            Self::Bed => "bed",
+            Self::ChairLeft => "chair_left",
+            Self::ChairRight => "chair_right",
+            Self::Stove => "stove",
+            Self::KitchenBench => "kitchen_bench",
+            Self::Table => "table",
+            Self::Bookshelf => "bookshelf",
+            Self::Ladder => "ladder",
+            Self::Teapot => "teapot",
+            Self::LogX => "log_x",
+            Self::LogY => "log_y",
+            Self::Boat => "boat",
+            Self::Cairn => "cairn",
+            Self::Gumboots => "gumboots",
+            Self::Umbrella => "umbrella",
+            Self::Shovel => "shovel",
+            Self::Map => "map",
+            Self::WeatherReport => "weather_report",
+            Self::Lantern => "lantern",
+            Self::Crowbar => "crowbar",
+            Self::Axe => "axe",
+            Self::FishingRod => "fishing_rod",
+            Self::Firewood => "firewood",
+            Self::Clear => "clear",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|spawn| spawn.name() == name)
+    }
+
+    /// The layer of the spatial table this occupies, if any. Only one thing can be spawned in
+    /// each layer of a cell.
+    pub fn layer(self) -> Option<Layer> {
+        match self {
+            Self::Ground
+            | Self::Floor
+            | Self::Trail
+            | Self::Stream
+            | Self::Ford
+            | Self::Bridge
+            | Self::RuinsFloor
+            | Self::PierFloor
+            | Self::EndOfPier
+            | Self::Water
+            | Self::Lake
+            | Self::Lookout => Some(Layer::Floor),
+            Self::Wall
+            | Self::RuinsWall
+            | Self::Tree
+            | Self::Door
+            | Self::Window
+            | Self::Altar
+            | Self::BulletinBoard
+            | Self::Lamp
+            | Self::TowerLeg
+            | Self::Grass
+            | Self::Signpost
+            | Self::Bed
+            | Self::ChairLeft
+            | Self::ChairRight
+            | Self::Stove
+            | Self::KitchenBench
+            | Self::Table
+            | Self::Bookshelf
+            | Self::Ladder
+            | Self::Teapot
+            | Self::LogX
+            | Self::LogY
+            | Self::Boat
+            | Self::Cairn => Some(Layer::Feature),
+            Self::Rock
+            | Self::Flower
+            | Self::Tea
+            | Self::Mushroom
+            | Self::Gumboots
+            | Self::Umbrella
+            | Self::Shovel
+            | Self::Map
+            | Self::WeatherReport
+            | Self::Lantern
+            | Self::Crowbar
+            | Self::Axe
+            | Self::FishingRod
+            | Self::Firewood => Some(Layer::Item),
+            Self::Light | Self::FlowerPatch | Self::TeaPatch | Self::Clear => None,
+        }
+    }
+}
+
+impl World {
+    pub fn spawn_prefab_cell<R: Rng>(
+        &mut self,
+        spawn: Spawn,
+        coord: Coord,
+        height: f64,
+        rng: &mut R,
+    ) {
+        match spawn {
+            Spawn::Ground => {
+                self.spawn_ground(coord, height);
+            }
+            Spawn::Floor => {
+                self.spawn_floor(coord, height);
+            }
+            Spawn::Trail => {
+                self.spawn_trail(coord, height);
+            }
+            Spawn::Stream => {
+                self.spawn_stream(coord, height);
+            }
+            Spawn::Ford => {
+                self.spawn_stream(coord, height);
+                self.make_ford(coord);
+            }
+            Spawn::Bridge => {
+                self.spawn_stream(coord, height);
+                self.build_bridge(coord);
+            }
+            Spawn::RuinsFloor => {
+                self.spawn_ruins_floor(coord, height);
+            }
+            Spawn::PierFloor => {
+                self.spawn_pier_floor(coord, height);
+            }
+            Spawn::EndOfPier => {
+                let floor = self.spawn_pier_floor(coord, height);
+                self.components.end_of_pier.insert(floor, ());
+            }
+            Spawn::Water => {
+                self.spawn_water(coord, rng);
+            }
+            Spawn::Lake => {
+                self.spawn_lake_water(coord, rng);
+            }
+            Spawn::Wall => {
+                self.spawn_wall(coord);
+            }
+            Spawn::RuinsWall => {
+                self.spawn_ruins_wall(coord);
+            }
+            Spawn::Tree => {
+                self.spawn_tree(coord, rng);
+            }
+            Spawn::Door => {
+                self.spawn_door(coord, Axis::X);
+            }
+            Spawn::Window => {
+                self.spawn_window(coord, Axis::X);
+            }
+            Spawn::Light => {
+                self.spawn_light(coord);
+            }
+            Spawn::Altar => {
+                self.spawn_altar(coord);
+            }
+            Spawn::BulletinBoard => {
+                self.spawn_bulletin_board(coord);
+            }
+            Spawn::Lamp => {
+                self.spawn_lamp(coord);
+            }
+            Spawn::TowerLeg => {
+                self.spawn_tower_leg(coord);
+            }
+            Spawn::Lookout => {
+                self.spawn_lookout(coord, height);
+            }
+            Spawn::Grass => {
+                self.spawn_grass(coord);
+            }
+            Spawn::Rock => {
+                self.spawn_rock(coord);
+            }
+            Spawn::Flower => {
+                self.spawn_flower(coord);
+            }
+            Spawn::Tea => {
+                self.spawn_tea(coord);
+            }
+            Spawn::FlowerPatch | Spawn::TeaPatch => {
+                let item = if spawn == Spawn::FlowerPatch {
+                    Item::Flower
+                } else {
+                    Item::Tea
+                };
+                if let Some(floor) = self.spatial_table.layers_at_checked(coord).floor {
+                    self.components.forage_patch.insert(floor, item);
+                }
+            }
+            Spawn::Mushroom => {
+                self.spawn_mushroom(coord, forage::MUSHROOM_LIFETIME.choose(rng));
+            }
+            Spawn::Signpost => {
+                self.spawn_signpost(coord, "A weathered signpost.".to_string());
+            }
+            Spawn::Bed => {
+                self.spawn_bed(coord);
+            }
+            Spawn::ChairLeft => {
+                self.spawn_chair_left_facing(coord);
+            }
+            Spawn::ChairRight => {
+                self.spawn_chair_right_facing(coord);
+            }
+            Spawn::Stove => {
+                self.spawn_stove(coord);
+            }
+            Spawn::KitchenBench => {
+                self.spawn_kitchen_bench(coord);
+            }
+            Spawn::Table => {
+                self.spawn_table(coord);
+            }
+            Spawn::Bookshelf => {
+                self.spawn_bookshelf(coord);
+            }
+            Spawn::Ladder => {
+                self.spawn_ladder(coord);
+            }
+            Spawn::Teapot => {
+                self.spawn_teapot(coord);
+            }
+            Spawn::LogX => {
+                self.spawn_log(coord, Axis::X);
+            }
+            Spawn::LogY => {
+                self.spawn_log(coord, Axis::Y);
+            }
+            Spawn::Boat => {
+                self.spawn_boat(coord);
+            }
+            Spawn::Cairn => {
+                self.spawn_cairn(coord);
+            }
+            Spawn::Gumboots => {
+                self.spawn_gumboots(coord);
+            }
+            Spawn::Umbrella => {
+                self.spawn_umbrella(coord);
+            }
+            Spawn::Shovel => {
+                self.spawn_shovel(coord);
+            }
+            Spawn::Map => {
+                self.spawn_map(coord);
+            }
+            Spawn::WeatherReport => {
+                self.spawn_weather_report(coord);
+            }
+            Spawn::Lantern => {
+                self.spawn_lantern(coord);
+            }
+            Spawn::Crowbar => {
+                self.spawn_crowbar(coord);
+            }
+            Spawn::Axe => {
+                self.spawn_axe(coord);
+            }
+            Spawn::FishingRod => {
+                self.spawn_fishing_rod(coord);
+            }
+            Spawn::Firewood => {
+                self.spawn_firewood(coord);
+            }
+            Spawn::Clear => (),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Any cell that satisfies the constraints
+    Random,
+    /// The cell with the greatest height that satisfies the constraints
+    Highest,
+}
+
+/// Rules about where a prefab may be placed. Heights and distances are measured from the centre
+/// of the prefab.
+#[derive(Debug, Clone, Copy)]
+pub struct Constraints {
+    pub placement: Placement,
+    pub min_height: f64,
+    pub min_cabin_distance: u32,
+    /// Minimum distance from any prefab that has already been placed
+    pub min_separation: u32,
+    /// Minimum distance from the edge of the map
+    pub edge_padding: i32,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self {
+            placement: Placement::Random,
+            min_height: 0.,
+            min_cabin_distance: 0,
+            min_separation: 0,
+            edge_padding: 0,
+        }
+    }
+}
+
+/// A rotation by a multiple of 90 degrees, optionally preceded by a mirroring in the x axis
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Transform {
+    pub rotations: u32,
+    pub mirror: bool,
+}
+
+impl Transform {
+    pub fn apply(&self, offset: Coord) -> Coord {
+        let mut offset = if self.mirror {
+            Coord::new(-offset.x, offset.y)
+        } else {
+            offset
+        };
+        for _ in 0..self.rotations {
+            offset = offset.left90();
+        }
+        offset
+    }
+}
+
+/// A structure described by a text file. See the files in the prefabs directory for the format.
+#[derive(Debug, Clone)]
+pub struct Prefab {
+    pub constraints: Constraints,
+    rotate: bool,
+    mirror: bool,
+    size: Size,
+    legend: BTreeMap<char, Vec<Spawn>>,
+    anchors: BTreeMap<char, String>,
+    rows: Vec<String>,
+}
+
+impl Prefab {
+    /// Parses a prefab file. Prefabs that don't specify their own edge padding get the padding
+    /// from `config`, and numbers can be given as the name of a terrain config parameter.
+    pub fn parse(text: &str, config: &TerrainConfig) -> Result<Self, String> {
+        let mut constraints = Constraints {
+            edge_padding: config.padding,
+            ..Default::default()
+        };
+        let mut rotate = false;
+        let mut mirror = false;
+        let mut legend = BTreeMap::new();
+        let mut anchors = BTreeMap::new();
+        let mut lines = text.split('\n');
+        for line in lines.by_ref() {
+            if line.is_empty() || line.starts_with("# ") {
+                continue;
+            }
+            let mut words = line.split(' ');
+            let keyword = words.next().unwrap_or("");
+            let args = words.collect::<Vec<_>>();
+            let number = |args: &[&str]| -> Result<f64, String> {
+                args.first()
+                    .and_then(|arg| arg.parse().ok().or_else(|| config.parameter(arg)))
+                    .ok_or_else(|| format!("expected a number after \"{}\"", keyword))
+            };
+            let character = |args: &[&str]| -> Result<char, String> {
+                let mut chars = args.first().map(|arg| arg.chars()).into_iter().flatten();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => Ok(ch),
+                    _ => Err(format!("expected a character after \"{}\"", keyword)),
+                }
+            };
+            match keyword {
+                "map" => break,
+                "rotate" => rotate = true,
+                "mirror" => mirror = true,
+                "placement" => {
+                    constraints.placement = match args.first() {
+                        Some(&"random") => Placement::Random,
+                        Some(&"highest") => Placement::Highest,
+                        _ => return Err(format!("unknown placement in \"{}\"", line)),
+                    }
+                }
+                "min_height" => constraints.min_height = number(&args)?,
+                "min_cabin_distance" => constraints.min_cabin_distance = number(&args)? as u32,
+                "min_separation" => constraints.min_separation = number(&args)? as u32,
+                "edge_padding" => constraints.edge_padding = number(&args)? as i32,
+                "legend" => {
+                    let ch = character(&args)?;
+                    let spawns = args[1..]
+                        .iter()
+                        .map(|&name| {
+                            Spawn::from_name(name).ok_or_else(|| format!("unknown spawn {}", name))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    legend.insert(ch, spawns);
+                }
+                "anchor" => {
+                    let ch = character(&args)?;
+                    let anchor_name = args
+                        .get(1)
+                        .ok_or_else(|| format!("anchor {} has no name", ch))?;
+                    anchors.insert(ch, anchor_name.to_string());
+                }
+                other => return Err(format!("unknown keyword \"{}\"", other)),
+            }
+        }
+        let mut rows = lines.map(|row| row.to_string()).collect::<Vec<_>>();
+        while rows.last().map(|row| row.is_empty()).unwrap_or(false) {
+            rows.pop();
+        }
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        if width == 0 {
+            return Err("empty map".to_string());
+        }
+        for row in &rows {
+            for ch in row.chars() {
+                if ch != ' ' && !legend.contains_key(&ch) {
+                    return Err(format!("unexpected char {}", ch));
+                }
+            }
+        }
+        for ch in anchors.keys() {
+            if !legend.contains_key(ch) {
+                return Err(format!("anchor {} isn't in the legend", ch));
+            }
+        }
+        Ok(Self {
+            constraints,
+            rotate,
+            mirror,
+            size: Size::new_u16(width as u16, rows.len() as u16),
+            legend,
+            anchors,
+            rows,
+        })
+    }
+
+    /// Picks one of the orientations this prefab is allowed to be placed in
+    pub fn choose_transform<R: Rng>(&self, rng: &mut R) -> Transform {
+        Transform {
+            rotations: if self.rotate { rng.gen_range(0..4) } else { 0 },
+            mirror: self.mirror && rng.gen(),
+        }
+    }
+
+    /// The non-empty cells of the prefab, relative to its centre once transformed, along with the
+    /// things to spawn in each
+    pub fn cells(&self, transform: Transform) -> Vec<(Coord, &[Spawn])> {
+        self.chars(transform)
+            .map(|(offset, ch)| (offset, self.legend[&ch].as_slice()))
+            .collect()
+    }
+
+    /// The names of the anchors in the prefab, relative to its centre once transformed
+    pub fn anchors(&self, transform: Transform) -> BTreeMap<String, Coord> {
+        let mut anchors = BTreeMap::new();
+        for (offset, ch) in self.chars(transform) {
+            if let Some(name) = self.anchors.get(&ch) {
+                anchors.entry(name.clone()).or_insert(offset);
+            }
+        }
+        anchors
+    }
+
+    fn chars(&self, transform: Transform) -> impl '_ + Iterator<Item = (Coord, char)> {
+        let centre = self.size.to_coord().unwrap() / 2;
+        self.rows.iter().enumerate().flat_map(move |(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|&(_, ch)| ch != ' ')
+                .map(move |(x, ch)| (transform.apply(Coord::new(x as i32, y as i32) - centre), ch))
+        })
+    }
+}
+
+/// All the prefabs that can appear in the world, by name
+pub struct PrefabLibrary {
+    prefabs: BTreeMap<&'static str, Prefab>,
+}
+
+/// The text of each prefab, by name
+const SOURCES: &[(&str, &str)] = &[
+    ("ruins", include_str!("prefabs/ruins.txt")),
+    ("lamp", include_str!("prefabs/lamp.txt")),
+    ("lookout", include_str!("prefabs/lookout.txt")),
+];
+
+impl PrefabLibrary {
+    pub fn new(config: &TerrainConfig) -> Self {
+        let prefabs = SOURCES
+            .iter()
+            .map(|&(name, text)| {
+                let prefab = Prefab::parse(text, config)
+                    .unwrap_or_else(|message| panic!("invalid prefab {}: {}", name, message));
+                (name, prefab)
+            })
+            .collect();
+        Self { prefabs }
+    }
+
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        SOURCES.iter().map(|&(name, _)| name)
+    }
+
+    pub fn get(&self, name: &str) -> &Prefab {
+        self.prefabs
+            .get(name)
+            .unwrap_or_else(|| panic!("no such prefab: {}", name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spawn_names_round_trip() {
+        for &spawn in Spawn::ALL {
+            assert_eq!(Spawn::from_name(spawn.name()), Some(spawn));
+        }
+        assert_eq!(Spawn::from_name("dragon"), None);
+    }
+
+    #[test]
+    fn library_prefabs_parse() {
+        let config = TerrainConfig::default();
+        let library = PrefabLibrary::new(&config);
+        for name in PrefabLibrary::names() {
+            assert!(!library.get(name).cells(Transform::default()).is_empty());
+        }
+        let ruins = &library.get("ruins").constraints;
+        assert_eq!(ruins.min_height, config.high_ground);
+        assert_eq!(ruins.min_cabin_distance, config.feature_distance);
+    }
+
+    #[test]
+    fn parse_prefab() {
+        let config = TerrainConfig::default();
+        let text = "\
+placement highest
+min_height high_ground
+min_separation 5
+legend # ruins_floor ruins_wall
+legend a ruins_floor altar
+anchor a altar
+map
+###
+#a
+###
+";
+        let prefab = Prefab::parse(text, &config).unwrap();
+        assert_eq!(prefab.constraints.placement, Placement::Highest);
+        assert_eq!(prefab.constraints.min_height, config.high_ground);
+        assert_eq!(prefab.constraints.min_separation, 5);
+        assert_eq!(prefab.constraints.edge_padding, config.padding);
+        assert_eq!(prefab.size, Size::new(3, 3));
+        assert_eq!(prefab.cells(Transform::default()).len(), 8);
+        assert_eq!(
+            prefab.anchors(Transform::default()).get("altar"),
+            Some(&Coord::new(0, 0))
+        );
+    }
+
+    #[test]
+    fn reject_bad_prefabs() {
+        let reject = |text: &str| Prefab::parse(text, &TerrainConfig::default()).unwrap_err();
+        assert_eq!(reject("map\n.x\n"), "unexpected char .");
+        assert_eq!(reject("legend x dragon\nmap\nx\n"), "unknown spawn dragon");
+        assert_eq!(
+            reject("legend x rock\nanchor y top\nmap\nx\n"),
+            "anchor y isn't in the legend"
+        );
+        assert_eq!(
+            reject("min_height lofty\nmap\n"),
+            "expected a number after \"min_height\""
+        );
+        assert_eq!(
+            reject("legend xy rock\nmap\n"),
+            "expected a character after \"legend\""
+        );
+        assert_eq!(reject("spin\nmap\n"), "unknown keyword \"spin\"");
+        assert_eq!(reject("legend x rock\nmap\n\n"), "empty map");
+    }
+}
@@ -0,0 +1,175 @@
+use crate::world::World;
+use gridbugs::{
+    direction::CardinalDirection,
+    grid_2d::{Coord, Grid},
+};
+use std::collections::VecDeque;
+
+impl World {
+    /// True if the player could walk onto the cell in a freshly generated world. Closed doors
+    /// count as passable since the player can open them, as do fords since the streams start out
+    /// low.
+    pub fn is_passable_on_foot(&self, coord: Coord) -> bool {
+        let layers = match self.spatial_table.layers_at(coord) {
+            Some(layers) => layers,
+            None => return false,
+        };
+        let walkable_floor = layers
+            .floor
+            .map(|floor| !self.components.lake.contains(floor))
+            .unwrap_or(false);
+        let blocked = layers
+            .feature
+            .map(|feature| {
+                self.components.solid.contains(feature)
+                    && !self.components.door_state.contains(feature)
+            })
+            .unwrap_or(false);
+        walkable_floor && !blocked && self.can_cross_stream(coord)
+    }
+
+    /// True if the cell would be passable on foot if not for a tree standing in it
+    pub fn is_tree_on_dry_land(&self, coord: Coord) -> bool {
+        let layers = match self.spatial_table.layers_at(coord) {
+            Some(layers) => layers,
+            None => return false,
+        };
+        let dry_floor = layers
+            .floor
+            .map(|floor| !self.components.lake.contains(floor))
+            .unwrap_or(false);
+        let tree = layers
+            .feature
+            .map(|feature| self.components.tree.contains(feature))
+            .unwrap_or(false);
+        dry_floor && tree && self.can_cross_stream(coord)
+    }
+
+    /// Flood fills outwards from `start` through cells that are passable on foot
+    pub fn reachable_from(&self, start: Coord) -> Grid<bool> {
+        let mut reached = Grid::new_copy(self.size(), false);
+        let mut queue = VecDeque::new();
+        if let Some(cell) = reached.get_mut(start) {
+            *cell = true;
+            queue.push_back(start);
+        }
+        while let Some(coord) = queue.pop_front() {
+            for d in CardinalDirection::all() {
+                let neighbour = coord + d.coord();
+                if !self.is_passable_on_foot(neighbour) {
+                    continue;
+                }
+                let cell = reached.get_checked_mut(neighbour);
+                if !*cell {
+                    *cell = true;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Removes any trees standing on the given cells, returning the number removed
+    pub fn clear_trees(&mut self, coords: &[Coord]) -> usize {
+        let mut num_cleared = 0;
+        for &coord in coords {
+            let tree = self
+                .spatial_table
+                .layers_at(coord)
+                .and_then(|layers| layers.feature)
+                .filter(|&feature| self.components.tree.contains(feature));
+            if let Some(entity) = tree {
+                self.spatial_table.remove(entity);
+                self.components.remove_entity(entity);
+                num_cleared += 1;
+            }
+        }
+        num_cleared
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        terrain::{ensure_reachable, GenerationError, Landmark},
+        world::World,
+    };
+    use gridbugs::grid_2d::{coord_2d::Axis, Coord, Grid, Size};
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    /// Builds a world from rows of cells, where '.' is ground, 'T' is a tree, '~' is the lake,
+    /// '#' is a wall and '+' is a closed door
+    fn world_from_rows(rows: &[&str]) -> World {
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        let size = Size::new(rows[0].len() as u32, rows.len() as u32);
+        let mut world = World::new(size);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, ch) in row.chars().enumerate() {
+                let coord = Coord::new(x as i32, y as i32);
+                if ch == '~' {
+                    world.spawn_lake_water(coord, &mut rng);
+                    continue;
+                }
+                world.spawn_ground(coord, 0.5);
+                match ch {
+                    'T' => {
+                        world.spawn_tree(coord, &mut rng);
+                    }
+                    '#' => {
+                        world.spawn_wall(coord);
+                    }
+                    '+' => {
+                        world.spawn_door(coord, Axis::Y);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        world
+    }
+
+    const START: Coord = Coord::new(0, 1);
+    const TARGET: Coord = Coord::new(4, 1);
+
+    fn ensure_target_reachable(world: &mut World) -> Result<usize, GenerationError> {
+        let topography_grid = Grid::new_copy(world.size(), 0.5);
+        ensure_reachable(
+            world,
+            &topography_grid,
+            START,
+            &[(Landmark::CabinDoor, TARGET)],
+        )
+    }
+
+    #[test]
+    fn closed_doors_are_passable() {
+        let mut world = world_from_rows(&["..#..", "..+..", "..#.."]);
+        assert!(world.is_passable_on_foot(Coord::new(2, 1)));
+        assert!(!world.is_passable_on_foot(Coord::new(2, 0)));
+        assert!(*world.reachable_from(START).get_checked(TARGET));
+        assert_eq!(ensure_target_reachable(&mut world), Ok(0));
+    }
+
+    #[test]
+    fn trees_in_the_way_are_cleared() {
+        let mut world = world_from_rows(&["..T..", "..T..", "..T.."]);
+        assert!(!*world.reachable_from(START).get_checked(TARGET));
+        assert_eq!(ensure_target_reachable(&mut world), Ok(1));
+        assert!(*world.reachable_from(START).get_checked(TARGET));
+        // only the one tree on the path is cut down
+        let trees_left = (0..3)
+            .filter(|&y| world.is_tree_on_dry_land(Coord::new(2, y)))
+            .count();
+        assert_eq!(trees_left, 2);
+    }
+
+    #[test]
+    fn targets_across_the_lake_are_unreachable() {
+        let mut world = world_from_rows(&["..~..", "..~..", "..~.."]);
+        assert_eq!(
+            ensure_target_reachable(&mut world),
+            Err(GenerationError::Unreachable(Landmark::CabinDoor))
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     components::{DoorState, Equipment, Item, Tile},
+    level::Level,
     realtime::flicker,
     spatial::{Layer, Location},
     visibility::Light,
@@ -49,6 +50,63 @@ impl World {
         entity
     }
 
+    pub fn spawn_trail(&mut self, coord: Coord, height: f64) -> Entity {
+        let entity = self.spawn_ground(coord, height);
+        self.components.tile.insert(entity, Tile::Trail);
+        self.components.trail.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_stream(&mut self, coord: Coord, height: f64) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Floor),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Stream);
+        self.components.height.insert(entity, height);
+        self.components.stream.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_lookout(&mut self, coord: Coord, height: f64) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Floor),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Lookout);
+        self.components.height.insert(entity, height);
+        self.components.lookout.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_tower_leg(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::TowerLeg);
+        self.components.solid.insert(entity, ());
+        entity
+    }
+
     pub fn spawn_wall(&mut self, coord: Coord) -> Entity {
         let entity = self.entity_allocator.alloc();
         self.spatial_table
@@ -354,6 +412,40 @@ impl World {
         entity
     }
 
+    pub fn spawn_mushroom(&mut self, coord: Coord, lifetime: u32) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Item),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Mushroom);
+        self.components.item.insert(entity, Item::Mushroom);
+        self.components.mushroom.insert(entity, lifetime);
+        entity
+    }
+
+    pub fn spawn_signpost(&mut self, coord: Coord, text: String) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Signpost);
+        self.components.signpost.insert(entity, text);
+        self.components.solid.insert(entity, ());
+        entity
+    }
+
     pub fn spawn_bed(&mut self, coord: Coord) -> Entity {
         let entity = self.entity_allocator.alloc();
         self.spatial_table
@@ -404,6 +496,156 @@ impl World {
         entity
     }
 
+    pub fn spawn_stove(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Stove);
+        self.components.stove.insert(entity, ());
+        self.components.solid.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_kitchen_bench(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::KitchenBench);
+        self.components.kitchen_bench.insert(entity, ());
+        self.components.solid.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_table(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Table);
+        self.components.solid.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_bookshelf(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Bookshelf);
+        self.components.solid.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_ladder(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Ladder);
+        self.components.solid.insert(entity, ());
+        self.components.ladder.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_hatch(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Hatch);
+        self.components.solid.insert(entity, ());
+        self.components.stairs.insert(entity, Level::Ground);
+        entity
+    }
+
+    pub fn spawn_stairs_down(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::StairsDown);
+        self.components.solid.insert(entity, ());
+        self.components.stairs.insert(entity, Level::Cellar);
+        entity
+    }
+
+    pub fn spawn_stairs_up(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::StairsUp);
+        self.components.solid.insert(entity, ());
+        self.components.stairs.insert(entity, Level::Ground);
+        entity
+    }
+
+    pub fn spawn_cellar_floor(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Floor),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Floor);
+        self.components.cellar.insert(entity, ());
+        entity
+    }
+
     pub fn spawn_teapot(&mut self, coord: Coord) -> Entity {
         let entity = self.entity_allocator.alloc();
         self.spatial_table
@@ -564,4 +806,104 @@ impl World {
         self.components.equipment.insert(entity, Equipment::Crowbar);
         entity
     }
+
+    pub fn spawn_log(&mut self, coord: Coord, axis: Axis) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Log(axis));
+        self.components.solid.insert(entity, ());
+        self.components.fallen_log.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_axe(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Item),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Axe);
+        self.components.equipment.insert(entity, Equipment::Axe);
+        entity
+    }
+
+    pub fn spawn_fishing_rod(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Item),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::FishingRod);
+        self.components
+            .equipment
+            .insert(entity, Equipment::FishingRod);
+        entity
+    }
+
+    pub fn spawn_firewood(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Item),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Firewood);
+        self.components.item.insert(entity, Item::Firewood);
+        entity
+    }
+
+    pub fn spawn_boat(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Boat);
+        self.components.boat.insert(entity, ());
+        entity
+    }
+
+    pub fn spawn_cairn(&mut self, coord: Coord) -> Entity {
+        let entity = self.entity_allocator.alloc();
+        self.spatial_table
+            .update(
+                entity,
+                Location {
+                    coord,
+                    layer: Some(Layer::Feature),
+                },
+            )
+            .unwrap();
+        self.components.tile.insert(entity, Tile::Cairn);
+        self.components.solid.insert(entity, ());
+        self.components.cairn.insert(entity, ());
+        entity
+    }
 }
@@ -0,0 +1,96 @@
+use gridbugs::{coord_2d::Coord, rgb_int::Rgb24};
+use rational::Rational;
+use serde::{Deserialize, Serialize};
+
+/// Chance of a lightning strike during each turn of heavy rain
+pub const STRIKE_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 40,
+};
+
+/// Chance that a given lightning strike hits a tree, felling it
+pub const TREE_STRIKE_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 4,
+};
+
+/// Chance of a tree being blown over during each turn of a storm (heavy rain and gale)
+pub const TREE_FALL_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 60,
+};
+
+pub const MEDIUM_RAIN_GALE_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 6,
+};
+
+pub const HEAVY_RAIN_GALE_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 2,
+};
+
+pub const FLASH_COLOUR: Rgb24 = Rgb24::new(200, 200, 255);
+pub const FLASH_VISION_DISTANCE_SQUARED: u32 = 1600;
+const FLASH_FRAMES: u32 = 30;
+
+/// Sound travels much slower than light. This is the delay in frames per cell of distance between
+/// the strike and the player before the thunder is heard.
+const THUNDER_FRAMES_PER_CELL: u32 = 3;
+const NEAR_THUNDER_DISTANCE: u32 = 15;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Thunder {
+    Near,
+    Distant,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LightningStrike {
+    pub coord: Coord,
+    age_frames: u32,
+    thunder_delay_frames: u32,
+    distance: u32,
+}
+
+impl LightningStrike {
+    pub fn new(coord: Coord, player_coord: Coord) -> Self {
+        let distance = ((coord - player_coord).magnitude2() as f64).sqrt() as u32;
+        Self {
+            coord,
+            age_frames: 0,
+            thunder_delay_frames: 1 + distance * THUNDER_FRAMES_PER_CELL,
+            distance,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        self.age_frames += 1;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.age_frames > FLASH_FRAMES && self.age_frames > self.thunder_delay_frames
+    }
+
+    /// 255 at the moment of the strike, fading to 0 over a short time
+    pub fn flash_intensity(&self) -> u8 {
+        if self.age_frames >= FLASH_FRAMES {
+            0
+        } else {
+            (((FLASH_FRAMES - self.age_frames) * 255) / FLASH_FRAMES) as u8
+        }
+    }
+
+    /// Returns the thunder on the single frame on which it reaches the player
+    pub fn thunder(&self) -> Option<Thunder> {
+        if self.age_frames == self.thunder_delay_frames {
+            if self.distance < NEAR_THUNDER_DISTANCE {
+                Some(Thunder::Near)
+            } else {
+                Some(Thunder::Distant)
+            }
+        } else {
+            None
+        }
+    }
+}
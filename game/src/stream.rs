@@ -0,0 +1,89 @@
+use crate::{components::Tile, world::World};
+use gridbugs::{direction::CardinalDirection, entity_table::Entity, grid_2d::Coord};
+use std::collections::{HashSet, VecDeque};
+
+/// Number of flooded cells that each cell of ditch connected to a stream carries away
+pub const DITCH_DRAINAGE: usize = 4;
+
+impl World {
+    fn stream_floor(&self, coord: Coord) -> Option<Entity> {
+        self.spatial_table
+            .layers_at(coord)?
+            .floor
+            .filter(|&floor| self.components.stream.contains(floor))
+    }
+
+    pub fn is_stream(&self, coord: Coord) -> bool {
+        self.stream_floor(coord).is_some()
+    }
+
+    pub fn is_ford(&self, coord: Coord) -> bool {
+        self.stream_floor(coord)
+            .map(|floor| self.components.ford.contains(floor))
+            .unwrap_or(false)
+    }
+
+    /// False if the coordinate is part of a stream with no bridge, and isn't a ford that's
+    /// currently shallow enough to wade across
+    pub fn can_cross_stream(&self, coord: Coord) -> bool {
+        match self.stream_floor(coord) {
+            None => true,
+            Some(floor) => {
+                self.components.bridge.contains(floor)
+                    || self.components.tile.get(floor) == Some(&Tile::Ford)
+            }
+        }
+    }
+
+    pub fn build_bridge(&mut self, coord: Coord) {
+        if let Some(floor) = self.stream_floor(coord) {
+            self.components.ford.remove(floor);
+            self.components.bridge.insert(floor, ());
+            self.components.tile.insert(floor, Tile::Bridge);
+        }
+    }
+
+    pub fn make_ford(&mut self, coord: Coord) {
+        if let Some(floor) = self.stream_floor(coord) {
+            if !self.components.bridge.contains(floor) {
+                self.components.ford.insert(floor, ());
+                self.components.tile.insert(floor, Tile::Ford);
+            }
+        }
+    }
+
+    /// Heavy rain swells the streams, covering the stones of the fords
+    pub fn set_fords_swollen(&mut self, swollen: bool) {
+        let tile = if swollen { Tile::Stream } else { Tile::Ford };
+        for ford in self.components.ford.entities().collect::<Vec<_>>() {
+            self.components.tile.insert(ford, tile);
+        }
+    }
+
+    /// Ditch cells joined to a stream by an unbroken line of ditch. Water collected by these
+    /// ditches runs off into the stream rather than pooling.
+    pub fn drained_ditches(&self) -> HashSet<Entity> {
+        let mut drained = HashSet::new();
+        let mut queue = VecDeque::new();
+        for stream in self.components.stream.entities() {
+            if let Some(coord) = self.spatial_table.coord_of(stream) {
+                queue.push_back(coord);
+            }
+        }
+        while let Some(coord) = queue.pop_front() {
+            for d in CardinalDirection::all() {
+                let neighbour = coord + d.coord();
+                if let Some(floor) = self
+                    .spatial_table
+                    .layers_at(neighbour)
+                    .and_then(|layers| layers.floor)
+                {
+                    if self.components.ditch.contains(floor) && drained.insert(floor) {
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+        drained
+    }
+}
@@ -1,10 +1,14 @@
 use crate::{
-    components::{EntityData, Equipment},
+    cabin,
+    components::{EntityData, Equipment, Item, Tile},
+    forage,
+    prefab::{Placement, Prefab, PrefabLibrary},
     spatial::{Layer, Location},
+    trail,
     world::World,
 };
 use gridbugs::{
-    direction::{self, CardinalDirection},
+    direction::CardinalDirection,
     entity_table::Entity,
     grid_2d::{Coord, Grid, Size},
 };
@@ -13,130 +17,329 @@ use rand::{
     seq::{IteratorRandom, SliceRandom},
     Rng,
 };
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
 
 pub struct Terrain {
     pub world: World,
     pub player: Entity,
     pub cabin_direction: CardinalDirection,
+    pub stats: GenerationStats,
 }
 
-#[allow(dead_code)]
-pub fn from_str<R: Rng>(s: &str, player_data: EntityData, rng: &mut R) -> Terrain {
-    let rows = s.split('\n').filter(|s| !s.is_empty()).collect::<Vec<_>>();
-    let size = Size::new_u16(rows[0].len() as u16, rows.len() as u16);
-    let mut world = World::new(size);
-    let mut player_data = Some(player_data);
-    let mut player = None;
-    let mut prev_wall = false;
-    for (y, row) in rows.iter().enumerate() {
-        for (x, ch) in row.chars().enumerate() {
-            if ch.is_control() {
-                continue;
+/// Something that world generation tries to place
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Landmark {
+    Cabin,
+    CabinDoor,
+    Window,
+    Bed,
+    Cellar,
+    Chair,
+    Teapot,
+    Table,
+    Bookshelf,
+    Kitchen,
+    Player,
+    Prefab(&'static str),
+    Pier,
+    FlowerPatch,
+    TeaPatch,
+    Spring,
+    Streams,
+    Rocks,
+    Equipment,
+    Boat,
+    Island,
+}
+
+impl fmt::Display for Landmark {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Cabin => write!(f, "the cabin"),
+            Self::CabinDoor => write!(f, "the cabin door"),
+            Self::Window => write!(f, "a window"),
+            Self::Bed => write!(f, "the bed"),
+            Self::Cellar => write!(f, "the cellar stairs"),
+            Self::Chair => write!(f, "the chair"),
+            Self::Teapot => write!(f, "the teapot"),
+            Self::Table => write!(f, "the table"),
+            Self::Bookshelf => write!(f, "the bookshelf"),
+            Self::Kitchen => write!(f, "the kitchen"),
+            Self::Player => write!(f, "the player"),
+            Self::Prefab(name) => write!(f, "the {}", name),
+            Self::Pier => write!(f, "the pier"),
+            Self::FlowerPatch => write!(f, "the flower meadow"),
+            Self::TeaPatch => write!(f, "the tea garden"),
+            Self::Spring => write!(f, "a spring"),
+            Self::Streams => write!(f, "the streams"),
+            Self::Rocks => write!(f, "the rocks"),
+            Self::Equipment => write!(f, "the equipment"),
+            Self::Boat => write!(f, "the boat"),
+            Self::Island => write!(f, "the island"),
+        }
+    }
+}
+
+/// Why an attempt at generating a world was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GenerationError {
+    /// No cell satisfies the rules for where the landmark can go
+    NoSite(Landmark),
+    /// There are fewer free cells than the landmark needs
+    NoRoom(Landmark),
+    /// The landmark would extend past the edge of the map
+    OffMap(Landmark),
+    /// The landmark runs into something, or blocks something else
+    Blocked(Landmark),
+    /// The landmark would be under the lake
+    Flooded(Landmark),
+    /// A prefab has no anchor with this name
+    MissingAnchor(&'static str),
+    /// No trail can be laid from the cabin to the named destination
+    NoRoute(&'static str),
+    /// The player can't walk to the landmark, even after clearing trees out of the way
+    Unreachable(Landmark),
+}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoSite(landmark) => write!(f, "nowhere to put {}", landmark),
+            Self::NoRoom(landmark) => write!(f, "no room for {}", landmark),
+            Self::OffMap(landmark) => write!(f, "{} is off the edge of the map", landmark),
+            Self::Blocked(landmark) => write!(f, "{} is blocked", landmark),
+            Self::Flooded(landmark) => write!(f, "{} is in the lake", landmark),
+            Self::MissingAnchor(name) => write!(f, "missing anchor \"{}\" in prefab", name),
+            Self::NoRoute(name) => write!(f, "no route for the trail to \"{}\"", name),
+            Self::Unreachable(landmark) => write!(f, "the player can't reach {}", landmark),
+        }
+    }
+}
+
+/// A record of what it took to generate a world
+#[derive(Debug, Clone, Default)]
+pub struct GenerationStats {
+    /// Number of attempts, including the one that succeeded
+    pub attempts: u32,
+    /// How many attempts were rejected for each reason
+    pub rejections: BTreeMap<GenerationError, u32>,
+    /// Trees cut down to clear a way to things the player couldn't otherwise reach
+    pub trees_cleared: usize,
+}
+
+impl GenerationStats {
+    fn fmt_rejections(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (error, count) in &self.rejections {
+            write!(f, "\n  rejected {} time(s): {}", count, error)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for GenerationStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "generated terrain in {} attempt(s), clearing {} tree(s)",
+            self.attempts, self.trees_cleared
+        )?;
+        self.fmt_rejections(f)
+    }
+}
+
+/// Every attempt at generating a world was rejected
+#[derive(Debug, Clone)]
+pub struct GenerationFailed {
+    pub stats: GenerationStats,
+}
+
+impl fmt::Display for GenerationFailed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "gave up generating terrain after {} attempt(s)",
+            self.stats.attempts
+        )?;
+        self.stats.fmt_rejections(f)
+    }
+}
+
+/// Parameters for world generation. Missing fields take their default values when deserializing,
+/// so a config file only needs to mention the parameters it changes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TerrainConfig {
+    pub width: u32,
+    pub height: u32,
+    /// Minimum distance from the edge of the map to the cabin, the flower and tea patches, and
+    /// any prefabs that don't specify their own padding
+    pub padding: i32,
+    /// Scale of the height noise. Smaller values give broader hills.
+    pub topography_spread: f64,
+    /// Cells higher than this count as high ground, where the cabin and patches are placed
+    pub high_ground: f64,
+    /// Minimum distance between the cabin, the ruins and the flower and tea patches
+    pub feature_distance: u32,
+    /// Scale of the tree density noise
+    pub tree_chance_spread: f64,
+    pub tree_chance_scale: f64,
+    /// Subtracted from the tree density noise before scaling, so the sparsest areas are clearings
+    pub tree_chance_offset: f64,
+    /// Cells where the tree density noise is below this tend to be grassy
+    pub grass_threshold: f64,
+    /// Chance of grass growing anywhere that isn't a tree
+    pub grass_chance: f64,
+    pub num_rocks: usize,
+    pub num_extra_lamps: usize,
+    pub num_streams: usize,
+    pub flower_patch_radius: u32,
+    pub tea_patch_radius: u32,
+    /// Distance from the middle of the lake to the edge of the map on the lake side
+    pub lake_depth: i32,
+    pub pier_length: i32,
+    /// Minimum distance between the cabin and any equipment, and between pieces of equipment
+    pub equipment_cabin_distance: u32,
+    pub equipment_spread: u32,
+    /// How many pieces of equipment to hide around the map, taken in order from `EQUIPMENT`
+    pub num_equipment: usize,
+}
+
+impl Default for TerrainConfig {
+    fn default() -> Self {
+        Self {
+            width: 120,
+            height: 120,
+            padding: 30,
+            topography_spread: 0.05,
+            high_ground: 0.65,
+            feature_distance: 20,
+            tree_chance_spread: 0.05,
+            tree_chance_scale: 0.3,
+            tree_chance_offset: 0.2,
+            grass_threshold: 0.4,
+            grass_chance: 0.1,
+            num_rocks: 200,
+            num_extra_lamps: 3,
+            num_streams: 3,
+            flower_patch_radius: 5,
+            tea_patch_radius: 5,
+            lake_depth: 24,
+            pier_length: 8,
+            equipment_cabin_distance: 50,
+            equipment_spread: 20,
+            num_equipment: EQUIPMENT.len(),
+        }
+    }
+}
+
+/// Number of cells within a forage patch of the given radius
+fn patch_area(radius: u32) -> usize {
+    Size::new(radius * 2, radius * 2)
+        .coord_iter_row_major()
+        .filter(|&offset| {
+            let rel_coord = offset - Coord::new(radius as i32, radius as i32);
+            rel_coord.magnitude2() < radius * radius
+        })
+        .count()
+}
+
+impl TerrainConfig {
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    /// Checks that a world can be generated with these parameters at all, describing the first
+    /// one that's out of range
+    pub fn validate(&self) -> Result<(), String> {
+        if self.padding < 0 {
+            return Err("padding can't be negative".to_string());
+        }
+        let min_size = 2 * self.padding as u32 + cabin::SIZE;
+        if self.width < min_size || self.height < min_size {
+            return Err(format!(
+                "width and height must be at least {} to fit the cabin inside a padding of {}",
+                min_size, self.padding
+            ));
+        }
+        if !(0. ..1.).contains(&self.high_ground) {
+            return Err("high_ground must be at least 0 and less than 1".to_string());
+        }
+        if !(0. ..=1.).contains(&self.grass_chance) {
+            return Err("grass_chance must be between 0 and 1".to_string());
+        }
+        if self.topography_spread <= 0. || self.tree_chance_spread <= 0. {
+            return Err("topography_spread and tree_chance_spread must be positive".to_string());
+        }
+        let max_lake_depth = self.width.min(self.height) as i32 / 2;
+        if self.lake_depth < 1 || self.lake_depth > max_lake_depth {
+            return Err(format!("lake_depth must be from 1 to {}", max_lake_depth));
+        }
+        if self.pier_length < 1 || self.pier_length >= self.lake_depth {
+            return Err(format!(
+                "pier_length must be at least 1 and less than lake_depth ({})",
+                self.lake_depth
+            ));
+        }
+        for (name, radius) in [
+            ("flower_patch_radius", self.flower_patch_radius),
+            ("tea_patch_radius", self.tea_patch_radius),
+        ] {
+            if radius > self.padding as u32 {
+                return Err(format!("{} can't be more than padding", name));
             }
-            let coord = Coord::new(x as i32, y as i32);
-            match ch {
-                ',' => {
-                    world.spawn_floor(coord, 0.);
-                }
-                '.' => {
-                    world.spawn_ground(coord, 0.);
-                }
-                '1' => {
-                    world.spawn_floor(coord, 0.);
-                    world.spawn_gumboots(coord);
-                }
-                '2' => {
-                    world.spawn_floor(coord, 0.);
-                    world.spawn_umbrella(coord);
-                }
-                '3' => {
-                    world.spawn_floor(coord, 0.);
-                    world.spawn_shovel(coord);
-                }
-                '4' => {
-                    world.spawn_floor(coord, 0.);
-                    world.spawn_map(coord);
-                }
-                '5' => {
-                    world.spawn_floor(coord, 0.);
-                    world.spawn_weather_report(coord);
-                }
-                '6' => {
-                    world.spawn_ground(coord, 0.);
-                    world.spawn_lantern(coord);
-                }
-                '&' => {
-                    world.spawn_ground(coord, 0.);
-                    world.spawn_tree(coord, rng);
-                }
-                'L' => {
-                    world.spawn_floor(coord, 0.);
-                    world.spawn_light(coord);
-                }
-                'M' => {
-                    world.spawn_floor(coord, 0.);
-                    world.spawn_light(coord);
-                }
-                '#' => {
-                    world.spawn_floor(coord, 0.);
-                    world.spawn_wall(coord);
-                    prev_wall = true;
-                    continue;
-                }
-                '+' => {
-                    world.spawn_floor(coord, 0.);
-                    if prev_wall {
-                        world.spawn_door(coord, direction::Axis::Y);
-                    } else {
-                        world.spawn_door(coord, direction::Axis::X);
-                    }
-                }
-                '%' => {
-                    world.spawn_floor(coord, 0.);
-                    if prev_wall {
-                        world.spawn_window(coord, direction::Axis::Y);
-                    } else {
-                        world.spawn_window(coord, direction::Axis::X);
-                    }
-                }
-                '~' => {
-                    world.spawn_water(coord, rng);
-                }
-                '@' => {
-                    world.spawn_ground(coord, 0.);
-                    let location = Location {
-                        coord,
-                        layer: Some(Layer::Character),
-                    };
-                    player = Some(world.insert_entity_data(location, player_data.take().unwrap()));
-                }
-                ' ' => (),
-                _ => log::warn!(
-                    "unexpected char in terrain: {} ({})",
-                    ch.escape_unicode(),
-                    ch
-                ),
+            if patch_area(radius) < forage::PATCH_CAPACITY {
+                return Err(format!(
+                    "{} is too small to fit {} plants",
+                    name,
+                    forage::PATCH_CAPACITY
+                ));
             }
-            prev_wall = false;
         }
+        if self.num_equipment > EQUIPMENT.len() {
+            return Err(format!(
+                "num_equipment can't be more than {}",
+                EQUIPMENT.len()
+            ));
+        }
+        Ok(())
     }
-    let player = player.expect("didn't create player");
-    Terrain {
-        world,
-        player,
-        cabin_direction: CardinalDirection::West,
+
+    /// The value of a parameter that prefabs can refer to by name
+    pub fn parameter(&self, name: &str) -> Option<f64> {
+        match name {
+            "padding" => Some(self.padding as f64),
+            "high_ground" => Some(self.high_ground),
+            "feature_distance" => Some(self.feature_distance as f64),
+            _ => None,
+        }
     }
 }
 
-const SIZE: Size = Size::new_u16(120, 120);
+/// Equipment hidden around the map, in the order it's placed
+pub const EQUIPMENT: &[Equipment] = &[
+    Equipment::Umbrella,
+    Equipment::Shovel,
+    Equipment::Lantern,
+    Equipment::Gumboots,
+    Equipment::WeatherReport,
+    Equipment::Map,
+    Equipment::Crowbar,
+    Equipment::Axe,
+    Equipment::FishingRod,
+];
 
-fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain, &str> {
-    let size = SIZE;
+fn try_generate<R: Rng>(
+    config: &TerrainConfig,
+    player_data: EntityData,
+    rng: &mut R,
+) -> Result<Terrain, GenerationError> {
+    let size = config.size();
     let topography = Perlin2::new(rng);
-    let topography_spread = 0.05;
+    let topography_spread = config.topography_spread;
     let tree_chance = Perlin2::new(rng);
-    let tree_chance_spread = 0.05;
-    let tree_chance_scale = 0.3;
+    let tree_chance_spread = config.tree_chance_spread;
+    let tree_chance_scale = config.tree_chance_scale;
     let mut world = World::new(size);
     let mut no_trees = Grid::new_copy(size, false);
     let mut player_data = Some(player_data);
@@ -146,15 +349,18 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
             coord.y as f64 * topography_spread,
         ))
     });
-    let padding = 30;
+    let padding = config.padding;
+    let feature_distance = config.feature_distance;
+    // far enough from the edge that everything around the cabin is on the map
+    let cabin_padding = padding.max(cabin::SIZE as i32 / 2);
     let cabin_coord = topography_grid
         .enumerate()
         .filter_map(|(coord, &f)| {
-            if coord.x > padding
-                && coord.y > padding
-                && coord.x < size.x() as i32 - padding
-                && coord.y < size.y() as i32 - padding
-                && f > 0.65
+            if coord.x > cabin_padding
+                && coord.y > cabin_padding
+                && coord.x < size.x() as i32 - cabin_padding
+                && coord.y < size.y() as i32 - cabin_padding
+                && f > config.high_ground
             {
                 Some(coord)
             } else {
@@ -162,182 +368,62 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
             }
         })
         .choose(rng)
-        .ok_or("no cabin coord")?;
-    world.spawn_bed(cabin_coord);
-    world.spawn_light(cabin_coord);
-    let cabin_size = Size::new(9, 9);
-    let cabin_top_left = cabin_coord - (cabin_size.to_coord().unwrap() / 2);
-    for coord in cabin_size.coord_iter_row_major() {
-        world.spawn_floor(
-            coord + cabin_top_left,
-            *topography_grid.get_checked(coord + cabin_top_left),
-        );
-    }
-    let door_direction = rng.gen::<CardinalDirection>();
-    let door_coord =
-        cabin_coord + door_direction.coord() * cabin_size.get(door_direction.axis()) as i32 / 2;
-    *no_trees.get_checked_mut(door_coord + door_direction.coord()) = true;
-    *no_trees.get_checked_mut(door_coord + door_direction.coord() * 2) = true;
-    world.spawn_door(door_coord, door_direction.axis());
-    world.spawn_light(door_coord - door_direction.coord());
-    let lamp_coord =
-        door_coord + (door_direction.coord() * 5) + door_direction.left90().coord() * 1;
-    world.spawn_lamp(lamp_coord);
-    let bulletin_board_coord =
-        door_coord + (door_direction.coord() * 3) + door_direction.right90().coord();
-    world.spawn_bulletin_board(bulletin_board_coord);
-    *no_trees.get_checked_mut(bulletin_board_coord + Coord::new(0, 1)) = true;
-    *no_trees.get_checked_mut(bulletin_board_coord + Coord::new(0, 2)) = true;
-    let mut chair_candidates = Vec::new();
-    for coord in [
-        door_direction.left90().coord(),
-        door_direction.right90().coord(),
-    ] {
-        let window_coord =
-            door_coord + coord * cabin_size.get(door_direction.axis().other()) as i32 / 4;
-        world.spawn_window(window_coord, door_direction.axis());
-        world.spawn_light(window_coord - door_direction.coord());
-        chair_candidates.push(window_coord - door_direction.coord());
-    }
-    for direction in [
-        door_direction.left90(),
-        door_direction.opposite(),
-        door_direction.right90(),
-    ] {
-        let mid_coord =
-            cabin_coord + direction.coord() * cabin_size.get(direction.axis()) as i32 / 2;
-        world.spawn_window(mid_coord, direction.axis());
-        world.spawn_light(mid_coord - direction.coord());
-        chair_candidates.push(mid_coord - direction.coord());
-        for coord in [direction.left90().coord(), direction.right90().coord()] {
-            let window_coord =
-                mid_coord + coord * cabin_size.get(direction.axis().other()) as i32 / 4;
-            world.spawn_window(window_coord, direction.axis());
-            world.spawn_light(window_coord - direction.coord());
-            chair_candidates.push(window_coord - direction.coord());
-        }
-    }
-    let &chair_coord = chair_candidates.choose(rng).unwrap();
-    if chair_coord.x < cabin_coord.x {
-        world.spawn_chair_left_facing(chair_coord);
-    } else {
-        world.spawn_chair_right_facing(chair_coord);
-    }
-    let chair_window_direction = CardinalDirection::all()
-        .find(|d| {
-            world
-                .spatial_table
-                .layers_at_checked(chair_coord + d.coord())
-                .feature
-                .is_some()
-        })
-        .unwrap();
-    let teapot_coord = chair_coord
-        + if rng.gen() {
-            chair_window_direction.left90().coord()
-        } else {
-            chair_window_direction.right90().coord()
-        };
-    world.spawn_teapot(teapot_coord);
-    for offset in cabin_size.coord_iter_row_major() {
-        let coord = cabin_top_left + offset;
-        if world
-            .spatial_table
-            .layers_at_checked(coord)
-            .feature
-            .is_none()
-            && CardinalDirection::all().any(|d| {
-                world
-                    .spatial_table
-                    .layers_at_checked(d.coord() + coord)
-                    .floor
-                    .is_none()
-            })
-        {
-            world.spawn_wall(coord);
-        }
+        .ok_or(GenerationError::NoSite(Landmark::Cabin))?;
+    let cabin = cabin::build(
+        &mut world,
+        &topography_grid,
+        &mut no_trees,
+        cabin_coord,
+        rng,
+    )?;
+    if !cabin.player_coord.is_valid(size) {
+        return Err(GenerationError::OffMap(Landmark::Player));
     }
+    let door_coord = cabin.door_coord;
+    let door_direction = cabin.door_direction;
     let player_location = Location {
-        coord: cabin_coord
-            + door_direction.coord() * cabin_size.get(door_direction.axis()) as i32 * 2,
+        coord: cabin.player_coord,
         layer: Some(Layer::Character),
     };
     let player = world.insert_entity_data(player_location, player_data.take().unwrap());
-    let ruins_coord = topography_grid
-        .enumerate()
-        .filter_map(|(coord, &f)| {
-            if coord.x > padding
-                && coord.y > padding
-                && coord.x < size.x() as i32 - padding
-                && coord.y < size.y() as i32 - padding
-                && coord.manhattan_distance(cabin_coord) > 20
-                && f > 0.65
-            {
-                Some(coord)
-            } else {
-                None
-            }
-        })
-        .choose(rng)
-        .ok_or("no ruins coord")?;
-    let ruins_txt = include_str!("ruins_prefab.txt");
-    let ruins_num_rotations = rng.gen_range(0..4);
-    for (y, row) in ruins_txt.split('\n').filter(|s| !s.is_empty()).enumerate() {
-        for (x, ch) in row.chars().enumerate() {
-            let mut out_coord = Coord::new(x as i32, y as i32);
-            for _ in 0..ruins_num_rotations {
-                out_coord = out_coord.left90();
-            }
-            out_coord += ruins_coord;
-            match ch {
-                '#' => {
-                    world.spawn_ruins_floor(out_coord, *topography_grid.get_checked(out_coord));
-                    world.spawn_ruins_wall(out_coord);
-                }
-                '.' => {
-                    world.spawn_ruins_floor(out_coord, *topography_grid.get_checked(out_coord));
-                }
-                '?' => {
-                    world.spawn_ground(out_coord, *topography_grid.get_checked(out_coord));
-                    world.spawn_altar(out_coord);
-                    *no_trees.get_checked_mut(out_coord + Coord::new(0, 1)) = true;
-                    *no_trees.get_checked_mut(out_coord + Coord::new(0, 2)) = true;
-                    *no_trees.get_checked_mut(out_coord + Coord::new(0, 3)) = true;
-                }
-                ' ' => (),
-                other => panic!("unexpected char {}", other),
-            }
-        }
-    }
-    let num_extra_lamps = 3;
-    let mut lamp_coords = vec![lamp_coord];
+    let prefab_library = PrefabLibrary::new(config);
+    let mut prefab_coords = Vec::new();
+    let ruins = place_prefab(
+        &mut world,
+        &topography_grid,
+        &mut no_trees,
+        cabin_coord,
+        &mut prefab_coords,
+        prefab_library.get("ruins"),
+        rng,
+    )
+    .ok_or(GenerationError::NoSite(Landmark::Prefab("ruins")))?;
+    let ruins_coord = ruins.coord;
+    let altar_coord = ruins.anchor("altar")?;
+    let num_extra_lamps = config.num_extra_lamps;
     for _ in 0..num_extra_lamps {
-        let coord = size
-            .coord_iter_row_major()
-            .filter(|coord| {
-                coord.x > padding
-                    && coord.y > padding
-                    && coord.x < size.x() as i32 - padding
-                    && coord.y < size.y() as i32 - padding
-                    && coord.manhattan_distance(cabin_coord) > 20
-                    && coord.manhattan_distance(ruins_coord) > 20
-                    && lamp_coords
-                        .iter()
-                        .all(|&c| coord.manhattan_distance(c) > 20)
-            })
-            .choose(rng)
-            .ok_or("no lamp coord")?;
-        lamp_coords.push(coord);
-        world.spawn_lamp(coord);
-        *no_trees.get_checked_mut(coord + Coord::new(0, 1)) = true;
-        *no_trees.get_checked_mut(coord + Coord::new(0, 2)) = true;
+        place_prefab(
+            &mut world,
+            &topography_grid,
+            &mut no_trees,
+            cabin_coord,
+            &mut prefab_coords,
+            prefab_library.get("lamp"),
+            rng,
+        )
+        .ok_or(GenerationError::NoSite(Landmark::Prefab("lamp")))?;
     }
     let lake_direction = rng.gen::<CardinalDirection>();
     let lake_mid = (size.to_coord().unwrap() / 2)
-        + (lake_direction.coord() * (((size.get(lake_direction.axis()) as i32) / 2) - 24));
-    let pier_offset = rng.gen_range(-40..40) + lake_mid.get(lake_direction.axis().other());
+        + (lake_direction.coord()
+            * (((size.get(lake_direction.axis()) as i32) / 2) - config.lake_depth));
+    let pier_spread = size.get(lake_direction.axis().other()) as i32 / 3;
+    let pier_offset =
+        rng.gen_range(-pier_spread..pier_spread) + lake_mid.get(lake_direction.axis().other());
     let lake_edge = lake_mid.set(lake_direction.axis().other(), 0);
     let mut lake_offset = 0;
+    let mut pier_start_coord = None;
+    let mut pier_end_coord = None;
     let lake_offset_step_pool = [0, 0, 0, 0, 0, 0, 0, 1, -1];
     for i in 0..(size.get(lake_direction.axis().other()) as i32) {
         lake_offset += lake_offset_step_pool.choose(rng).unwrap();
@@ -345,19 +431,33 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
         if i == pier_offset {
             let pier_coord =
                 Coord::new_axis(j - lake_direction.sign() * 2, i, lake_direction.axis());
+            let pier_length = config.pier_length;
+            let end_coord = pier_coord + (lake_direction.coord() * (pier_length - 1));
+            let pier_extent = [
+                pier_coord - Coord::new_axis(1, 0, lake_direction.axis()),
+                pier_coord - lake_direction.coord(),
+                end_coord + lake_direction.left90().coord(),
+                end_coord + lake_direction.right90().coord(),
+            ];
+            if pier_extent.iter().any(|coord| !coord.is_valid(size)) {
+                return Err(GenerationError::OffMap(Landmark::Pier));
+            }
             *no_trees.get_checked_mut(pier_coord - Coord::new_axis(1, 0, lake_direction.axis())) =
                 true;
-            let pier_length = 8;
+            pier_start_coord = Some(pier_coord - lake_direction.coord());
             for i in 0..pier_length {
                 let coord = pier_coord
                     + Coord::new_axis(i * lake_direction.sign(), 0, lake_direction.axis());
+                if world.spatial_table.layers_at_checked(coord).floor.is_some() {
+                    return Err(GenerationError::Blocked(Landmark::Pier));
+                }
                 world.spawn_pier_floor(coord, *topography_grid.get_checked(coord));
                 *no_trees.get_checked_mut(coord) = true;
             }
-            let pier_end_coord = pier_coord + (lake_direction.coord() * (pier_length - 1));
-            if let Some(floor) = world.spatial_table.layers_at_checked(pier_end_coord).floor {
+            if let Some(floor) = world.spatial_table.layers_at_checked(end_coord).floor {
                 world.components.end_of_pier.insert(floor, ());
             }
+            pier_end_coord = Some(end_coord);
             let pier_lamp_coord = pier_coord
                 + (lake_direction.coord() * (pier_length - 1))
                 + if rng.gen() {
@@ -388,7 +488,7 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
             }
             if world.spatial_table.layers_at_checked(coord).floor.is_none() {
                 if coord == player_location.coord {
-                    return Err("player spawned in lake");
+                    return Err(GenerationError::Flooded(Landmark::Player));
                 }
                 world.spawn_lake_water(coord, rng);
                 let noise = tree_chance.noise01((
@@ -418,9 +518,9 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
                 && coord.y > padding
                 && coord.x < size.x() as i32 - padding
                 && coord.y < size.y() as i32 - padding
-                && coord.manhattan_distance(cabin_coord) > 20
-                && coord.manhattan_distance(ruins_coord) > 30
-                && f > 0.65
+                && coord.manhattan_distance(cabin_coord) > feature_distance
+                && coord.manhattan_distance(ruins_coord) > feature_distance * 3 / 2
+                && f > config.high_ground
             {
                 Some(coord)
             } else {
@@ -428,8 +528,8 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
             }
         })
         .choose(rng)
-        .ok_or("no flower patch coord")?;
-    let flower_patch_radius = 5;
+        .ok_or(GenerationError::NoSite(Landmark::FlowerPatch))?;
+    let flower_patch_radius = config.flower_patch_radius;
     let mut flower_candidates = Vec::new();
     for offset in Size::new(flower_patch_radius * 2, flower_patch_radius * 2).coord_iter_row_major()
     {
@@ -442,9 +542,14 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
             }
         }
     }
-    let num_flowers = 12;
+    // the ground isn't laid until later, so remember where the patches are for now
+    let mut forage_patches = flower_candidates
+        .iter()
+        .map(|&coord| (coord, Item::Flower))
+        .collect::<Vec<_>>();
+    let num_flowers = forage::PATCH_CAPACITY;
     if flower_candidates.len() < num_flowers {
-        return Err("not enough flower candidates");
+        return Err(GenerationError::NoRoom(Landmark::FlowerPatch));
     }
     for &coord in flower_candidates.choose_multiple(rng, num_flowers) {
         world.spawn_flower(coord);
@@ -457,10 +562,10 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
                 && coord.y > padding
                 && coord.x < size.x() as i32 - padding
                 && coord.y < size.y() as i32 - padding
-                && coord.manhattan_distance(cabin_coord) > 20
-                && coord.manhattan_distance(ruins_coord) > 20
-                && coord.manhattan_distance(flower_patch_coord) > 20
-                && f > 0.65
+                && coord.manhattan_distance(cabin_coord) > feature_distance
+                && coord.manhattan_distance(ruins_coord) > feature_distance
+                && coord.manhattan_distance(flower_patch_coord) > feature_distance
+                && f > config.high_ground
             {
                 Some(coord)
             } else {
@@ -468,8 +573,8 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
             }
         })
         .choose(rng)
-        .ok_or("no tea patch coord")?;
-    let tea_patch_radius = 5;
+        .ok_or(GenerationError::NoSite(Landmark::TeaPatch))?;
+    let tea_patch_radius = config.tea_patch_radius;
     let mut tea_candidates = Vec::new();
     for offset in Size::new(tea_patch_radius * 2, tea_patch_radius * 2).coord_iter_row_major() {
         let rel_coord = offset - Coord::new(tea_patch_radius as i32, tea_patch_radius as i32);
@@ -481,9 +586,10 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
             }
         }
     }
-    let num_tea = 12;
+    forage_patches.extend(tea_candidates.iter().map(|&coord| (coord, Item::Tea)));
+    let num_tea = forage::PATCH_CAPACITY;
     if tea_candidates.len() < num_tea {
-        return Err("not enough tea candidates");
+        return Err(GenerationError::NoRoom(Landmark::TeaPatch));
     }
     for &coord in tea_candidates.choose_multiple(rng, num_tea) {
         world.spawn_tea(coord);
@@ -495,6 +601,43 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
             || coord.x > size.width() as i32 - padding
             || coord.y > size.height() as i32 - padding
     };
+    let lookout_coord = place_prefab(
+        &mut world,
+        &topography_grid,
+        &mut no_trees,
+        cabin_coord,
+        &mut prefab_coords,
+        prefab_library.get("lookout"),
+        rng,
+    )
+    .ok_or(GenerationError::NoSite(Landmark::Prefab("lookout")))?
+    .anchor("lookout")?;
+    place_streams(
+        config.num_streams,
+        &mut world,
+        &topography_grid,
+        &mut no_trees,
+        lake_direction,
+        player_location.coord,
+        rng,
+    )?;
+    let pier_start_coord = pier_start_coord.ok_or(GenerationError::NoSite(Landmark::Pier))?;
+    let pier_end_coord = pier_end_coord.ok_or(GenerationError::NoSite(Landmark::Pier))?;
+    let trail_destinations = [
+        ("The ruins", altar_coord),
+        ("The lake", pier_start_coord),
+        ("The flower meadow", flower_patch_coord),
+        ("The tea garden", tea_patch_coord),
+        ("The lookout tower", lookout_coord),
+    ];
+    place_trails(
+        &mut world,
+        &topography_grid,
+        &mut no_trees,
+        door_coord + door_direction.coord(),
+        &trail_destinations,
+        player_location.coord,
+    )?;
     let mut rock_candidates = Vec::new();
     let mut behind_tree = Grid::new_copy(size, false);
     for coord in size.coord_iter_row_major() {
@@ -518,7 +661,7 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
                     < (tree_chance.noise01((
                         coord.x as f64 * tree_chance_spread,
                         coord.y as f64 * tree_chance_spread,
-                    )) - 0.2)
+                    )) - config.tree_chance_offset)
                         * tree_chance_scale
             {
                 for i in 0..3 {
@@ -532,7 +675,9 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
                     coord.x as f64 * tree_chance_spread,
                     coord.y as f64 * tree_chance_spread,
                 ));
-                if (noise < 0.4 && rng.gen::<f64>() * 0.8 > noise) || rng.gen::<f64>() < 0.1 {
+                if (noise < config.grass_threshold && rng.gen::<f64>() * 0.8 > noise)
+                    || rng.gen::<f64>() < config.grass_chance
+                {
                     if world
                         .spatial_table
                         .layers_at_checked(coord)
@@ -551,9 +696,14 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
         }
         world.spawn_ground(coord, *topography_grid.get_checked(coord));
     }
-    let num_rocks = 200;
+    for (coord, item) in forage_patches {
+        if let Some(floor) = world.spatial_table.layers_at_checked(coord).floor {
+            world.components.forage_patch.insert(floor, item);
+        }
+    }
+    let num_rocks = config.num_rocks;
     if rock_candidates.len() < num_rocks {
-        return Err("not enough rock rock candidate");
+        return Err(GenerationError::NoRoom(Landmark::Rocks));
     }
     for &coord in rock_candidates.choose_multiple(rng, num_rocks) {
         world.spawn_rock(coord);
@@ -564,7 +714,7 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
         if cell.feature.is_none() && cell.item.is_none() && cell.character.is_none() {
             if let Some(floor) = cell.floor {
                 if world.components.ground.contains(floor) {
-                    if coord.manhattan_distance(cabin_coord) > 50 {
+                    if coord.manhattan_distance(cabin_coord) > config.equipment_cabin_distance {
                         if coord.x > padding
                             && coord.y > padding
                             && coord.x < size.x() as i32 - padding
@@ -583,30 +733,25 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
     let mut equipment_candidates_spread: Vec<Coord> = Vec::new();
     'outer: for &coord in &equipment_candidates {
         for &existing in &equipment_candidates_spread {
-            if existing.manhattan_distance(coord) < 20 {
+            if existing.manhattan_distance(coord) < config.equipment_spread {
                 continue 'outer;
             }
         }
         equipment_candidates_spread.push(coord);
     }
-    let num_equipment = 6;
-    if equipment_candidates_spread.len() < num_equipment {
-        return Err("not enough equipment candidates");
-    }
-    let equipment_types = vec![
-        Equipment::Umbrella,
-        Equipment::Shovel,
-        Equipment::Lantern,
-        Equipment::Gumboots,
-        Equipment::WeatherReport,
-        Equipment::Map,
-        Equipment::Crowbar,
+    let equipment_types = &EQUIPMENT[..config.num_equipment];
+    if equipment_candidates_spread.len() < equipment_types.len() {
+        return Err(GenerationError::NoRoom(Landmark::Equipment));
+    }
+    let mut reachability_targets = vec![
+        (Landmark::CabinDoor, door_coord),
+        (Landmark::Pier, pier_end_coord),
     ];
     for (coord, equipment) in equipment_candidates_spread
         .into_iter()
-        .take(equipment_types.len())
-        .zip(equipment_types.into_iter())
+        .zip(equipment_types.iter().cloned())
     {
+        reachability_targets.push((Landmark::Equipment, coord));
         match equipment {
             Equipment::Umbrella => world.spawn_umbrella(coord),
             Equipment::Shovel => world.spawn_shovel(coord),
@@ -615,20 +760,479 @@ fn try_generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Result<Terrain,
             Equipment::WeatherReport => world.spawn_weather_report(coord),
             Equipment::Map => world.spawn_map(coord),
             Equipment::Crowbar => world.spawn_crowbar(coord),
+            Equipment::Axe => world.spawn_axe(coord),
+            Equipment::FishingRod => world.spawn_fishing_rod(coord),
         };
     }
+    place_boat(&mut world, lake_direction)?;
+    place_island(&mut world, rng)?;
+    let trees_cleared = ensure_reachable(
+        &mut world,
+        &topography_grid,
+        player_location.coord,
+        &reachability_targets,
+    )?;
     Ok(Terrain {
         world,
         player,
         cabin_direction: door_direction.opposite(),
+        stats: GenerationStats {
+            trees_cleared,
+            ..Default::default()
+        },
     })
 }
 
-pub fn generate<R: Rng>(player_data: EntityData, rng: &mut R) -> Terrain {
-    loop {
-        match try_generate(player_data.clone(), rng) {
-            Ok(terrain) => break terrain,
-            Err(message) => log::warn!("{}", message),
+/// Where a prefab was placed, and where its anchors ended up
+struct PlacedPrefab {
+    coord: Coord,
+    anchors: BTreeMap<String, Coord>,
+}
+
+impl PlacedPrefab {
+    fn anchor(&self, name: &'static str) -> Result<Coord, GenerationError> {
+        self.anchors
+            .get(name)
+            .cloned()
+            .ok_or(GenerationError::MissingAnchor(name))
+    }
+}
+
+/// Places a prefab from the library somewhere that satisfies its constraints, in a randomly chosen
+/// orientation. The centre of each placed prefab is added to `prefab_coords`. Returns `None` if
+/// nowhere satisfies the constraints.
+fn place_prefab<R: Rng>(
+    world: &mut World,
+    topography_grid: &Grid<f64>,
+    no_trees: &mut Grid<bool>,
+    cabin_coord: Coord,
+    prefab_coords: &mut Vec<Coord>,
+    prefab: &Prefab,
+    rng: &mut R,
+) -> Option<PlacedPrefab> {
+    let size = world.size();
+    let constraints = prefab.constraints;
+    let transform = prefab.choose_transform(rng);
+    let cells = prefab.cells(transform);
+    let is_free = |coord: Coord| {
+        if !coord.is_valid(size) {
+            return false;
+        }
+        let layers = world.spatial_table.layers_at_checked(coord);
+        !no_trees.get_checked(coord)
+            && layers.floor.is_none()
+            && layers.feature.is_none()
+            && layers.item.is_none()
+            && layers.character.is_none()
+    };
+    let padding = constraints.edge_padding;
+    let candidates = size.coord_iter_row_major().filter(|&coord| {
+        coord.x > padding
+            && coord.y > padding
+            && coord.x < size.x() as i32 - padding
+            && coord.y < size.y() as i32 - padding
+            && *topography_grid.get_checked(coord) >= constraints.min_height
+            && coord.manhattan_distance(cabin_coord) > constraints.min_cabin_distance
+            && prefab_coords
+                .iter()
+                .all(|&c| coord.manhattan_distance(c) > constraints.min_separation)
+            && cells.iter().all(|&(offset, _)| is_free(coord + offset))
+    });
+    let coord = match constraints.placement {
+        Placement::Random => candidates.choose(rng),
+        Placement::Highest => candidates.max_by(|&a, &b| {
+            topography_grid
+                .get_checked(a)
+                .partial_cmp(topography_grid.get_checked(b))
+                .unwrap()
+        }),
+    }?;
+    for (offset, spawns) in cells {
+        let cell_coord = coord + offset;
+        *no_trees.get_checked_mut(cell_coord) = true;
+        for &spawn in spawns {
+            world.spawn_prefab_cell(
+                spawn,
+                cell_coord,
+                *topography_grid.get_checked(cell_coord),
+                rng,
+            );
+        }
+    }
+    prefab_coords.push(coord);
+    let anchors = prefab
+        .anchors(transform)
+        .into_iter()
+        .map(|(name, offset)| (name, coord + offset))
+        .collect();
+    Some(PlacedPrefab { coord, anchors })
+}
+
+/// Traces streams from springs on high ground downhill into the lake, with fords at intervals
+/// along them
+fn place_streams<R: Rng>(
+    num_streams: usize,
+    world: &mut World,
+    topography_grid: &Grid<f64>,
+    no_trees: &mut Grid<bool>,
+    lake_direction: CardinalDirection,
+    player_coord: Coord,
+    rng: &mut R,
+) -> Result<(), GenerationError> {
+    let max_attempts = 20;
+    let min_length = 15;
+    let ford_spacing = 12;
+    let padding = 10;
+    let size = world.size();
+    let is_free = |world: &World, no_trees: &Grid<bool>, coord: Coord| {
+        let layers = world.spatial_table.layers_at_checked(coord);
+        coord != player_coord
+            && !no_trees.get_checked(coord)
+            && layers.floor.is_none()
+            && layers.feature.is_none()
+            && layers.item.is_none()
+    };
+    let springs = size
+        .coord_iter_row_major()
+        .filter(|&coord| {
+            coord.x > padding
+                && coord.y > padding
+                && coord.x < size.x() as i32 - padding
+                && coord.y < size.y() as i32 - padding
+                && *topography_grid.get_checked(coord) > 0.7
+                && is_free(world, no_trees, coord)
+        })
+        .collect::<Vec<_>>();
+    let mut num_placed = 0;
+    for _ in 0..max_attempts {
+        if num_placed == num_streams {
+            break;
+        }
+        let &spring = springs
+            .choose(rng)
+            .ok_or(GenerationError::NoSite(Landmark::Spring))?;
+        if world.is_stream(spring) {
+            continue;
+        }
+        let path = match trace_stream(spring, topography_grid, lake_direction, rng, |coord| {
+            if world.is_stream(coord)
+                || world
+                    .spatial_table
+                    .layers_at_checked(coord)
+                    .floor
+                    .map(|floor| world.components.lake.contains(floor))
+                    .unwrap_or(false)
+            {
+                Some(true)
+            } else if coord.x > 2
+                && coord.y > 2
+                && coord.x < size.x() as i32 - 3
+                && coord.y < size.y() as i32 - 3
+                && is_free(world, no_trees, coord)
+            {
+                Some(false)
+            } else {
+                None
+            }
+        }) {
+            Some(path) if path.len() >= min_length => path,
+            _ => continue,
+        };
+        for &coord in &path {
+            world.spawn_stream(coord, *topography_grid.get_checked(coord));
+            *no_trees.get_checked_mut(coord) = true;
+        }
+        for &coord in path.iter().skip(ford_spacing / 2).step_by(ford_spacing) {
+            world.make_ford(coord);
+        }
+        num_placed += 1;
+    }
+    if num_placed == 0 {
+        return Err(GenerationError::NoSite(Landmark::Streams));
+    }
+    Ok(())
+}
+
+/// Follows the lie of the land from a spring, with a pull towards the lake so the stream doesn't
+/// get stuck in hollows. `classify` returns `Some(true)` for cells the stream can flow into and
+/// end (the lake or another stream), `Some(false)` for cells the stream can flow through, and
+/// `None` for cells it must avoid.
+fn trace_stream<R: Rng, F: Fn(Coord) -> Option<bool>>(
+    spring: Coord,
+    topography_grid: &Grid<f64>,
+    lake_direction: CardinalDirection,
+    rng: &mut R,
+    classify: F,
+) -> Option<Vec<Coord>> {
+    let max_length = 200;
+    let lake_pull = 0.01;
+    let meander = 0.01;
+    let progress = |coord: Coord| (coord.get(lake_direction.axis()) * lake_direction.sign()) as f64;
+    let mut path = vec![spring];
+    while path.len() < max_length {
+        let coord = *path.last().unwrap();
+        let neighbours = CardinalDirection::all()
+            .filter(|&d| d != lake_direction.opposite())
+            .map(|d| coord + d.coord())
+            .filter(|neighbour| !path.contains(neighbour))
+            .filter_map(|neighbour| Some((neighbour, classify(neighbour)?)))
+            .collect::<Vec<_>>();
+        if neighbours.iter().any(|&(_, joins)| joins) {
+            return Some(path);
+        }
+        let &(next, _) = neighbours.iter().min_by(|a, b| {
+            let score =
+                |coord: Coord| *topography_grid.get_checked(coord) - progress(coord) * lake_pull;
+            score(a.0).partial_cmp(&score(b.0)).unwrap()
+        })?;
+        // jitter the chosen direction so streams meander a little
+        let next = neighbours
+            .iter()
+            .map(|&(coord, _)| coord)
+            .filter(|&coord| {
+                (*topography_grid.get_checked(coord) - *topography_grid.get_checked(next)).abs()
+                    < meander
+            })
+            .choose(rng)
+            .unwrap_or(next);
+        path.push(next);
+    }
+    None
+}
+
+/// Extra trail cost of crossing a stream, so that trails cross streams rather than follow them
+const BRIDGE_COST: u32 = 200;
+
+/// Lays trails from the cabin door to each destination, with signposts where they part ways
+fn place_trails(
+    world: &mut World,
+    topography_grid: &Grid<f64>,
+    no_trees: &mut Grid<bool>,
+    start: Coord,
+    destinations: &[(&'static str, Coord)],
+    player_coord: Coord,
+) -> Result<(), GenerationError> {
+    let size = world.size();
+    let edge_padding = 4;
+    let passable = |world: &World, coord: Coord| {
+        if coord.x < edge_padding
+            || coord.y < edge_padding
+            || coord.x >= size.width() as i32 - edge_padding
+            || coord.y >= size.height() as i32 - edge_padding
+        {
+            return false;
+        }
+        let layers = world.spatial_table.layers_at_checked(coord);
+        let walkable_floor = layers
+            .floor
+            .map(|floor| {
+                (world.components.ground.contains(floor) && !world.components.lake.contains(floor))
+                    || world.components.stream.contains(floor)
+                    || matches!(
+                        world.components.tile.get(floor),
+                        Some(&Tile::RuinsFloor) | Some(&Tile::PierFloor)
+                    )
+            })
+            .unwrap_or(true);
+        let blocked = layers
+            .feature
+            .map(|feature| world.components.solid.contains(feature))
+            .unwrap_or(false);
+        walkable_floor && !blocked
+    };
+    let mut on_trail = Grid::new_copy(size, false);
+    let mut paths = Vec::new();
+    for &(name, goal) in destinations {
+        let path = trail::find_path(start, goal, topography_grid, &on_trail, |coord| {
+            if !passable(world, coord) {
+                None
+            } else if world.is_stream(coord) {
+                Some(BRIDGE_COST)
+            } else {
+                Some(0)
+            }
+        })
+        .ok_or(GenerationError::NoRoute(name))?;
+        for &coord in &path {
+            *on_trail.get_checked_mut(coord) = true;
+        }
+        paths.push((name, path));
+    }
+    for (_, path) in &paths {
+        for &coord in path {
+            *no_trees.get_checked_mut(coord) = true;
+            if world.spatial_table.layers_at_checked(coord).floor.is_none() {
+                world.spawn_trail(coord, *topography_grid.get_checked(coord));
+            } else if world.is_stream(coord) {
+                world.build_bridge(coord);
+            }
+        }
+    }
+    let mut junctions = trail::junctions(&paths);
+    if !junctions.iter().any(|junction| junction.coord == start) {
+        // always put up a sign at the trailhead
+        junctions.push(trail::Junction {
+            coord: start,
+            destinations: paths
+                .iter()
+                .filter_map(|(name, path)| {
+                    let direction = CardinalDirection::all()
+                        .find(|d| path.get(1) == Some(&(start + d.coord())))?;
+                    Some((*name, direction))
+                })
+                .collect(),
+        });
+    }
+    for junction in junctions {
+        let signpost_coord = CardinalDirection::all()
+            .map(|d| junction.coord + d.coord())
+            .find(|&coord| {
+                let layers = world.spatial_table.layers_at_checked(coord);
+                passable(world, coord)
+                    && coord != player_coord
+                    && !on_trail.get_checked(coord)
+                    && layers.floor.is_none()
+                    && layers.feature.is_none()
+                    && layers.item.is_none()
+            });
+        if let Some(coord) = signpost_coord {
+            *no_trees.get_checked_mut(coord) = true;
+            world.spawn_signpost(coord, trail::signpost_text(&junction));
+        }
+    }
+    Ok(())
+}
+
+/// Moors a rowing boat in the lake just past the end of the pier
+fn place_boat(world: &mut World, lake_direction: CardinalDirection) -> Result<(), GenerationError> {
+    let end_of_pier = world
+        .components
+        .end_of_pier
+        .entities()
+        .next()
+        .ok_or(GenerationError::NoSite(Landmark::Pier))?;
+    let boat_coord = world
+        .spatial_table
+        .coord_of(end_of_pier)
+        .ok_or(GenerationError::NoSite(Landmark::Pier))?
+        + lake_direction.coord();
+    let layers = world.spatial_table.layers_at_checked(boat_coord);
+    let in_lake = layers
+        .floor
+        .map(|floor| world.components.lake.contains(floor))
+        .unwrap_or(false);
+    if !in_lake || layers.feature.is_some() {
+        return Err(GenerationError::NoRoom(Landmark::Boat));
+    }
+    world.spawn_boat(boat_coord);
+    Ok(())
+}
+
+/// Raises a small island out of the deep part of the lake with a cairn at its centre
+fn place_island<R: Rng>(world: &mut World, rng: &mut R) -> Result<(), GenerationError> {
+    let island_radius = 2;
+    let min_depth = 8;
+    let size = world.size();
+    world.compute_lake_depths();
+    let island_coord = world
+        .components
+        .lake_depth
+        .iter()
+        .filter(|(_, &depth)| depth >= min_depth)
+        .filter_map(|(entity, _)| world.spatial_table.coord_of(entity))
+        .filter(|coord| {
+            coord.x > island_radius
+                && coord.y > island_radius
+                && coord.x < size.x() as i32 - island_radius - 1
+                && coord.y < size.y() as i32 - island_radius - 1
+        })
+        .choose(rng)
+        .ok_or(GenerationError::NoSite(Landmark::Island))?;
+    for offset in Size::new_u16(island_radius as u16 * 2 + 1, island_radius as u16 * 2 + 1)
+        .coord_iter_row_major()
+    {
+        let rel_coord = offset - Coord::new(island_radius, island_radius);
+        if rel_coord.magnitude2() > (island_radius * island_radius + 1) as u32 {
+            continue;
+        }
+        let coord = island_coord + rel_coord;
+        let layers = *world.spatial_table.layers_at_checked(coord);
+        for entity in [layers.floor, layers.feature].into_iter().flatten() {
+            world.spatial_table.remove(entity);
+            world.components.remove_entity(entity);
+        }
+        // high enough that the island never floods
+        world.spawn_ground(coord, 1.);
+        if rel_coord.magnitude2() == (island_radius * island_radius) as u32 && rng.gen::<bool>() {
+            world.spawn_tree(coord, rng);
+        }
+    }
+    world.spawn_cairn(island_coord);
+    Ok(())
+}
+
+/// Extra cost of cutting through a tree when clearing a way to something the player can't reach.
+/// This is high so that clearings make use of existing gaps between the trees where they can.
+const TREE_CLEARING_COST: u32 = 100;
+
+/// Checks that the player can walk from `start` to each of the targets. Where trees are in the
+/// way, the cheapest path through them is cleared. Returns the number of trees cut down, or an
+/// error naming the first target that can't be reached at all.
+pub fn ensure_reachable(
+    world: &mut World,
+    topography_grid: &Grid<f64>,
+    start: Coord,
+    targets: &[(Landmark, Coord)],
+) -> Result<usize, GenerationError> {
+    let no_trail = Grid::new_copy(world.size(), false);
+    let mut reachable = world.reachable_from(start);
+    let mut trees_cleared = 0;
+    for &(landmark, target) in targets {
+        if *reachable.get_checked(target) {
+            continue;
+        }
+        let path = trail::find_path(start, target, topography_grid, &no_trail, |coord| {
+            if world.is_passable_on_foot(coord) {
+                Some(0)
+            } else if world.is_tree_on_dry_land(coord) {
+                Some(TREE_CLEARING_COST)
+            } else {
+                None
+            }
+        })
+        .ok_or(GenerationError::Unreachable(landmark))?;
+        trees_cleared += world.clear_trees(&path);
+        reachable = world.reachable_from(start);
+        if !*reachable.get_checked(target) {
+            return Err(GenerationError::Unreachable(landmark));
+        }
+    }
+    Ok(trees_cleared)
+}
+
+/// Attempts at generating a world before giving up
+const MAX_ATTEMPTS: u32 = 1000;
+
+/// Generates a world, retrying until an attempt succeeds or there have been too many attempts
+pub fn generate<R: Rng>(
+    config: &TerrainConfig,
+    player_data: EntityData,
+    rng: &mut R,
+) -> Result<Terrain, GenerationFailed> {
+    let mut stats = GenerationStats::default();
+    while stats.attempts < MAX_ATTEMPTS {
+        stats.attempts += 1;
+        match try_generate(config, player_data.clone(), rng) {
+            Ok(mut terrain) => {
+                stats.trees_cleared = terrain.stats.trees_cleared;
+                terrain.stats = stats;
+                return Ok(terrain);
+            }
+            Err(error) => {
+                log::warn!("{}", error);
+                *stats.rejections.entry(error).or_insert(0) += 1;
+            }
         }
     }
+    Err(GenerationFailed { stats })
 }
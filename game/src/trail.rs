@@ -0,0 +1,227 @@
+use gridbugs::{
+    direction::CardinalDirection,
+    grid_2d::{Coord, Grid},
+};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+};
+
+/// Cost of stepping onto an unmarked cell
+const STEP_COST: u32 = 10;
+
+/// Cost of stepping onto a cell that's already part of a trail. This is cheaper than an unmarked
+/// cell so that trails to different destinations share a path where they can.
+const TRAIL_STEP_COST: u32 = 5;
+
+/// Multiplier for the square of the height difference between neighbouring cells, which steers
+/// trails around steep ground
+const SLOPE_COST: f64 = 160_000.;
+
+fn step_cost(heights: &Grid<f64>, on_trail: &Grid<bool>, from: Coord, to: Coord) -> u32 {
+    let base = if *on_trail.get_checked(to) {
+        TRAIL_STEP_COST
+    } else {
+        STEP_COST
+    };
+    let slope = heights.get_checked(to) - heights.get_checked(from);
+    base + (slope * slope * SLOPE_COST) as u32
+}
+
+/// A* search for the cheapest path from `start` to a cell cardinally adjacent to `goal` (or
+/// `goal` itself). `extra_cost` returns the additional cost of stepping onto a cell, or `None` if
+/// the cell can't be part of a trail. The returned path includes `start`.
+pub fn find_path<F: Fn(Coord) -> Option<u32>>(
+    start: Coord,
+    goal: Coord,
+    heights: &Grid<f64>,
+    on_trail: &Grid<bool>,
+    extra_cost: F,
+) -> Option<Vec<Coord>> {
+    let heuristic = |coord: Coord| coord.manhattan_distance(goal) * TRAIL_STEP_COST;
+    let mut best_cost = Grid::new_copy(heights.size(), u32::MAX);
+    let mut came_from: Grid<Option<Coord>> = Grid::new_copy(heights.size(), None);
+    let mut queue = BinaryHeap::new();
+    *best_cost.get_checked_mut(start) = 0;
+    queue.push(Reverse((heuristic(start), 0, start.x, start.y)));
+    while let Some(Reverse((_, cost, x, y))) = queue.pop() {
+        let coord = Coord::new(x, y);
+        if cost > *best_cost.get_checked(coord) {
+            continue;
+        }
+        if coord.manhattan_distance(goal) <= 1 {
+            let mut path = vec![coord];
+            let mut current = coord;
+            while let Some(previous) = *came_from.get_checked(current) {
+                path.push(previous);
+                current = previous;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for direction in CardinalDirection::all() {
+            let neighbour = coord + direction.coord();
+            if !neighbour.is_valid(heights.size()) {
+                continue;
+            }
+            let extra = match extra_cost(neighbour) {
+                Some(extra) => extra,
+                None => continue,
+            };
+            let neighbour_cost = cost + step_cost(heights, on_trail, coord, neighbour) + extra;
+            if neighbour_cost < *best_cost.get_checked(neighbour) {
+                *best_cost.get_checked_mut(neighbour) = neighbour_cost;
+                *came_from.get_checked_mut(neighbour) = Some(coord);
+                queue.push(Reverse((
+                    neighbour_cost + heuristic(neighbour),
+                    neighbour_cost,
+                    neighbour.x,
+                    neighbour.y,
+                )));
+            }
+        }
+    }
+    None
+}
+
+/// A point on the trail network where trails to different destinations part ways, along with
+/// the direction to follow for each destination
+pub struct Junction {
+    pub coord: Coord,
+    pub destinations: Vec<(&'static str, CardinalDirection)>,
+}
+
+/// Finds the cells where trails leading to different destinations diverge. Each path must be
+/// listed with the name of its destination.
+pub fn junctions(paths: &[(&'static str, Vec<Coord>)]) -> Vec<Junction> {
+    let mut next_steps: BTreeMap<(i32, i32), Vec<(&'static str, CardinalDirection)>> =
+        BTreeMap::new();
+    for (name, path) in paths {
+        for window in path.windows(2) {
+            let direction = CardinalDirection::all()
+                .find(|d| window[0] + d.coord() == window[1])
+                .expect("path steps must be cardinal");
+            next_steps
+                .entry((window[0].x, window[0].y))
+                .or_default()
+                .push((*name, direction));
+        }
+    }
+    next_steps
+        .into_iter()
+        .filter(|(_, destinations)| {
+            destinations
+                .iter()
+                .any(|&(_, direction)| direction != destinations[0].1)
+        })
+        .map(|((x, y), destinations)| Junction {
+            coord: Coord::new(x, y),
+            destinations,
+        })
+        .collect()
+}
+
+fn direction_name(direction: CardinalDirection) -> &'static str {
+    match direction {
+        CardinalDirection::North => "north",
+        CardinalDirection::East => "east",
+        CardinalDirection::South => "south",
+        CardinalDirection::West => "west",
+    }
+}
+
+pub fn signpost_text(junction: &Junction) -> String {
+    let mut lines = vec!["A weathered signpost reads:".to_string()];
+    for &(name, direction) in &junction.destinations {
+        lines.push(format!("{}: {}", name, direction_name(direction)));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gridbugs::grid_2d::Size;
+
+    const SIZE: Size = Size::new_u16(7, 5);
+    const START: Coord = Coord::new(0, 2);
+    const GOAL: Coord = Coord::new(6, 2);
+
+    fn path_on_flat_ground<F: Fn(Coord) -> Option<u32>>(extra_cost: F) -> Option<Vec<Coord>> {
+        let heights = Grid::new_copy(SIZE, 0.5);
+        let on_trail = Grid::new_copy(SIZE, false);
+        find_path(START, GOAL, &heights, &on_trail, extra_cost)
+    }
+
+    fn assert_connected(path: &[Coord]) {
+        assert_eq!(path[0], START);
+        assert_eq!(path.last().unwrap().manhattan_distance(GOAL), 1);
+        for window in path.windows(2) {
+            assert_eq!(window[0].manhattan_distance(window[1]), 1);
+        }
+    }
+
+    #[test]
+    fn straight_path_on_open_ground() {
+        let path = path_on_flat_ground(|_| Some(0)).unwrap();
+        assert_connected(&path);
+        assert_eq!(path.len(), 6);
+        assert!(path.iter().all(|coord| coord.y == START.y));
+    }
+
+    #[test]
+    fn path_detours_around_impassable_cells() {
+        let blocked = Coord::new(3, 2);
+        let path = path_on_flat_ground(|coord| (coord != blocked).then_some(0)).unwrap();
+        assert_connected(&path);
+        assert!(!path.contains(&blocked));
+        // walling off the whole column leaves no path at all
+        assert!(path_on_flat_ground(|coord| (coord.x != 3).then_some(0)).is_none());
+    }
+
+    #[test]
+    fn extra_cost_steers_the_path() {
+        let path = path_on_flat_ground(|coord| {
+            Some(if coord.y == START.y && coord.x > 0 {
+                100
+            } else {
+                0
+            })
+        })
+        .unwrap();
+        assert_connected(&path);
+        assert!(path[1..].iter().all(|coord| coord.y != START.y));
+    }
+
+    #[test]
+    fn junction_where_trails_part() {
+        let lake = vec![
+            Coord::new(0, 2),
+            Coord::new(1, 2),
+            Coord::new(2, 2),
+            Coord::new(2, 1),
+            Coord::new(2, 0),
+        ];
+        let ruins = vec![
+            Coord::new(0, 2),
+            Coord::new(1, 2),
+            Coord::new(2, 2),
+            Coord::new(3, 2),
+        ];
+        let junctions = junctions(&[("lake", lake), ("ruins", ruins)]);
+        assert_eq!(junctions.len(), 1);
+        let junction = &junctions[0];
+        assert_eq!(junction.coord, Coord::new(2, 2));
+        assert_eq!(
+            junction.destinations,
+            vec![
+                ("lake", CardinalDirection::North),
+                ("ruins", CardinalDirection::East)
+            ]
+        );
+        assert_eq!(
+            signpost_text(junction),
+            "A weathered signpost reads:\nlake: north\nruins: east"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{components::Tile, world::World};
+use crate::{components::Tile, storm, world::World};
 use gridbugs::{
     entity_table::Entity,
     grid_2d::{Coord, CoordIter, Grid, GridEnumerate, Size},
@@ -12,6 +12,9 @@ const AMBIENT_COL: Rgb24 = Rgb24::new_grey(31);
 const VISION_DISTANCE_SQUARED: u32 = 400;
 pub const VISION_DISTANCE: vision_distance::Circle =
     vision_distance::Circle::new_squared(VISION_DISTANCE_SQUARED);
+pub const LOOKOUT_VISION_DISTANCE_SQUARED: u32 = 2500;
+pub const LOOKOUT_VISION_DISTANCE: vision_distance::Circle =
+    vision_distance::Circle::new_squared(LOOKOUT_VISION_DISTANCE_SQUARED);
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Light {
@@ -33,8 +36,51 @@ impl InputGrid for Visibility {
     }
 }
 
+/// Every cell is visible, lit with `light_colour` along with any lights in the world
 #[derive(Debug, Clone, Copy)]
-pub struct Omniscient;
+pub struct Omniscient {
+    pub light_colour: Rgb24,
+}
+
+impl Omniscient {
+    pub const FULL_LIGHT: Self = Self {
+        light_colour: Rgb24::new_grey(255),
+    };
+
+    /// Lights every cell the way the player's own light lights the cells right beside them
+    pub fn with_player_light(colour: Rgb24) -> Self {
+        Self {
+            light_colour: AMBIENT_COL.saturating_add(colour),
+        }
+    }
+}
+
+/// The view from the top of the lookout tower, which isn't blocked by the trees
+#[derive(Debug, Clone, Copy)]
+pub struct Lookout {
+    pub vision_distance: vision_distance::Circle,
+    /// The daylight that lights up the view
+    pub colour: Rgb24,
+}
+
+struct ElevatedVisibility;
+
+impl InputGrid for ElevatedVisibility {
+    type Grid = World;
+    type Opacity = u8;
+    fn size(&self, world: &Self::Grid) -> Size {
+        world.size()
+    }
+    fn get_opacity(&self, _world: &Self::Grid, _coord: Coord) -> Self::Opacity {
+        0
+    }
+}
+
+/// A global light source which briefly lights up the entire map
+#[derive(Debug, Clone, Copy)]
+pub struct LightningFlash {
+    pub colour: Rgb24,
+}
 
 #[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct EntityTile {
@@ -79,6 +125,38 @@ impl Default for VisibilityCell {
 }
 
 impl VisibilityCell {
+    fn update_tile_layers(&mut self, world: &World, coord: Coord) {
+        let layers = world.spatial_table.layers_at_checked(coord);
+        if let Some(entity) = layers.floor {
+            if let Some(&tile) = world.components.tile.get(entity) {
+                self.tile_layers.floor = Some(EntityTile { entity, tile });
+            }
+        } else {
+            self.tile_layers.floor = None;
+        }
+        if let Some(entity) = layers.feature {
+            if let Some(&tile) = world.components.tile.get(entity) {
+                self.tile_layers.feature = Some(EntityTile { entity, tile });
+            }
+        } else {
+            self.tile_layers.feature = None;
+        }
+        if let Some(entity) = layers.character {
+            if let Some(&tile) = world.components.tile.get(entity) {
+                self.tile_layers.character = Some(EntityTile { entity, tile });
+            }
+        } else {
+            self.tile_layers.character = None;
+        }
+        if let Some(entity) = layers.item {
+            if let Some(&tile) = world.components.tile.get(entity) {
+                self.tile_layers.item = Some(EntityTile { entity, tile });
+            }
+        } else {
+            self.tile_layers.item = None;
+        }
+    }
+
     pub(crate) fn light_colour(&self) -> Rgb24 {
         self.light_colour
     }
@@ -165,48 +243,46 @@ impl VisibilityGrid {
         world: &World,
         shadowcast_context: &mut ShadowcastContext<u8>,
         omniscient: Option<Omniscient>,
+        lightning_flash: Option<LightningFlash>,
+        lookout: Option<Lookout>,
     ) {
         self.count += 1;
         let count = self.count;
         let grid = &mut self.grid;
-        if let Some(Omniscient) = omniscient {
+        if let Some(Omniscient { light_colour }) = omniscient {
             for coord in CoordIter::new(world.size()) {
                 let cell = grid.get_checked_mut(coord);
                 cell.last_seen_next = count;
                 cell.last_seen = count;
                 cell.visible_directions = DirectionBitmap::all();
                 cell.last_lit = count;
-                cell.light_colour = Rgb24::new_grey(255);
-                let layers = world.spatial_table.layers_at_checked(coord);
-                if let Some(entity) = layers.floor {
-                    if let Some(&tile) = world.components.tile.get(entity) {
-                        cell.tile_layers.floor = Some(EntityTile { entity, tile });
-                    }
-                } else {
-                    cell.tile_layers.floor = None;
-                }
-                if let Some(entity) = layers.feature {
-                    if let Some(&tile) = world.components.tile.get(entity) {
-                        cell.tile_layers.feature = Some(EntityTile { entity, tile });
-                    }
-                } else {
-                    cell.tile_layers.feature = None;
-                }
-                if let Some(entity) = layers.character {
-                    if let Some(&tile) = world.components.tile.get(entity) {
-                        cell.tile_layers.character = Some(EntityTile { entity, tile });
-                    }
-                } else {
-                    cell.tile_layers.character = None;
-                }
-                if let Some(entity) = layers.item {
-                    if let Some(&tile) = world.components.tile.get(entity) {
-                        cell.tile_layers.item = Some(EntityTile { entity, tile });
-                    }
-                } else {
-                    cell.tile_layers.item = None;
-                }
+                cell.light_colour = light_colour;
+                cell.update_tile_layers(world, coord);
             }
+        } else if let Some(Lookout {
+            vision_distance,
+            colour,
+        }) = lookout
+        {
+            shadowcast_context.for_each_visible(
+                player_coord,
+                &ElevatedVisibility,
+                world,
+                vision_distance,
+                255,
+                |coord, visible_directions, visibility| {
+                    let cell = grid.get_checked_mut(coord);
+                    cell.last_seen_next = count;
+                    cell.visible_directions = visible_directions;
+                    cell.last_lit = count;
+                    cell.light_colour =
+                        AMBIENT_COL.saturating_add(colour.normalised_scalar_mul(visibility));
+                    if cell.light_colour.saturating_channel_total() > 31 {
+                        cell.update_tile_layers(world, coord);
+                        cell.last_seen = count;
+                    }
+                },
+            );
         } else {
             shadowcast_context.for_each_visible(
                 player_coord,
@@ -223,7 +299,38 @@ impl VisibilityGrid {
                 },
             );
         }
+        if let Some(LightningFlash { colour }) = lightning_flash {
+            // A flash of lightning lights up everything in view of the player, and gives a glimpse
+            // of how the remembered parts of the map look now
+            for (coord, cell) in grid.enumerate_mut() {
+                if cell.last_seen != 0 {
+                    cell.update_tile_layers(world, coord);
+                }
+            }
+            shadowcast_context.for_each_visible(
+                player_coord,
+                &Visibility,
+                world,
+                vision_distance::Circle::new_squared(storm::FLASH_VISION_DISTANCE_SQUARED),
+                255,
+                |coord, visible_directions, visibility| {
+                    let cell = grid.get_checked_mut(coord);
+                    cell.last_seen_next = count;
+                    cell.visible_directions = visible_directions;
+                    cell.last_lit = count;
+                    cell.light_colour = cell
+                        .light_colour
+                        .saturating_add(colour.normalised_scalar_mul(visibility));
+                    cell.update_tile_layers(world, coord);
+                    cell.last_seen = count;
+                },
+            );
+        }
         for (light_coord, light) in world.all_lights_by_coord() {
+            if omniscient.is_some() && light_coord == player_coord {
+                // the player's light is already accounted for in the omniscient light colour
+                continue;
+            }
             shadowcast_context.for_each_visible(
                 light_coord,
                 &Visibility,
@@ -243,35 +350,7 @@ impl VisibilityGrid {
                             .light_colour
                             .saturating_add(light_colour.normalised_scalar_mul(visibility));
                         if cell.light_colour.saturating_channel_total() > 31 {
-                            let layers = world.spatial_table.layers_at_checked(cell_coord);
-                            if let Some(entity) = layers.floor {
-                                if let Some(&tile) = world.components.tile.get(entity) {
-                                    cell.tile_layers.floor = Some(EntityTile { entity, tile });
-                                }
-                            } else {
-                                cell.tile_layers.floor = None;
-                            }
-                            if let Some(entity) = layers.feature {
-                                if let Some(&tile) = world.components.tile.get(entity) {
-                                    cell.tile_layers.feature = Some(EntityTile { entity, tile });
-                                }
-                            } else {
-                                cell.tile_layers.feature = None;
-                            }
-                            if let Some(entity) = layers.character {
-                                if let Some(&tile) = world.components.tile.get(entity) {
-                                    cell.tile_layers.character = Some(EntityTile { entity, tile });
-                                }
-                            } else {
-                                cell.tile_layers.character = None;
-                            }
-                            if let Some(entity) = layers.item {
-                                if let Some(&tile) = world.components.tile.get(entity) {
-                                    cell.tile_layers.item = Some(EntityTile { entity, tile });
-                                }
-                            } else {
-                                cell.tile_layers.item = None;
-                            }
+                            cell.update_tile_layers(world, cell_coord);
                             cell.last_seen = count;
                         }
                     }
@@ -279,4 +358,28 @@ impl VisibilityGrid {
             );
         }
     }
+
+    /// Moves the remembered map along with the world of the endless forest, forgetting whatever
+    /// moves off the edge
+    pub fn shift(&mut self, offset: Coord) {
+        let size = self.grid.size();
+        let old = std::mem::replace(&mut self.grid, Grid::new_default(size));
+        for (coord, cell) in old.into_enumerate() {
+            if let Some(shifted) = self.grid.get_mut(coord + offset) {
+                *shifted = cell;
+            }
+        }
+    }
+
+    /// Adds everything within a radius of a coordinate to the remembered map, without making it
+    /// currently visible
+    pub fn reveal(&mut self, world: &World, centre: Coord, distance_squared: u32) {
+        let remembered = (self.count - 1).max(1);
+        for (coord, cell) in self.grid.enumerate_mut() {
+            if cell.last_seen != self.count && (coord - centre).magnitude2() <= distance_squared {
+                cell.update_tile_layers(world, coord);
+                cell.last_seen = remembered;
+            }
+        }
+    }
 }
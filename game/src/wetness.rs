@@ -0,0 +1,141 @@
+use crate::{
+    components::{Footprint, Tile},
+    game::RainLevel,
+    world::World,
+};
+use gridbugs::{direction::CardinalDirection, entity_table::Entity, grid_2d::Coord};
+use rational::Rational;
+
+/// Upper limit on how waterlogged the ground can get
+pub const MAX_GROUND_WETNESS: u32 = 720;
+
+/// Once the ground is wetter than this, mud starts to form on low ground
+const MUD_WETNESS_THRESHOLD: u32 = 180;
+
+/// Chance each turn of the puddles growing or shrinking by a level towards the level suited to
+/// the current rain
+pub const PUDDLE_CHANGE_CHANCE: Rational = Rational {
+    numerator: 1,
+    denominator: 30,
+};
+
+pub const MAX_PUDDLE_LEVEL: u32 = 2;
+
+/// Once puddles are big enough, they spread out of the dips they form in onto neighbouring ground
+/// that isn't much higher
+const PUDDLE_SPREAD_HEIGHT: f64 = 0.01;
+
+/// How long a fresh footprint lasts, in turns of light rain
+pub const FOOTPRINT_STRENGTH: u8 = 240;
+
+/// How much the ground's wetness changes with each turn of rain
+pub fn wetness_delta(rain_level: RainLevel) -> i32 {
+    match rain_level {
+        RainLevel::Light => -1,
+        RainLevel::Medium => 1,
+        RainLevel::Heavy => 3,
+    }
+}
+
+/// The number of low ground cells (above the flood water) that are muddy at a given wetness
+pub fn num_muddy_cells(wetness: u32) -> usize {
+    wetness.saturating_sub(MUD_WETNESS_THRESHOLD) as usize
+}
+
+pub fn puddle_level(rain_level: RainLevel) -> u32 {
+    match rain_level {
+        RainLevel::Light => 0,
+        RainLevel::Medium => 1,
+        RainLevel::Heavy => 2,
+    }
+}
+
+fn footprint_fade(rain_level: RainLevel) -> u8 {
+    match rain_level {
+        RainLevel::Light => 1,
+        RainLevel::Medium => 2,
+        RainLevel::Heavy => 4,
+    }
+}
+
+impl World {
+    fn ground_height(&self, coord: Coord) -> Option<(Entity, f64)> {
+        let floor = self.spatial_table.layers_at(coord)?.floor?;
+        if !self.components.ground.contains(floor) {
+            return None;
+        }
+        Some((floor, *self.components.height.get(floor)?))
+    }
+
+    /// Fills dips in the ground with puddles. Must be called after `flood`, which resets the
+    /// tiles of any puddles that have dried up. Ditches, trails and mud are left as they are.
+    pub fn fill_puddles(&mut self, level: u32) {
+        if level == 0 {
+            return;
+        }
+        let mut puddles = Vec::new();
+        for (entity, &height) in self.components.height.iter() {
+            if !self.components.ground.contains(entity) || self.components.water.contains(entity) {
+                continue;
+            }
+            let coord = match self.spatial_table.coord_of(entity) {
+                Some(coord) => coord,
+                None => continue,
+            };
+            let neighbours = CardinalDirection::all()
+                .filter_map(|d| self.ground_height(coord + d.coord()))
+                .collect::<Vec<_>>();
+            if neighbours.iter().any(|&(_, h)| h <= height) {
+                continue;
+            }
+            puddles.push(entity);
+            if level >= MAX_PUDDLE_LEVEL {
+                for (neighbour, h) in neighbours {
+                    if h < height + PUDDLE_SPREAD_HEIGHT {
+                        puddles.push(neighbour);
+                    }
+                }
+            }
+        }
+        for entity in puddles {
+            if self.components.tile.get(entity) == Some(&Tile::Ground) {
+                self.components.tile.insert(entity, Tile::Puddle);
+            }
+        }
+    }
+
+    /// Leaves a footprint in the mud at the given coordinate, if there is mud there
+    pub fn leave_footprint(&mut self, coord: Coord, direction: CardinalDirection) {
+        if let Some(floor) = self.spatial_table.layers_at_checked(coord).floor {
+            if self.components.tile.get(floor) == Some(&Tile::Mud) {
+                self.components.footprint.insert(
+                    floor,
+                    Footprint {
+                        direction,
+                        strength: FOOTPRINT_STRENGTH,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Rain gradually washes away footprints
+    pub fn fade_footprints(&mut self, rain_level: RainLevel) {
+        let fade = footprint_fade(rain_level);
+        let mut washed_away = Vec::new();
+        for (entity, footprint) in self.components.footprint.iter_mut() {
+            footprint.strength = footprint.strength.saturating_sub(fade);
+            if footprint.strength == 0 {
+                washed_away.push(entity);
+            }
+        }
+        for entity in washed_away {
+            self.components.footprint.remove(entity);
+        }
+    }
+
+    pub fn footprint(&self, coord: Coord) -> Option<Footprint> {
+        let floor = self.spatial_table.layers_at(coord)?.floor?;
+        self.components.footprint.get(floor).cloned()
+    }
+}
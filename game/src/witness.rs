@@ -1,6 +1,11 @@
 pub use crate::game::ActionError;
-use crate::game::{self, Config, ControlFlow, TickOutput};
-use gridbugs::direction::CardinalDirection;
+use crate::{
+    chunk::Chunk,
+    game::{self, Config, ControlFlow, TickOutput},
+    lake::LakeMode,
+    terrain::GenerationFailed,
+};
+use gridbugs::{direction::CardinalDirection, grid_2d::Coord};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -53,6 +58,12 @@ impl Running {
             Some(control_flow) => match control_flow {
                 ControlFlow::Prompt(message) => self.into_prompt_witness(message),
                 ControlFlow::Sleep => Witness::Sleep(Sleep(self.0)),
+                ControlFlow::Craft => Witness::Craft(Craft(self.0)),
+                ControlFlow::EnterLake { direction, mode } => Witness::EnterLake(EnterLake {
+                    direction,
+                    mode,
+                    private: self.0,
+                }),
                 ControlFlow::Win => Witness::Win,
                 ControlFlow::GameOver => Witness::GameOver,
             },
@@ -80,6 +91,26 @@ impl Running {
         }
     }
 
+    /// Shows the whole map lit as it is at the current time of day, for previewing maps
+    pub fn reveal_lit_map(self, game: &mut Game) -> Self {
+        game.0.reveal_lit_map();
+        self
+    }
+
+    /// Takes the chunks of the endless forest that are far enough from the player to be stored
+    /// outside the game
+    pub fn take_far_chunks(self, game: &mut Game) -> (Self, Vec<Chunk>) {
+        let chunks = game.0.take_far_chunks();
+        (self, chunks)
+    }
+
+    /// Hands back a stored chunk listed by `Game::wanted_chunks`, or `None` if it couldn't be
+    /// loaded
+    pub fn restore_chunk(self, game: &mut Game, coord: Coord, chunk: Option<Chunk>) -> Self {
+        game.0.restore_chunk(coord, chunk);
+        self
+    }
+
     pub fn player_dig(
         self,
         game: &mut Game,
@@ -88,6 +119,22 @@ impl Running {
         self.handle_control_flow_result(game.0.player_dig(config))
     }
 
+    pub fn player_chop(
+        self,
+        game: &mut Game,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.handle_control_flow_result(game.0.player_chop(config))
+    }
+
+    pub fn player_fish(
+        self,
+        game: &mut Game,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.handle_control_flow_result(game.0.player_fish(config))
+    }
+
     pub fn player_toggle_pushing(
         self,
         game: &mut Game,
@@ -162,6 +209,52 @@ impl Sleep {
     }
 }
 
+/// Represents the fact that the game is waiting for the player to choose a recipe to make at the
+/// kitchen bench
+#[derive(Debug)]
+pub struct Craft(Private);
+
+impl Craft {
+    pub fn cancel(self) -> Witness {
+        Witness::Running(Running(self.0))
+    }
+
+    /// Makes the recipe at the given index into `RECIPES`
+    pub fn commit(self, game: &mut Game, index: usize, config: &Config) -> Witness {
+        match game.0.player_craft(index, config) {
+            Ok(control_flow) => Running(self.0).handle_control_flow(control_flow),
+            Err(ActionError::Message(message)) => Witness::Prompt(Prompt {
+                message,
+                private: self.0,
+            }),
+        }
+    }
+}
+
+/// Represents the fact that the game is waiting for confirmation that the player character
+/// should wade or swim into the lake
+#[derive(Debug)]
+pub struct EnterLake {
+    direction: CardinalDirection,
+    mode: LakeMode,
+    private: Private,
+}
+
+impl EnterLake {
+    pub fn message(&self) -> String {
+        game::prompts::enter_lake(self.mode)
+    }
+
+    pub fn cancel(self) -> Witness {
+        Witness::Running(Running(self.private))
+    }
+
+    pub fn commit(self, game: &mut Game, config: &Config) -> Witness {
+        let control_flow = game.0.player_enter_lake(self.direction, config);
+        Running(self.private).handle_control_flow(control_flow)
+    }
+}
+
 #[derive(Debug)]
 pub struct Prompt {
     message: String,
@@ -186,14 +279,19 @@ impl Prompt {
 pub enum Witness {
     Running(Running),
     Sleep(Sleep),
+    Craft(Craft),
+    EnterLake(EnterLake),
     Prompt(Prompt),
     GameOver,
     Win,
 }
 
-pub fn new_game<R: Rng>(config: &Config, base_rng: &mut R) -> (Game, Running) {
-    let g = game::Game::new(config, base_rng);
-    (Game(g), Running(Private))
+pub fn new_game<R: Rng>(
+    config: &Config,
+    base_rng: &mut R,
+) -> Result<(Game, Running), GenerationFailed> {
+    let g = game::Game::new(config, base_rng)?;
+    Ok((Game(g), Running(Private)))
 }
 
 /// Wraps a `Game`, and can only be constructed from a `Running`, serving as proof that the wrapped
@@ -213,8 +311,14 @@ pub struct Game(game::Game);
 mod game_interface {
     use super::Game;
     use crate::{
+        components::Footprint,
         components::Item,
+        crafting::Ingredient,
         game::{Equipped, MotivationModifier, RainLevel, RainSchedule, Time, TopographyCell},
+        level::Level,
+        lighting::MoonPhase,
+        storm::Thunder,
+        terrain::GenerationStats,
         visibility::VisibilityGrid,
     };
     use gridbugs::{
@@ -245,6 +349,28 @@ mod game_interface {
             self.0.world_size()
         }
 
+        pub fn world_origin(&self) -> Coord {
+            self.0.world_origin()
+        }
+
+        pub fn is_endless(&self) -> bool {
+            self.0.is_endless()
+        }
+
+        pub fn level(&self) -> Level {
+            self.0.level()
+        }
+
+        pub fn generation_stats(&self) -> &GenerationStats {
+            self.0.generation_stats()
+        }
+
+        /// Stored chunks of the endless forest that should be handed back with
+        /// `Running::restore_chunk`
+        pub fn wanted_chunks(&self) -> Vec<Coord> {
+            self.0.wanted_chunks()
+        }
+
         pub fn topography_grid(&self) -> Grid<TopographyCell> {
             self.0.topography_grid()
         }
@@ -261,10 +387,46 @@ mod game_interface {
             self.0.rain_level()
         }
 
+        pub fn moon_phase(&self) -> MoonPhase {
+            self.0.moon_phase()
+        }
+
+        pub fn lightning_flash_intensity(&self) -> u8 {
+            self.0.lightning_flash_intensity()
+        }
+
+        pub fn thunder(&self) -> Option<Thunder> {
+            self.0.thunder()
+        }
+
         pub fn equipped(&self) -> &Equipped {
             self.0.equipped()
         }
 
+        pub fn ingredient_count(&self, ingredient: Ingredient) -> u32 {
+            self.0.ingredient_count(ingredient)
+        }
+
+        pub fn pantry_contents(&self) -> String {
+            self.0.pantry_contents()
+        }
+
+        pub fn crafted_today(&self, index: usize) -> bool {
+            self.0.crafted_today(index)
+        }
+
+        pub fn can_craft(&self, index: usize) -> bool {
+            self.0.can_craft(index)
+        }
+
+        pub fn fish_held(&self) -> u32 {
+            self.0.fish_held()
+        }
+
+        pub fn catch_summary(&self) -> String {
+            self.0.catch_summary()
+        }
+
         pub fn player_lantern(&self) -> bool {
             self.0.player_lantern()
         }
@@ -281,6 +443,14 @@ mod game_interface {
             self.0.last_motivation_modifiers()
         }
 
+        pub fn expected_walk_time(&self, coord: Coord) -> Option<u32> {
+            self.0.expected_walk_time(coord)
+        }
+
+        pub fn footprint(&self, coord: Coord) -> Option<Footprint> {
+            self.0.footprint(coord)
+        }
+
         pub fn time(&self) -> &Time {
             self.0.time()
         }
@@ -1,22 +1,30 @@
 use crate::{
     components::{Components, DoorState, EntityData, Tile},
+    level::Level,
     realtime::RealtimeComponents,
     spatial::{Location, SpatialTable},
+    stream,
     visibility::Light,
 };
 use gridbugs::{
+    direction::CardinalDirection,
     entity_table::{Entity, EntityAllocator},
     grid_2d::{Coord, Size},
 };
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize)]
 pub struct World {
     pub entity_allocator: EntityAllocator,
     pub components: Components,
     pub realtime_components: RealtimeComponents,
+    /// The spatial table of the level the player is on
     pub spatial_table: SpatialTable,
+    pub level: Level,
+    /// Spatial tables of the other levels
+    pub levels: HashMap<Level, SpatialTable>,
 }
 
 impl World {
@@ -30,6 +38,8 @@ impl World {
             components,
             realtime_components,
             spatial_table,
+            level: Level::Ground,
+            levels: HashMap::new(),
         }
     }
 
@@ -68,6 +78,7 @@ impl World {
             if let Some(entity) = spatial_cell.floor {
                 self.components.tile.get(entity) == Some(&Tile::Floor)
                     || self.components.tile.get(entity) == Some(&Tile::Ground)
+                    || self.components.tile.get(entity) == Some(&Tile::Trail)
             } else {
                 false
             }
@@ -76,6 +87,14 @@ impl World {
         }
     }
 
+    pub fn is_lookout(&self, coord: Coord) -> bool {
+        self.spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.floor)
+            .map(|floor| self.components.lookout.contains(floor))
+            .unwrap_or(false)
+    }
+
     pub fn is_wall_at_coord(&self, coord: Coord) -> bool {
         if let Some(spatial_cell) = self.spatial_table.layers_at(coord) {
             if let Some(entity) = spatial_cell.feature {
@@ -97,12 +116,18 @@ impl World {
     pub fn should_hide_rain(&self, coord: Coord) -> bool {
         if let Some(spatial_cell) = self.spatial_table.layers_at(coord) {
             if let Some(entity) = spatial_cell.floor {
+                if self.components.cellar.contains(entity) {
+                    return true;
+                }
                 let is_floor = self.components.tile.get(entity) == Some(&Tile::Floor);
                 let ground_below = if let Some(spatial_cell) =
                     self.spatial_table.layers_at(coord + Coord { x: 0, y: 1 })
                 {
                     if let Some(entity) = spatial_cell.floor {
-                        self.components.tile.get(entity) == Some(&Tile::Ground)
+                        matches!(
+                            self.components.tile.get(entity),
+                            Some(&Tile::Ground) | Some(&Tile::Trail)
+                        )
                     } else {
                         false
                     }
@@ -154,6 +179,8 @@ impl World {
         self.components.tile.insert(door, Tile::DoorClosed(axis));
     }
 
+    const FALLEN_TREE_LENGTH: i32 = 2;
+
     pub fn flatten_grass(&mut self, grass: Entity) -> bool {
         self.components.opacity.remove(grass);
         if let Some(tile) = self.components.tile.insert(grass, Tile::FlatGrass) {
@@ -183,7 +210,10 @@ impl World {
         }
     }
 
-    pub fn flood<R: Rng>(&mut self, n: usize, rng: &mut R) {
+    /// Floods the `n` lowest ground cells, and turns the next `num_mud` lowest cells to mud
+    pub fn flood<R: Rng>(&mut self, n: usize, num_mud: usize, rng: &mut R) {
+        let drained = self.drained_ditches();
+        let n = n.saturating_sub(drained.len() * stream::DITCH_DRAINAGE);
         let mut entities_by_height = self
             .components
             .height
@@ -192,23 +222,36 @@ impl World {
             .map(|(a, &b)| (a, b))
             .collect::<Vec<_>>();
         entities_by_height.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
-        for &(entity, _) in entities_by_height.iter().take(n) {
-            self.become_water(entity, rng);
-        }
-        for &(entity, _) in entities_by_height.iter().skip(n) {
+        let mut num_flooded = 0;
+        let mut num_dry = 0;
+        for (entity, _) in entities_by_height {
+            // drained ditches never flood, but still dry out if they were flooded before draining
+            if num_flooded < n && !drained.contains(&entity) {
+                self.become_water(entity, rng);
+                num_flooded += 1;
+                continue;
+            }
             let tile = if self.components.ditch.contains(entity) {
                 Tile::Ditch
+            } else if self.components.mud.contains(entity) || num_dry < num_mud {
+                Tile::Mud
+            } else if self.components.trail.contains(entity) {
+                Tile::Trail
             } else {
                 Tile::Ground
             };
+            num_dry += 1;
             self.components.tile.insert(entity, tile);
             self.components.realtime.remove(entity);
+            // erosion can lift a flooded cell back above the water line
+            self.components.water.remove(entity);
         }
     }
 
     pub fn dig(&mut self, coord: Coord) {
         if let Some(floor) = self.spatial_table.layers_at_checked(coord).floor {
             if self.components.ground.contains(floor) {
+                self.components.mud.remove(floor);
                 self.components.ditch.insert(floor, ());
                 if let Some(height) = self.components.height.get_mut(floor) {
                     *height -= 0.1;
@@ -216,4 +259,48 @@ impl World {
             }
         }
     }
+
+    /// Replaces a tree with a log lying on the ground in the given direction. The log covers the
+    /// cell where the tree stood and extends over any clear ground it falls onto.
+    pub fn fell_tree(&mut self, tree: Entity, direction: CardinalDirection) {
+        let coord = match self.spatial_table.coord_of(tree) {
+            Some(coord) => coord,
+            None => return,
+        };
+        self.spatial_table.remove(tree);
+        self.components.remove_entity(tree);
+        self.spawn_log(coord, direction.axis());
+        for i in 1..=Self::FALLEN_TREE_LENGTH {
+            let coord = coord + direction.coord() * i;
+            if let Some(layers) = self.spatial_table.layers_at(coord) {
+                let on_ground = layers
+                    .floor
+                    .map(|floor| self.components.ground.contains(floor))
+                    .unwrap_or(false);
+                if layers.feature.is_some() || layers.character.is_some() || !on_ground {
+                    break;
+                }
+                self.spawn_log(coord, direction.axis());
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Removes a fallen log, leaving firewood in its place if there's room. Returns true if
+    /// firewood was left.
+    pub fn chop_log(&mut self, log: Entity) -> bool {
+        let coord = match self.spatial_table.coord_of(log) {
+            Some(coord) => coord,
+            None => return false,
+        };
+        self.spatial_table.remove(log);
+        self.components.remove_entity(log);
+        if self.spatial_table.layers_at_checked(coord).item.is_none() {
+            self.spawn_firewood(coord);
+            true
+        } else {
+            false
+        }
+    }
 }
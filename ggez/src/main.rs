@@ -12,6 +12,8 @@ fn main() {
         initial_rng_seed,
         omniscient,
         new_game,
+        terrain_config,
+        maps,
     } = NativeCommon::parser()
         .with_help_default()
         .parse_env_or_exit();
@@ -42,5 +44,7 @@ fn main() {
         initial_rng_seed,
         omniscient,
         new_game,
+        terrain_config,
+        maps,
     }));
 }
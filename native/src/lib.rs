@@ -1,37 +1,98 @@
 use gridbugs::storage::{FileStorage, IfDirectoryMissing, Storage};
 
 pub use meap;
-use rainforest_app::{AppStorage, InitialRngSeed};
-use std::path::PathBuf;
+use rainforest_app::{AppStorage, CustomMap, InitialRngSeed, TerrainConfig};
+use std::{fs, path::PathBuf};
 
 const DEFAULT_SAVE_FILE: &str = "save";
 const DEFAULT_NEXT_TO_EXE_STORAGE_DIR: &str = "save";
 const DEFAULT_CONTROLS_FILE: &str = "controls.json";
+const DEFAULT_EDITOR_MAP_FILE: &str = "map.txt";
+const DEFAULT_DAILY_HISTORY_FILE: &str = "daily.json";
 
 pub struct NativeCommon {
     pub storage: AppStorage,
     pub initial_rng_seed: InitialRngSeed,
     pub omniscient: bool,
     pub new_game: bool,
+    pub terrain_config: TerrainConfig,
+    pub maps: Vec<CustomMap>,
+}
+
+fn load_custom_map(path: &str) -> Result<CustomMap, String> {
+    let text =
+        fs::read_to_string(path).map_err(|e| format!("couldn't read map {}: {}", path, e))?;
+    CustomMap::parse(&text).map_err(|e| format!("invalid map {}: {}", path, e))
+}
+
+/// Reads the terrain config from a JSON file if one is given, then applies any overrides of the
+/// form KEY=VALUE, where VALUE is a JSON value
+fn load_terrain_config(
+    path: Option<String>,
+    overrides: Vec<String>,
+) -> Result<TerrainConfig, String> {
+    let mut json = match path {
+        Some(path) => {
+            let text = fs::read_to_string(&path)
+                .map_err(|e| format!("couldn't read terrain config {}: {}", path, e))?;
+            serde_json::from_str::<serde_json::Value>(&text)
+                .map_err(|e| format!("couldn't parse terrain config {}: {}", path, e))?
+        }
+        None => serde_json::Value::Object(Default::default()),
+    };
+    for terrain_override in overrides {
+        let (key, value) = terrain_override
+            .split_once('=')
+            .ok_or_else(|| format!("expected KEY=VALUE but got {}", terrain_override))?;
+        let value =
+            serde_json::from_str(value).map_err(|e| format!("invalid value for {}: {}", key, e))?;
+        json.as_object_mut()
+            .ok_or("terrain config must be a JSON object")?
+            .insert(key.to_string(), value);
+    }
+    let config: TerrainConfig =
+        serde_json::from_value(json).map_err(|e| format!("invalid terrain config: {}", e))?;
+    config
+        .validate()
+        .map_err(|e| format!("invalid terrain config: {}", e))?;
+    Ok(config)
 }
 
 impl NativeCommon {
     pub fn parser() -> impl meap::Parser<Item = Self> {
         meap::let_map! {
             let {
-                rng_seed = opt_opt::<u64, _>("INT", 'r').name("rng-seed").desc("rng seed to use for first new game");
+                rng_seed = opt_opt::<String, _>("SEED", 'r').name("rng-seed")
+                    .desc("rng seed or seed code (e.g. moss-heron-cedar) to use for first new game");
                 save_file = opt_opt("PATH", 's').name("save-file").desc("save file")
                     .with_default(DEFAULT_SAVE_FILE.to_string());
                 controls_file = opt_opt("PATH", "controls-file").desc("controls file")
                     .with_default(DEFAULT_CONTROLS_FILE.to_string());
+                editor_map_file = opt_opt("PATH", "editor-map-file").desc("file the map editor saves to")
+                    .with_default(DEFAULT_EDITOR_MAP_FILE.to_string());
+                daily_history_file = opt_opt("PATH", "daily-history-file")
+                    .desc("file that keeps the results of daily forests")
+                    .with_default(DEFAULT_DAILY_HISTORY_FILE.to_string());
                 storage_dir = opt_opt("PATH", 'd').name("storage-dir")
                     .desc("directory that will contain state")
                     .with_default(DEFAULT_NEXT_TO_EXE_STORAGE_DIR.to_string());
                 delete_save = flag("delete-save").desc("delete save game file");
                 new_game = flag("new-game").desc("start a new game, skipping the menu");
                 omniscient = flag("omniscient").desc("enable omniscience");
+                terrain_config_file = opt_opt::<String, _>("PATH", "terrain-config")
+                    .desc("JSON file of world generation parameters");
+                terrain_overrides = opt_multi::<String, _>("KEY=VALUE", "terrain")
+                    .desc("override a world generation parameter, e.g. --terrain width=60");
+                map_files = opt_multi::<String, _>("PATH", "map")
+                    .desc("hand-authored map to offer on the main menu (may be repeated)");
             } in {{
-                let initial_rng_seed = rng_seed.map(InitialRngSeed::U64).unwrap_or(InitialRngSeed::Random);
+                let initial_rng_seed = match rng_seed {
+                    None => InitialRngSeed::Random,
+                    Some(code) => InitialRngSeed::from_code(&code).unwrap_or_else(|| {
+                        eprintln!("invalid seed code: {}", code);
+                        std::process::exit(1);
+                    }),
+                };
                 let mut file_storage = Storage::new(
                     match FileStorage::next_to_exe(&storage_dir, IfDirectoryMissing::Create) {
                         Ok(fs) => fs,
@@ -48,16 +109,33 @@ impl NativeCommon {
                         log::warn!("couldn't find save file to delete");
                     }
                 }
+                let terrain_config = load_terrain_config(terrain_config_file, terrain_overrides)
+                    .unwrap_or_else(|message| {
+                        eprintln!("{}", message);
+                        std::process::exit(1);
+                    });
+                let maps = map_files
+                    .iter()
+                    .map(|path| load_custom_map(path))
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap_or_else(|message| {
+                        eprintln!("{}", message);
+                        std::process::exit(1);
+                    });
                 let storage = AppStorage {
                     handle: file_storage,
                     save_game_key: save_file,
                     controls_key: controls_file,
+                    editor_map_key: editor_map_file,
+                    daily_history_key: daily_history_file,
                 };
                 Self {
                     initial_rng_seed,
                     storage,
                     omniscient,
                     new_game,
+                    terrain_config,
+                    maps,
                 }
             }}
         }
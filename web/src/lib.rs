@@ -2,11 +2,29 @@ use gridbugs::{
     chargrid_web::{Context, Size},
     storage::{LocalStorage, Storage},
 };
-use rainforest_app::{app, AppArgs, AppStorage, InitialRngSeed};
+use rainforest_app::{app, AppArgs, AppStorage, InitialRngSeed, TerrainConfig};
 use wasm_bindgen::prelude::*;
 
 const SAVE_KEY: &str = "save";
 const CONTROLS_KEY: &str = "controls";
+const EDITOR_MAP_KEY: &str = "editor_map";
+const DAILY_HISTORY_KEY: &str = "daily_history";
+const SEED_QUERY_PARAM: &str = "seed";
+
+/// Reads the seed code from the page's `?seed=` query parameter, if any
+fn initial_rng_seed() -> InitialRngSeed {
+    let code = web_sys::window()
+        .and_then(|window| window.location().search().ok())
+        .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok())
+        .and_then(|params| params.get(SEED_QUERY_PARAM));
+    match code {
+        None => InitialRngSeed::Random,
+        Some(code) => InitialRngSeed::from_code(&code).unwrap_or_else(|| {
+            log::warn!("ignoring invalid seed code: {}", code);
+            InitialRngSeed::Random
+        }),
+    }
+}
 
 #[wasm_bindgen(start)]
 pub fn run() -> Result<(), JsValue> {
@@ -19,10 +37,14 @@ pub fn run() -> Result<(), JsValue> {
             handle: storage,
             save_game_key: SAVE_KEY.to_string(),
             controls_key: CONTROLS_KEY.to_string(),
+            editor_map_key: EDITOR_MAP_KEY.to_string(),
+            daily_history_key: DAILY_HISTORY_KEY.to_string(),
         },
-        initial_rng_seed: InitialRngSeed::Random,
+        initial_rng_seed: initial_rng_seed(),
         omniscient: false,
         new_game: false,
+        terrain_config: TerrainConfig::default(),
+        maps: Vec::new(),
     };
     context.run(app(args));
     Ok(())
@@ -32,6 +32,8 @@ fn main() {
                 initial_rng_seed,
                 omniscient,
                 new_game,
+                terrain_config,
+                maps,
             },
         force_opengl,
     } = Args::parser().with_help_default().parse_env_or_exit();
@@ -63,5 +65,7 @@ fn main() {
         initial_rng_seed,
         omniscient,
         new_game,
+        terrain_config,
+        maps,
     }));
 }